@@ -1,4 +1,3 @@
-use parsercher;
 use parsercher::dom::Tag;
 
 fn main() {
@@ -9,9 +8,9 @@ fn main() {
     q.set_attr("id", "q");
     q.set_attr("class", "target");
 
-    assert_eq!(Tag::p_implies_q(&p, &q), true);
+    assert!(Tag::p_implies_q(&p, &q));
 
     let mut q = Tag::new("h1");
     q.set_attr("id", "q");
-    assert_eq!(Tag::p_implies_q(&p, &q), false);
+    assert!(!Tag::p_implies_q(&p, &q));
 }
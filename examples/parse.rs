@@ -1,5 +1,3 @@
-use parsercher;
-
 fn main() {
     let html = r#"
 <!DOCTYPE html>
@@ -27,7 +25,7 @@ fn main() {
   </body>
 </html>
 "#;
-    if let Ok(dom) = parsercher::parse(&html) {
+    if let Ok(dom) = parsercher::parse(html) {
         println!("{:#?}", dom);
     }
 }
@@ -1,5 +1,3 @@
-use parsercher;
-
 fn main() {
     let html = r#"
 <head>
@@ -10,7 +8,7 @@ fn main() {
     <h1>Hello, world!</h1>
 </body>
 "#;
-    if let Ok(dom) = parsercher::parse(&html) {
+    if let Ok(dom) = parsercher::parse(html) {
         parsercher::print_dom_tree(&dom);
     }
 }
@@ -1,5 +1,3 @@
-use parsercher;
-
 fn main() {
     let html = r#"
     <!DOCTYPE html>
@@ -23,7 +21,7 @@ fn main() {
     </html>
     "#;
 
-    let dom = parsercher::parse(&html).unwrap();
+    let dom = parsercher::parse(html).unwrap();
 
     let attrs = vec!["id", "class"];
     let values = parsercher::search_attrs(&dom, &attrs).unwrap();
@@ -1,5 +1,3 @@
-use parsercher;
-
 fn main() {
     let html = r#"
 <!DOCTYPE html>
@@ -41,7 +39,7 @@ fn main() {
 </html>
 "#;
 
-    let root_dom = parsercher::parse(&html).unwrap();
+    let root_dom = parsercher::parse(html).unwrap();
 
     let needle = r#"
 <ul class="targetList">
@@ -49,11 +47,11 @@ fn main() {
   <li class="key2"></li>
 </ul>
 "#;
-    let needle_dom = parsercher::parse(&needle).unwrap();
+    let needle_dom = parsercher::parse(needle).unwrap();
     // Remove `root`dom of needle_dom
-    let needle_dom = needle_dom.get_children().unwrap().get(0).unwrap();
+    let needle_dom = needle_dom.get_children().unwrap().first().unwrap();
 
-    if let Some(dom) = parsercher::search_dom(&root_dom, &needle_dom) {
+    if let Some(dom) = parsercher::search_dom(&root_dom, needle_dom) {
         parsercher::print_dom_tree(&dom);
     }
 }
@@ -1,4 +1,3 @@
-use parsercher;
 use parsercher::dom::Tag;
 
 fn main() {
@@ -10,7 +9,7 @@ fn main() {
 </ol>
 "#;
 
-    if let Ok(dom) = parsercher::parse(&doc) {
+    if let Ok(dom) = parsercher::parse(doc) {
         let mut needle = Tag::new("li");
         needle.set_attr("class", "target");
         if let Some(tags) = parsercher::search_tag(&dom, &needle) {
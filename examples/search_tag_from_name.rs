@@ -1,5 +1,3 @@
-use parsercher;
-
 fn main() {
     let doc = r#"
 <body>
@@ -9,7 +7,7 @@ fn main() {
 </body>
 "#;
 
-    if let Ok(dom) = parsercher::parse(&doc) {
+    if let Ok(dom) = parsercher::parse(doc) {
         if let Some(tags) = parsercher::search_tag_from_name(&dom, "h2") {
             println!("{:#?}", tags);
         }
@@ -1,4 +1,4 @@
-use parsercher;
+use parsercher::{ParseMode, ParseOptions};
 
 fn main() {
     let xml = r#"
@@ -20,7 +20,11 @@ fn main() {
   </Items>
 </Order>
 "#;
-    if let Ok(dom) = parsercher::parse(&xml) {
+    let options = ParseOptions {
+        mode: ParseMode::Xml,
+        ..Default::default()
+    };
+    if let Ok((dom, _)) = parsercher::parse_with(xml, options) {
         parsercher::print_dom_tree(&dom);
     }
 }
@@ -0,0 +1,318 @@
+//! Module of Attrs structure.
+
+use std::iter::FromIterator;
+
+use crate::dom::namespace;
+
+/// An attribute of a tag.
+#[derive(Debug, Clone)]
+struct Attr {
+    name: String,
+    raw_name: Option<String>,
+    namespace_uri: Option<String>,
+    value: String,
+    raw_value: Option<String>,
+}
+
+/// A structure that represents the attributes of a tag.
+///
+/// The attributes are kept in the order they were added (source order when parsed).
+/// Duplicate attributes are also kept. `get()` returns the first one as in HTML,
+/// `get_all()` returns all of them.
+///
+/// The raw names and values and the namespace URIs are not compared by `==`. The order of the attributes is compared.
+#[derive(Debug, Clone, Default)]
+pub struct Attrs {
+    attrs: Vec<Attr>,
+}
+
+impl PartialEq for Attrs {
+    fn eq(&self, other: &Attrs) -> bool {
+        self.attrs.len() == other.attrs.len()
+            && self
+                .attrs
+                .iter()
+                .zip(other.attrs.iter())
+                .all(|(a, b)| a.name == b.name && a.value == b.value)
+    }
+}
+
+impl Attrs {
+    /// Create new empty Attrs structure.
+    pub fn new() -> Attrs {
+        Attrs { attrs: Vec::new() }
+    }
+
+    /// Returns the number of attributes including duplicates.
+    pub fn len(&self) -> usize {
+        self.attrs.len()
+    }
+
+    /// Returns true if there is no attribute.
+    pub fn is_empty(&self) -> bool {
+        self.attrs.is_empty()
+    }
+
+    /// Returns true if the attribute exists.
+    pub fn contains_key(&self, name: &str) -> bool {
+        self.attrs.iter().any(|attr| attr.name == name)
+    }
+
+    /// Returns the value of the first attribute with the specified name.
+    ///
+    /// # Examples
+    /// ```
+    /// use parsercher::dom::Attrs;
+    ///
+    /// let mut attrs = Attrs::new();
+    /// attrs.push("class", "first");
+    /// attrs.push("class", "second");
+    ///
+    /// assert_eq!(attrs.get("class"), Some("first"));
+    /// assert_eq!(attrs.get("id"), None);
+    /// ```
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|attr| attr.name == name)
+            .map(|attr| attr.value.as_str())
+    }
+
+    /// Returns the values of all attributes with the specified name in order.
+    ///
+    /// # Examples
+    /// ```
+    /// use parsercher::dom::Attrs;
+    ///
+    /// let mut attrs = Attrs::new();
+    /// attrs.push("class", "first");
+    /// attrs.push("id", "id1");
+    /// attrs.push("class", "second");
+    ///
+    /// assert_eq!(attrs.get_all("class"), vec!["first", "second"]);
+    /// ```
+    pub fn get_all(&self, name: &str) -> Vec<&str> {
+        self.attrs
+            .iter()
+            .filter(|attr| attr.name == name)
+            .map(|attr| attr.value.as_str())
+            .collect()
+    }
+
+    /// Returns the value of the first attribute with the specified name
+    /// as written in the source document.
+    /// If the raw value is not set, returns the same as `get()`.
+    pub fn get_raw(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|attr| attr.name == name)
+            .map(|attr| attr.raw_value.as_ref().unwrap_or(&attr.value).as_str())
+    }
+
+    /// Returns the name of the first attribute with the specified name
+    /// as written in the source document.
+    /// If the raw name is not set, returns the same as `name`.
+    ///
+    /// # Examples
+    /// ```
+    /// let dom = parsercher::parse(r#"<div DATA-Id="1"></div>"#).unwrap();
+    /// let div = dom.get_children().unwrap()[0].get_tag().unwrap();
+    ///
+    /// let attrs = div.get_attrs().unwrap();
+    /// assert_eq!(attrs.get("data-id"), Some("1"));
+    /// assert_eq!(attrs.get_raw_name("data-id"), Some("DATA-Id"));
+    /// ```
+    pub fn get_raw_name(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|attr| attr.name == name)
+            .map(|attr| attr.raw_name.as_ref().unwrap_or(&attr.name).as_str())
+    }
+
+    /// Returns the namespace URI of the first attribute with the specified name.
+    /// Unprefixed attributes have no namespace except `xmlns`.
+    ///
+    /// # Examples
+    /// ```
+    /// let xml = r#"<svg xmlns:xlink="http://www.w3.org/1999/xlink"><use xlink:href="/a.svg"/></svg>"#;
+    /// let dom = parsercher::parse(&xml).unwrap();
+    /// let svg = dom.get_children().unwrap()[0].as_ref();
+    /// let use_tag = svg.get_children().unwrap()[0].get_tag().unwrap();
+    ///
+    /// let attrs = use_tag.get_attrs().unwrap();
+    /// assert_eq!(attrs.get_namespace_uri("xlink:href"), Some("http://www.w3.org/1999/xlink"));
+    /// assert_eq!(attrs.get_ns("http://www.w3.org/1999/xlink", "href"), Some("/a.svg"));
+    /// ```
+    pub fn get_namespace_uri(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|attr| attr.name == name)
+            .and_then(|attr| attr.namespace_uri.as_deref())
+    }
+
+    /// Set the namespace URI of all attributes with the specified name.
+    pub fn set_namespace_uri(&mut self, name: &str, namespace_uri: &str) {
+        for attr in self.attrs.iter_mut().filter(|attr| attr.name == name) {
+            attr.namespace_uri = Some(String::from(namespace_uri));
+        }
+    }
+
+    /// Returns the value of the first attribute with the namespace URI and the local name
+    /// regardless of the prefix.
+    pub fn get_ns(&self, namespace_uri: &str, local_name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|attr| {
+                attr.namespace_uri.as_deref() == Some(namespace_uri)
+                    && namespace::split_name(&attr.name).1 == local_name
+            })
+            .map(|attr| attr.value.as_str())
+    }
+
+    /// Set the value of the attribute.
+    /// If the attribute exists, the first one is replaced and the duplicates are removed.
+    /// Otherwise the attribute is added to the end.
+    ///
+    /// # Examples
+    /// ```
+    /// use parsercher::dom::Attrs;
+    ///
+    /// let mut attrs = Attrs::new();
+    /// attrs.push("class", "first");
+    /// attrs.push("id", "id1");
+    /// attrs.push("class", "second");
+    /// attrs.insert("class", "third");
+    ///
+    /// let names: Vec<&str> = attrs.iter().map(|(name, _)| name).collect();
+    /// assert_eq!(names, vec!["class", "id"]);
+    /// assert_eq!(attrs.get_all("class"), vec!["third"]);
+    /// ```
+    pub fn insert(&mut self, name: &str, value: &str) {
+        match self.attrs.iter_mut().find(|attr| attr.name == name) {
+            Some(attr) => {
+                attr.value = String::from(value);
+                attr.raw_value = None;
+            }
+            None => {
+                self.push(name, value);
+                return;
+            }
+        }
+
+        // remove the duplicates after the first one
+        let mut first = true;
+        self.attrs.retain(|attr| {
+            if attr.name != name {
+                return true;
+            }
+            let keep = first;
+            first = false;
+            keep
+        });
+    }
+
+    /// Add the attribute to the end even if the attribute already exists.
+    pub fn push(&mut self, name: &str, value: &str) {
+        self.attrs.push(Attr {
+            name: String::from(name),
+            raw_name: None,
+            namespace_uri: None,
+            value: String::from(value),
+            raw_value: None,
+        });
+    }
+
+    /// Add the attribute with the value as written in the source document.
+    pub(crate) fn push_raw(&mut self, name: &str, value: &str, raw_value: &str) {
+        self.attrs.push(Attr {
+            name: String::from(name),
+            raw_name: None,
+            namespace_uri: None,
+            value: String::from(value),
+            raw_value: Some(String::from(raw_value)),
+        });
+    }
+
+    /// Set the raw name of the last attribute.
+    pub(crate) fn set_last_raw_name(&mut self, raw_name: &str) {
+        if let Some(attr) = self.attrs.last_mut() {
+            attr.raw_name = Some(String::from(raw_name));
+        }
+    }
+
+    /// Set the raw value of the first attribute with the specified name.
+    pub(crate) fn set_raw(&mut self, name: &str, raw_value: &str) {
+        if let Some(attr) = self.attrs.iter_mut().find(|attr| attr.name == name) {
+            attr.raw_value = Some(String::from(raw_value));
+        }
+    }
+
+    /// Remove all attributes with the specified name.
+    /// Returns the value of the first one.
+    pub fn remove(&mut self, name: &str) -> Option<String> {
+        let value = self.get(name).map(String::from);
+        self.attrs.retain(|attr| attr.name != name);
+        value
+    }
+
+    /// Returns an iterator over the attribute names and values in order.
+    /// Duplicate attributes are also returned.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.attrs
+            .iter()
+            .map(|attr| (attr.name.as_str(), attr.value.as_str()))
+    }
+}
+
+impl<K: AsRef<str>, V: AsRef<str>> FromIterator<(K, V)> for Attrs {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Attrs {
+        let mut attrs = Attrs::new();
+        for (name, value) in iter {
+            attrs.push(name.as_ref(), value.as_ref());
+        }
+        attrs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn order_test() {
+        let attrs: Attrs = vec![("b", "1"), ("a", "2"), ("c", "3")]
+            .into_iter()
+            .collect();
+        let pairs: Vec<(&str, &str)> = attrs.iter().collect();
+        assert_eq!(pairs, vec![("b", "1"), ("a", "2"), ("c", "3")]);
+    }
+
+    #[test]
+    fn duplicate_test() {
+        let mut attrs = Attrs::new();
+        attrs.push("a", "1");
+        attrs.push("b", "2");
+        attrs.push("a", "3");
+        assert_eq!(attrs.len(), 3);
+        assert_eq!(attrs.get("a"), Some("1"));
+        assert_eq!(attrs.get_all("a"), vec!["1", "3"]);
+
+        attrs.insert("a", "4");
+        let pairs: Vec<(&str, &str)> = attrs.iter().collect();
+        assert_eq!(pairs, vec![("a", "4"), ("b", "2")]);
+
+        assert_eq!(attrs.remove("a"), Some("4".to_string()));
+        assert!(!attrs.contains_key("a"));
+    }
+
+    #[test]
+    fn raw_is_not_compared() {
+        let mut a = Attrs::new();
+        a.push_raw("href", "?a=1&b=2", "?a=1&amp;b=2");
+        let mut b = Attrs::new();
+        b.push("href", "?a=1&b=2");
+        assert!(a == b);
+        assert_eq!(a.get_raw("href"), Some("?a=1&amp;b=2"));
+        assert_eq!(b.get_raw("href"), Some("?a=1&b=2"));
+    }
+}
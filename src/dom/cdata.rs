@@ -0,0 +1,37 @@
+//! Module of CData structure.
+
+use std::fmt;
+
+/// A structure that represents a CDATA section.
+/// grammar: `<![CDATA[ data ]]>`
+///
+/// The data is not decoded and may contain `<` and `&`.
+/// `Display` writes it as a CDATA section.
+#[derive(Debug, PartialEq, Clone)]
+pub struct CData {
+    data: String,
+}
+
+impl CData {
+    /// Create new CData structure.
+    ///
+    /// # Arguments
+    /// * `data` - If `<![CDATA[a < b]]>`, then `a < b`.
+    pub fn new(data: &str) -> CData {
+        CData {
+            data: String::from(data),
+        }
+    }
+
+    /// Returns the data.
+    /// If `<![CDATA[a < b]]>`, then returns `a < b`.
+    pub fn get_data(&self) -> &str {
+        &self.data
+    }
+}
+
+impl fmt::Display for CData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<![CDATA[{}]]>", self.data)
+    }
+}
@@ -33,15 +33,15 @@ mod tests {
     fn eq_test() {
         let a = Comment::new("a comment");
         let b = Comment::new("a comment");
-        assert_eq!(a == b, true);
-        assert_eq!(a != b, false);
+        assert!(a == b);
+        assert!(!(a != b));
     }
 
     #[test]
     fn ne_test() {
         let a = Comment::new("a comment");
         let b = Comment::new("b comment");
-        assert_eq!(a != b, true);
-        assert_eq!(a == b, false);
+        assert!(a != b);
+        assert!(!(a == b));
     }
 }
@@ -0,0 +1,140 @@
+//! Module of Doctype structure.
+
+use std::fmt;
+
+/// A structure that represents a document type declaration.
+/// grammar: `<!DOCTYPE name [PUBLIC "public_id" ["system_id"] | SYSTEM "system_id"] [[internal_subset]]>`
+///
+/// `Display` writes it as a declaration.
+///
+/// # Examples
+/// ```rust
+/// use parsercher::dom::Doctype;
+///
+/// let mut doctype = Doctype::new("html");
+/// assert_eq!(doctype.to_string(), "<!DOCTYPE html>");
+///
+/// doctype.set_public_id("-//W3C//DTD HTML 4.01//EN");
+/// doctype.set_system_id("http://www.w3.org/TR/html4/strict.dtd");
+/// assert_eq!(
+///     doctype.to_string(),
+///     r#"<!DOCTYPE html PUBLIC "-//W3C//DTD HTML 4.01//EN" "http://www.w3.org/TR/html4/strict.dtd">"#
+/// );
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+pub struct Doctype {
+    name: String,
+    public_id: Option<String>,
+    system_id: Option<String>,
+    internal_subset: Option<String>,
+}
+
+impl Doctype {
+    /// Create new Doctype structure.
+    ///
+    /// # Arguments
+    /// * `name` - If `<!DOCTYPE html>`, then `html`.
+    pub fn new(name: &str) -> Doctype {
+        Doctype {
+            name: String::from(name),
+            public_id: None,
+            system_id: None,
+            internal_subset: None,
+        }
+    }
+
+    /// Returns the name of the root element.
+    /// If `<!DOCTYPE html>`, then returns `html`.
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Set the public identifier.
+    pub fn set_public_id(&mut self, public_id: &str) {
+        self.public_id = Some(String::from(public_id));
+    }
+
+    /// Returns the public identifier.
+    /// If `<!DOCTYPE html PUBLIC "-//W3C//DTD XHTML 1.0 Strict//EN" "...">`,
+    /// then returns `-//W3C//DTD XHTML 1.0 Strict//EN`.
+    pub fn get_public_id(&self) -> Option<&str> {
+        self.public_id.as_deref()
+    }
+
+    /// Set the system identifier.
+    pub fn set_system_id(&mut self, system_id: &str) {
+        self.system_id = Some(String::from(system_id));
+    }
+
+    /// Returns the system identifier.
+    /// If `<!DOCTYPE note SYSTEM "note.dtd">`, then returns `note.dtd`.
+    pub fn get_system_id(&self) -> Option<&str> {
+        self.system_id.as_deref()
+    }
+
+    /// Set the internal subset of the XML document type declaration.
+    pub fn set_internal_subset(&mut self, internal_subset: &str) {
+        self.internal_subset = Some(String::from(internal_subset));
+    }
+
+    /// Returns the internal subset of the XML document type declaration.
+    /// If `<!DOCTYPE note [<!ELEMENT note (#PCDATA)>]>`, then returns `<!ELEMENT note (#PCDATA)>`.
+    pub fn get_internal_subset(&self) -> Option<&str> {
+        self.internal_subset.as_deref()
+    }
+}
+
+/// Writes the identifier in double quotes, or in single quotes if it contains a double quote.
+fn write_id(f: &mut fmt::Formatter<'_>, id: &str) -> fmt::Result {
+    if id.contains('"') {
+        write!(f, " '{}'", id)
+    } else {
+        write!(f, " \"{}\"", id)
+    }
+}
+
+impl fmt::Display for Doctype {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<!DOCTYPE {}", self.name)?;
+        match (&self.public_id, &self.system_id) {
+            (Some(public_id), system_id) => {
+                write!(f, " PUBLIC")?;
+                write_id(f, public_id)?;
+                if let Some(system_id) = system_id {
+                    write_id(f, system_id)?;
+                }
+            }
+            (None, Some(system_id)) => {
+                write!(f, " SYSTEM")?;
+                write_id(f, system_id)?;
+            }
+            (None, None) => (),
+        }
+        if let Some(internal_subset) = &self.internal_subset {
+            write!(f, " [{}]", internal_subset)?;
+        }
+        write!(f, ">")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_test() {
+        let mut doctype = Doctype::new("note");
+        doctype.set_system_id("note.dtd");
+        assert_eq!(doctype.to_string(), r#"<!DOCTYPE note SYSTEM "note.dtd">"#);
+
+        doctype.set_internal_subset("<!ELEMENT note (#PCDATA)>");
+        assert_eq!(
+            doctype.to_string(),
+            r#"<!DOCTYPE note SYSTEM "note.dtd" [<!ELEMENT note (#PCDATA)>]>"#
+        );
+
+        let mut doctype = Doctype::new("html");
+        doctype.set_public_id("say \"hi\"");
+        assert_eq!(doctype.to_string(), r#"<!DOCTYPE html PUBLIC 'say "hi"'>"#);
+    }
+}
@@ -0,0 +1,22 @@
+//! Module of the HTML element categories used by the parser and the serializer.
+
+/// Elements that have no end tag in HTML.
+pub(crate) const VOID_ELEMENTS: [&str; 13] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+/// Elements whose content is text written without escaping.
+pub(crate) const RAW_TEXT_ELEMENTS: [&str; 8] = [
+    "script",
+    "style",
+    "xmp",
+    "iframe",
+    "noembed",
+    "noframes",
+    "noscript",
+    "plaintext",
+];
+
+/// Elements whose content is text in which the character references are decoded.
+pub(crate) const ESCAPABLE_RAW_TEXT_ELEMENTS: [&str; 2] = ["textarea", "title"];
@@ -0,0 +1,227 @@
+//! Module of iterators over the Dom structure tree.
+
+use std::collections::VecDeque;
+use std::slice;
+
+use crate::dom::Dom;
+
+/// An event of `Traverse`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Edge<'a> {
+    /// Entering the Dom structure, before its children.
+    Open(&'a Dom),
+    /// Leaving the Dom structure, after its children.
+    Close(&'a Dom),
+}
+
+/// Iterator over the descendants of a Dom structure in document order. (pre-order)
+///
+/// It is created by `Dom::descendants()`.
+/// The tree is walked lazily, so the iteration can be stopped at any point.
+#[derive(Debug, Clone)]
+pub struct Descendants<'a> {
+    stack: Vec<slice::Iter<'a, Box<Dom>>>,
+    depth: usize,
+}
+
+impl<'a> Descendants<'a> {
+    pub(crate) fn new(dom: &'a Dom) -> Descendants<'a> {
+        let mut stack = Vec::new();
+        if let Some(children) = dom.get_children() {
+            stack.push(children.iter());
+        }
+        Descendants { stack, depth: 0 }
+    }
+
+    /// Returns the depth of the last returned Dom structure.
+    /// The children of the starting dom are at depth 1.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+}
+
+impl<'a> Iterator for Descendants<'a> {
+    type Item = &'a Dom;
+
+    fn next(&mut self) -> Option<&'a Dom> {
+        loop {
+            let top = self.stack.last_mut()?;
+            match top.next() {
+                Some(dom) => {
+                    self.depth = self.stack.len();
+                    if let Some(children) = dom.get_children() {
+                        self.stack.push(children.iter());
+                    }
+                    return Some(dom);
+                }
+                None => {
+                    self.stack.pop();
+                }
+            }
+        }
+    }
+}
+
+/// Iterator over `Edge` events of a Dom structure tree in document order.
+///
+/// It is created by `Dom::traverse()`.
+/// The starting dom is also opened and closed.
+#[derive(Debug, Clone)]
+pub struct Traverse<'a> {
+    start: Option<&'a Dom>,
+    stack: Vec<(&'a Dom, slice::Iter<'a, Box<Dom>>)>,
+    depth: usize,
+}
+
+impl<'a> Traverse<'a> {
+    pub(crate) fn new(dom: &'a Dom) -> Traverse<'a> {
+        Traverse {
+            start: Some(dom),
+            stack: Vec::new(),
+            depth: 0,
+        }
+    }
+
+    /// Returns the depth of the Dom structure of the last returned event.
+    /// The starting dom is at depth 0.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    fn open(&mut self, dom: &'a Dom) -> Edge<'a> {
+        self.depth = self.stack.len();
+        let children = match dom.get_children() {
+            Some(children) => children.iter(),
+            None => [].iter(),
+        };
+        self.stack.push((dom, children));
+        Edge::Open(dom)
+    }
+}
+
+impl<'a> Iterator for Traverse<'a> {
+    type Item = Edge<'a>;
+
+    fn next(&mut self) -> Option<Edge<'a>> {
+        if let Some(dom) = self.start.take() {
+            return Some(self.open(dom));
+        }
+        let (_, children) = self.stack.last_mut()?;
+        match children.next() {
+            Some(child) => Some(self.open(child)),
+            None => {
+                let (dom, _) = self.stack.pop().unwrap();
+                self.depth = self.stack.len();
+                Some(Edge::Close(dom))
+            }
+        }
+    }
+}
+
+/// Iterator over the descendants of a Dom structure in breadth-first order.
+///
+/// It is created by `Dom::bfs()`.
+#[derive(Debug, Clone)]
+pub struct Bfs<'a> {
+    queue: VecDeque<(&'a Dom, usize)>,
+    depth: usize,
+}
+
+impl<'a> Bfs<'a> {
+    pub(crate) fn new(dom: &'a Dom) -> Bfs<'a> {
+        let mut bfs = Bfs {
+            queue: VecDeque::new(),
+            depth: 0,
+        };
+        bfs.push_children(dom, 1);
+        bfs
+    }
+
+    /// Returns the depth of the last returned Dom structure.
+    /// The children of the starting dom are at depth 1.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    fn push_children(&mut self, dom: &'a Dom, depth: usize) {
+        if let Some(children) = dom.get_children() {
+            for child in children.iter() {
+                self.queue.push_back((child, depth));
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for Bfs<'a> {
+    type Item = &'a Dom;
+
+    fn next(&mut self) -> Option<&'a Dom> {
+        let (dom, depth) = self.queue.pop_front()?;
+        self.depth = depth;
+        self.push_children(dom, depth + 1);
+        Some(dom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser;
+
+    fn name(dom: &Dom) -> String {
+        match dom.get_tag() {
+            Some(tag) => tag.get_name().to_string(),
+            None => format!("{:?}", dom.dom_type),
+        }
+    }
+
+    #[test]
+    fn descendants_test() {
+        let dom = parser::parse("<a><b>text</b><!--c--></a><d></d>").unwrap();
+        let types: Vec<String> = dom.descendants().map(name).collect();
+        assert_eq!(types, vec!["a", "b", "Text", "Comment", "d"]);
+
+        let mut iter = dom.descendants();
+        let mut depths = Vec::new();
+        while iter.next().is_some() {
+            depths.push(iter.depth());
+        }
+        assert_eq!(depths, vec![1, 2, 3, 2, 1]);
+
+        let b = dom.descendants().nth(1).unwrap();
+        assert_eq!(b.descendants().count(), 1);
+
+        let empty = parser::parse("<a></a>").unwrap();
+        let a = empty.descendants().next().unwrap();
+        assert_eq!(a.descendants().next(), None);
+    }
+
+    #[test]
+    fn traverse_test() {
+        let dom = parser::parse("<a><b></b>text</a>").unwrap();
+        let mut iter = dom.traverse();
+        let mut events = Vec::new();
+        while let Some(edge) = iter.next() {
+            let event = match edge {
+                Edge::Open(dom) => format!("+{}", name(dom)),
+                Edge::Close(dom) => format!("-{}", name(dom)),
+            };
+            events.push(format!("{}{}", event, iter.depth()));
+        }
+        assert_eq!(
+            events,
+            vec!["+root0", "+a1", "+b2", "-b2", "+Text2", "-Text2", "-a1", "-root0"]
+        );
+    }
+
+    #[test]
+    fn bfs_test() {
+        let dom = parser::parse("<a><b><c></c></b><d></d></a><e></e>").unwrap();
+        let mut iter = dom.bfs();
+        let mut names = Vec::new();
+        while let Some(dom) = iter.next() {
+            names.push(format!("{}{}", name(dom), iter.depth()));
+        }
+        assert_eq!(names, vec!["a1", "e1", "b2", "d2", "c3"]);
+    }
+}
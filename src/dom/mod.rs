@@ -1,15 +1,47 @@
 //! Module for representing a tree of Dom structures.
 
+pub mod attrs;
+pub mod cdata;
 pub mod comment;
+pub mod doctype;
+pub(crate) mod elements;
+pub mod iter;
+pub mod namespace;
+pub mod position;
+pub mod processing_instruction;
 pub mod tag;
 pub mod text;
+pub mod tree;
+pub mod xml_declaration;
 
+pub use attrs::Attrs;
+pub use cdata::CData;
 pub use comment::Comment;
+pub use doctype::Doctype;
+pub use iter::Bfs;
+pub use iter::Descendants;
+pub use iter::Edge;
+pub use iter::Traverse;
+pub use position::Position;
+pub use position::Span;
+pub use processing_instruction::ProcessingInstruction;
 pub use tag::Tag;
 pub use text::Text;
+pub use tree::DomTree;
+pub use tree::NodeId;
+pub use xml_declaration::XmlDeclaration;
 
 use crate::parser;
+use crate::parser::ParseError;
 use crate::searcher;
+use crate::selector::Selector;
+use crate::selector::SelectorError;
+use crate::visitor;
+use crate::visitor::Visitor;
+use crate::visitor::VisitorMut;
+use crate::xpath::XPath;
+use crate::xpath::XPathError;
+use crate::xpath::XPathValue;
 
 /// Type of Dom structure.
 #[derive(Debug, PartialEq, Clone)]
@@ -17,17 +49,41 @@ pub enum DomType {
     Tag,
     Text,
     Comment,
+    Doctype,
+    CData,
+    ProcessingInstruction,
 }
 
 /// A structure that represents the parsing result of a tag document.
-#[derive(Debug, PartialEq, Clone)]
+///
+/// The span is not compared by `==`.
+#[derive(Debug, Clone)]
 pub struct Dom {
     /// Type of Dom structure
     pub dom_type: DomType,
     tag: Option<Tag>,
     text: Option<Text>,
     comment: Option<Comment>,
+    doctype: Option<Doctype>,
+    cdata: Option<CData>,
+    processing_instruction: Option<ProcessingInstruction>,
+    #[allow(clippy::vec_box)]
     children: Option<Vec<Box<Dom>>>,
+    span: Option<Span>,
+    root: bool,
+}
+
+impl PartialEq for Dom {
+    fn eq(&self, other: &Dom) -> bool {
+        self.dom_type == other.dom_type
+            && self.tag == other.tag
+            && self.text == other.text
+            && self.comment == other.comment
+            && self.doctype == other.doctype
+            && self.cdata == other.cdata
+            && self.processing_instruction == other.processing_instruction
+            && self.get_children() == other.get_children()
+    }
 }
 
 impl Dom {
@@ -38,7 +94,12 @@ impl Dom {
             tag: None,
             text: None,
             comment: None,
+            doctype: None,
+            cdata: None,
+            processing_instruction: None,
             children: None,
+            span: None,
+            root: false,
         }
     }
 
@@ -49,14 +110,23 @@ impl Dom {
         let tag = Tag::new("root");
         let mut dom = Dom::new(DomType::Tag);
         dom.set_tag(tag);
+        dom.root = true;
         dom
     }
 
+    /// Returns true if the root dom created by `Dom::new_root()`.
+    pub fn is_root(&self) -> bool {
+        self.root
+    }
+
     fn domtype_str(&self) -> String {
         match self.dom_type {
-            DomType::Tag => return String::from("Tag"),
-            DomType::Text => return String::from("Text"),
-            DomType::Comment => return String::from("Comment"),
+            DomType::Tag => String::from("Tag"),
+            DomType::Text => String::from("Text"),
+            DomType::Comment => String::from("Comment"),
+            DomType::Doctype => String::from("Doctype"),
+            DomType::CData => String::from("CData"),
+            DomType::ProcessingInstruction => String::from("ProcessingInstruction"),
         }
     }
 
@@ -77,6 +147,23 @@ impl Dom {
         self.tag.as_ref()
     }
 
+    /// Returns the mutable Tag structure.
+    /// If it does not have a Tag structure, it returns `None`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// let mut root_dom = parsercher::parse(r#"<a href="/old" target="_blank">link</a>"#).unwrap();
+    /// let a = root_dom.get_children_mut().unwrap().first_mut().unwrap();
+    ///
+    /// let tag = a.get_tag_mut().unwrap();
+    /// tag.set_attr("href", "/new");
+    /// tag.remove_attr("target");
+    /// assert_eq!(root_dom.to_html(), r#"<a href="/new">link</a>"#);
+    /// ```
+    pub fn get_tag_mut(&mut self) -> Option<&mut Tag> {
+        self.tag.as_mut()
+    }
+
     /// Set Text structure.
     ///
     /// # Panics
@@ -111,6 +198,128 @@ impl Dom {
         self.comment.as_ref()
     }
 
+    /// Set Doctype structure.
+    ///
+    /// # Panics
+    /// `self.dom_type` is not `DomType::Doctype`
+    pub fn set_doctype(&mut self, doctype: Doctype) {
+        match self.dom_type {
+            DomType::Doctype => self.doctype = Some(doctype),
+            _ => panic!("invalid DomType. expect Doctype but {}", self.domtype_str()),
+        }
+    }
+
+    /// Returns the Doctype structure.
+    /// If it does not have a Doctype structure, it returns `None`.
+    pub fn get_doctype(&self) -> Option<&Doctype> {
+        self.doctype.as_ref()
+    }
+
+    /// Returns the document type declaration in the children.
+    /// It is usually called on the root dom.
+    ///
+    /// # Examples
+    /// ```rust
+    /// let html = r#"
+    /// <!DOCTYPE html PUBLIC "-//W3C//DTD XHTML 1.0 Strict//EN" "http://www.w3.org/TR/xhtml1/DTD/xhtml1-strict.dtd">
+    /// <html></html>
+    /// "#;
+    /// let root_dom = parsercher::parse(&html).unwrap();
+    ///
+    /// let doctype = root_dom.doctype().unwrap();
+    /// assert_eq!(doctype.get_name(), "html");
+    /// assert_eq!(doctype.get_public_id(), Some("-//W3C//DTD XHTML 1.0 Strict//EN"));
+    /// ```
+    pub fn doctype(&self) -> Option<&Doctype> {
+        self.get_children()?
+            .iter()
+            .find_map(|child| child.get_doctype())
+    }
+
+    /// Set CData structure.
+    ///
+    /// # Panics
+    /// `self.dom_type` is not `DomType::CData`
+    pub fn set_cdata(&mut self, cdata: CData) {
+        match self.dom_type {
+            DomType::CData => self.cdata = Some(cdata),
+            _ => panic!("invalid DomType. expect CData but {}", self.domtype_str()),
+        }
+    }
+
+    /// Returns the CData structure.
+    /// If it does not have a CData structure, it returns `None`.
+    pub fn get_cdata(&self) -> Option<&CData> {
+        self.cdata.as_ref()
+    }
+
+    /// Set ProcessingInstruction structure.
+    ///
+    /// # Panics
+    /// `self.dom_type` is not `DomType::ProcessingInstruction`
+    pub fn set_processing_instruction(&mut self, processing_instruction: ProcessingInstruction) {
+        match self.dom_type {
+            DomType::ProcessingInstruction => {
+                self.processing_instruction = Some(processing_instruction)
+            }
+            _ => panic!(
+                "invalid DomType. expect ProcessingInstruction but {}",
+                self.domtype_str()
+            ),
+        }
+    }
+
+    /// Returns the ProcessingInstruction structure.
+    /// If it does not have a ProcessingInstruction structure, it returns `None`.
+    pub fn get_processing_instruction(&self) -> Option<&ProcessingInstruction> {
+        self.processing_instruction.as_ref()
+    }
+
+    /// Returns the XML declaration in the children.
+    /// It is usually called on the root dom.
+    ///
+    /// # Examples
+    /// ```rust
+    /// let xml = r#"<?xml version="1.0" encoding="UTF-8"?><note></note>"#;
+    /// let root_dom = parsercher::parse(&xml).unwrap();
+    ///
+    /// let declaration = root_dom.xml_declaration().unwrap();
+    /// assert_eq!(declaration.get_version(), "1.0");
+    /// assert_eq!(declaration.get_encoding(), Some("UTF-8"));
+    /// ```
+    pub fn xml_declaration(&self) -> Option<XmlDeclaration> {
+        self.get_children()?.iter().find_map(|child| {
+            child
+                .get_processing_instruction()
+                .and_then(|pi| pi.xml_declaration())
+        })
+    }
+
+    /// Set the range of the source document from which the Dom structure was parsed.
+    pub fn set_span(&mut self, span: Span) {
+        self.span = Some(span);
+    }
+
+    /// Returns the range of the source document from which the Dom structure was parsed.
+    /// For a tag, it is from the start tag to the end tag.
+    /// If it was not created by parsing, it returns `None`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// let html = "<body>\n  <h1>Hello</h1>\n</body>";
+    /// let root_dom = parsercher::parse(&html).unwrap();
+    /// let body_dom = root_dom.get_children().unwrap().first().unwrap();
+    /// let h1_dom = body_dom.get_children().unwrap().first().unwrap();
+    ///
+    /// let span = h1_dom.get_span().unwrap();
+    /// assert_eq!(&html[span.get_range()], "<h1>Hello</h1>");
+    /// assert_eq!(span.get_start().get_line(), 2);
+    /// assert_eq!(span.get_start().get_column(), 3);
+    /// ```
+    pub fn get_span(&self) -> Option<Span> {
+        self.span
+    }
+
     /// Add a child Dom structure.
     pub fn add_child(&mut self, dom: Dom) {
         let dom = Box::new(dom);
@@ -119,17 +328,173 @@ impl Dom {
                 children.push(dom);
             }
             None => {
-                let mut children = Vec::new();
-                children.push(dom);
-                self.children = Some(children);
+                self.children = Some(vec![dom]);
             }
         }
     }
 
+    /// Removes and returns the children.
+    #[allow(clippy::vec_box)]
+    pub(crate) fn take_children(&mut self) -> Option<Vec<Box<Dom>>> {
+        self.children.take().filter(|children| !children.is_empty())
+    }
+
     /// Returns child Dom structures as Vec.
     /// If it does not have children, it returns `None`.
+    #[allow(clippy::vec_box)]
     pub fn get_children(&self) -> Option<&Vec<Box<Dom>>> {
-        self.children.as_ref()
+        self.children
+            .as_ref()
+            .filter(|children| !children.is_empty())
+    }
+
+    /// Returns the mutable child Dom structures.
+    /// If no child has been added, it returns `None`.
+    ///
+    /// If all the children are removed through it, it returns an empty Vec instead of `None`.
+    /// The Dom structure has no children nevertheless, and `get_children()` returns `None`.
+    #[allow(clippy::vec_box)]
+    pub fn get_children_mut(&mut self) -> Option<&mut Vec<Box<Dom>>> {
+        self.children.as_mut()
+    }
+
+    /// Sets `None` to the children if there is no child.
+    fn normalize_children(&mut self) {
+        if self
+            .children
+            .as_ref()
+            .is_some_and(|children| children.is_empty())
+        {
+            self.children = None;
+        }
+    }
+
+    /// Insert a child Dom structure at `index`.
+    ///
+    /// # Panics
+    /// `index` is greater than the number of children.
+    pub fn insert_child(&mut self, index: usize, dom: Dom) {
+        self.children
+            .get_or_insert_with(Vec::new)
+            .insert(index, Box::new(dom));
+    }
+
+    /// Remove and return the child Dom structure at `index`.
+    ///
+    /// # Panics
+    /// `index` is out of range.
+    ///
+    /// # Examples
+    /// ```rust
+    /// let mut root_dom = parsercher::parse("<ul><li>1</li><li>2</li></ul>").unwrap();
+    /// let ul = root_dom.get_children_mut().unwrap().first_mut().unwrap();
+    ///
+    /// let li = ul.remove_child(0);
+    /// assert_eq!(li.to_html(), "<li>1</li>");
+    /// assert_eq!(root_dom.to_html(), "<ul><li>2</li></ul>");
+    /// ```
+    pub fn remove_child(&mut self, index: usize) -> Dom {
+        let children = match &mut self.children {
+            Some(children) => children,
+            None => panic!("remove index (is {}) should be < len (is 0)", index),
+        };
+        let dom = children.remove(index);
+        self.normalize_children();
+        *dom
+    }
+
+    /// Keep only the children for which `f` returns true.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use parsercher::dom::DomType;
+    ///
+    /// let mut root_dom = parsercher::parse("<p>text<!-- comment --><b>bold</b></p>").unwrap();
+    /// let p = root_dom.get_children_mut().unwrap().first_mut().unwrap();
+    ///
+    /// p.retain_children(|dom| dom.dom_type != DomType::Comment);
+    /// assert_eq!(root_dom.to_html(), "<p>text<b>bold</b></p>");
+    /// ```
+    pub fn retain_children<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&Dom) -> bool,
+    {
+        if let Some(children) = &mut self.children {
+            children.retain(|dom| f(dom));
+        }
+        self.normalize_children();
+    }
+
+    /// Replace this Dom structure with `dom` and return the old one.
+    pub fn replace_with(&mut self, dom: Dom) -> Dom {
+        std::mem::replace(self, dom)
+    }
+
+    /// Put this Dom structure into `wrapper` as its last child, and put `wrapper` in its place.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use parsercher::dom::{Dom, DomType, Tag};
+    ///
+    /// let mut root_dom = parsercher::parse("<p><a>link</a></p>").unwrap();
+    /// let p = root_dom.get_children_mut().unwrap().first_mut().unwrap();
+    /// let a = p.get_children_mut().unwrap().first_mut().unwrap();
+    ///
+    /// let mut strong = Dom::new(DomType::Tag);
+    /// strong.set_tag(Tag::new("strong"));
+    /// a.wrap(strong);
+    /// assert_eq!(root_dom.to_html(), "<p><strong><a>link</a></strong></p>");
+    /// ```
+    pub fn wrap(&mut self, wrapper: Dom) {
+        let dom = self.replace_with(wrapper);
+        self.add_child(dom);
+    }
+
+    /// Replace the child Dom structure at `index` with its children, and return the child without them.
+    ///
+    /// # Panics
+    /// `index` is out of range.
+    ///
+    /// # Examples
+    /// ```rust
+    /// let mut root_dom = parsercher::parse("<p><span>a<b>b</b></span>c</p>").unwrap();
+    /// let p = root_dom.get_children_mut().unwrap().first_mut().unwrap();
+    ///
+    /// let span = p.unwrap_child(0);
+    /// assert_eq!(span.to_html(), "<span></span>");
+    /// assert_eq!(root_dom.to_html(), "<p>a<b>b</b>c</p>");
+    /// ```
+    pub fn unwrap_child(&mut self, index: usize) -> Dom {
+        let mut dom = self.remove_child(index);
+        if let Some(grandchildren) = dom.children.take() {
+            self.children
+                .get_or_insert_with(Vec::new)
+                .splice(index..index, grandchildren);
+        }
+        dom
+    }
+
+    /// Returns the Dom structure tree as a tag document.
+    /// Characters in texts and attribute values are escaped.
+    ///
+    /// Parsing the returned document results in an equal Dom structure tree.
+    ///
+    /// # Examples
+    /// ```rust
+    /// let html = r#"
+    /// <ul>
+    ///   <li class="item">Tom &amp; Jerry</li>
+    ///   <!-- comment -->
+    /// </ul>
+    /// "#;
+    /// let dom = parsercher::parse(&html).unwrap();
+    ///
+    /// let doc = dom.to_html();
+    /// assert_eq!(doc, r#"<ul><li class="item">Tom &amp; Jerry</li><!-- comment --></ul>"#);
+    /// assert_eq!(parsercher::parse(&doc).unwrap(), dom);
+    /// ```
+    pub fn to_html(&self) -> String {
+        self.to_string()
     }
 
     /// Returns true if p is a sufficient condition for q.
@@ -191,6 +556,27 @@ impl Dom {
                     }
                 }
             }
+            DomType::Doctype => return p.get_doctype() == q.get_doctype(),
+            DomType::CData => {
+                if let Some(q_cdata) = q.get_cdata() {
+                    if let Some(p_cdata) = p.get_cdata() {
+                        if q_cdata.get_data().contains(p_cdata.get_data()) {
+                            return true;
+                        }
+                    }
+                }
+            }
+            DomType::ProcessingInstruction => {
+                if let Some(q_pi) = q.get_processing_instruction() {
+                    if let Some(p_pi) = p.get_processing_instruction() {
+                        if q_pi.get_target() == p_pi.get_target()
+                            && q_pi.get_data().contains(p_pi.get_data())
+                        {
+                            return true;
+                        }
+                    }
+                }
+            }
         }
         false
     }
@@ -238,10 +624,10 @@ impl Dom {
         if !Dom::p_implies_q(p, q) {
             return false;
         }
-        if let None = p.get_children() {
+        if p.get_children().is_none() {
             return true;
         }
-        if let None = q.get_children() {
+        if q.get_children().is_none() {
             return false;
         }
 
@@ -265,6 +651,12 @@ impl Dom {
 
     /// Return the `needle`-like subtree from the Dom structure tree.
     /// The `needle` argument must be parsable html.
+    /// It is parsed in `ParseMode::Html`, so its names are lowercase. For a tree parsed in
+    /// `ParseMode::Xml`, use `parsercher::search_dom()` with a needle parsed in the same mode.
+    /// If `needle` is empty, returns `None`.
+    ///
+    /// # Errors
+    /// Returns a `ParseError` if the `needle` cannot be parsed.
     ///
     /// # Examples
     /// Get the subtree that satisfies the following tag names and attribute values.
@@ -331,13 +723,13 @@ impl Dom {
     /// ```
     /// output:
     /// ```text
-    /// <ul class="targetList" id="list1">
+    /// <ul id="list1" class="targetList">
     ///   <li class="key1">
     ///     TEXT: "1-1"
     ///   <li class="key2">
     ///     <span>
     ///       TEXT: "1-2"
-    /// <ul class="targetList" id="list3">
+    /// <ul id="list3" class="targetList">
     ///   <li class="key1">
     ///     TEXT: "3-1"
     ///   <li class="item">
@@ -345,11 +737,15 @@ impl Dom {
     ///   <li class="key2">
     ///     TEXT: "3-3"
     /// ```
-    pub fn search(&self, needle: &str) -> Result<Option<Vec<Box<Dom>>>, String> {
-        let needle = parser::parse(&needle)?;
+    #[allow(clippy::vec_box)]
+    pub fn search(&self, needle: &str) -> Result<Option<Vec<Box<Dom>>>, ParseError> {
+        let needle = parser::parse(needle)?;
         // remove root dom
-        let needle = needle.get_children().unwrap().get(0).unwrap();
-        let root_dom = match searcher::search_dom(&self, &needle) {
+        let needle = match needle.get_children().and_then(|children| children.first()) {
+            Some(needle) => needle,
+            None => return Ok(None),
+        };
+        let root_dom = match searcher::search_dom(self, needle) {
             Some(root_dom) => root_dom,
             None => return Ok(None),
         };
@@ -358,6 +754,238 @@ impl Dom {
             None => Ok(None),
         }
     }
+
+    /// Returns references to the subtrees that match the `needle` tag document.
+    /// Unlike `Dom::search()`, the subtrees are not copied.
+    /// If `needle` is empty, returns an empty Vec.
+    ///
+    /// # Errors
+    /// Returns `ParseError` if `needle` cannot be parsed.
+    ///
+    /// # Examples
+    /// ```rust
+    /// let html = r#"
+    /// <ul id="list1" class="targetList">
+    ///   <li class="key1">1-1</li>
+    ///   <li class="key2">1-2</li>
+    /// </ul>
+    /// <ul id="list2" class="targetList">
+    ///   <li class="key1">2-1</li>
+    /// </ul>
+    /// "#;
+    /// let root_dom = parsercher::parse(&html).unwrap();
+    ///
+    /// let result = root_dom
+    ///     .search_refs(r#"<ul class="targetList"><li class="key1"></li></ul>"#)
+    ///     .unwrap();
+    /// assert_eq!(result.len(), 2);
+    /// assert_eq!(result[1].get_children().unwrap().len(), 1);
+    /// ```
+    pub fn search_refs(&self, needle: &str) -> Result<Vec<&Dom>, ParseError> {
+        let needle = parser::parse(needle)?;
+        // remove root dom
+        match needle.get_children().and_then(|children| children.first()) {
+            Some(needle) => Ok(searcher::search_dom_refs(self, needle)),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Returns an iterator over the descendants in document order. (pre-order)
+    /// This dom itself is not included.
+    /// `Descendants::depth()` returns the depth of the current dom.
+    ///
+    /// # Examples
+    /// ```rust
+    /// let html = "<ul><li>first</li><li>second</li></ul>";
+    /// let dom = parsercher::parse(&html).unwrap();
+    ///
+    /// let first = dom
+    ///     .descendants()
+    ///     .find_map(|dom| dom.get_text())
+    ///     .unwrap();
+    /// assert_eq!(first.get_text(), "first");
+    /// ```
+    pub fn descendants(&self) -> Descendants<'_> {
+        Descendants::new(self)
+    }
+
+    /// Returns an iterator over `Edge::Open` and `Edge::Close` events in document order.
+    /// This dom itself is opened first and closed last.
+    /// `Traverse::depth()` returns the depth of the current dom.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use parsercher::dom::Edge;
+    ///
+    /// let html = "<ul><li>first</li><li>second</li></ul>";
+    /// let dom = parsercher::parse(&html).unwrap();
+    ///
+    /// let mut out = String::new();
+    /// let mut traverse = dom.traverse();
+    /// while let Some(edge) = traverse.next() {
+    ///     match edge {
+    ///         Edge::Open(dom) => {
+    ///             if let Some(text) = dom.get_text() {
+    ///                 out += &format!("{}{}\n", "  ".repeat(traverse.depth()), text.get_text());
+    ///             }
+    ///         }
+    ///         Edge::Close(_) => (),
+    ///     }
+    /// }
+    /// assert_eq!(out, "      first\n      second\n");
+    /// ```
+    pub fn traverse(&self) -> Traverse<'_> {
+        Traverse::new(self)
+    }
+
+    /// Returns an iterator over the descendants in breadth-first order.
+    /// This dom itself is not included.
+    /// `Bfs::depth()` returns the depth of the current dom.
+    ///
+    /// # Examples
+    /// ```rust
+    /// let html = "<div><p>text</p></div><span></span>";
+    /// let dom = parsercher::parse(&html).unwrap();
+    ///
+    /// let names: Vec<&str> = dom
+    ///     .bfs()
+    ///     .filter_map(|dom| dom.get_tag())
+    ///     .map(|tag| tag.get_name())
+    ///     .collect();
+    /// assert_eq!(names, vec!["div", "span", "p"]);
+    /// ```
+    pub fn bfs(&self) -> Bfs<'_> {
+        Bfs::new(self)
+    }
+
+    /// Returns an iterator over the descendant tags for which `needle` is a sufficient condition.
+    /// It is the lazy version of `parsercher::search_tag()`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use parsercher::dom::Tag;
+    ///
+    /// let html = r#"
+    /// <ol>
+    ///    <li class="target">first</li>
+    ///    <li>second</li>
+    ///    <li class="target">therd</li>
+    /// </ol>
+    /// "#;
+    /// let dom = parsercher::parse(&html).unwrap();
+    ///
+    /// let mut needle = Tag::new("li");
+    /// needle.set_attr("class", "target");
+    /// let first = dom.find_tags(&needle).next().unwrap();
+    /// assert_eq!(first.to_html(), r#"<li class="target">first</li>"#);
+    /// assert_eq!(dom.find_tags(&needle).count(), 2);
+    /// ```
+    pub fn find_tags<'a>(&'a self, needle: &'a Tag) -> impl Iterator<Item = &'a Dom> + 'a {
+        self.descendants().filter(move |dom| match dom.get_tag() {
+            Some(tag) => Tag::p_implies_q(needle, tag),
+            None => false,
+        })
+    }
+
+    /// Returns an iterator over the values of the `attr` attribute of the descendant tags.
+    /// It is the lazy version of `parsercher::search_attr()`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// let html = r#"
+    /// <a href="/1">1</a>
+    /// <a>none</a>
+    /// <a href="/2">2</a>
+    /// "#;
+    /// let dom = parsercher::parse(&html).unwrap();
+    ///
+    /// let hrefs: Vec<&str> = dom.attr_values("href").collect();
+    /// assert_eq!(hrefs, vec!["/1", "/2"]);
+    /// ```
+    pub fn attr_values<'a>(&'a self, attr: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.descendants()
+            .filter_map(move |dom| dom.get_tag()?.get_attrs()?.get(attr))
+    }
+
+    /// Calls `visitor` for each child of this dom. This dom itself is not visited.
+    /// See `Visitor`.
+    pub fn visit<V: Visitor + ?Sized>(&self, visitor: &mut V) {
+        visitor::visit_children(visitor, self);
+    }
+
+    /// Calls `visitor` for each child of this dom and applies the returned `VisitAction`.
+    /// This dom itself is not visited. See `VisitorMut`.
+    pub fn visit_mut<V: VisitorMut + ?Sized>(&mut self, visitor: &mut V) {
+        visitor::visit_children_mut(visitor, self);
+    }
+
+    /// Returns the descendants that match the CSS selector in document order.
+    /// See `Selector` for the supported selectors.
+    ///
+    /// # Errors
+    /// Returns `SelectorError` if the selector is invalid or not supported.
+    ///
+    /// # Examples
+    /// ```rust
+    /// let html = r#"
+    /// <div class="content">
+    ///   <ul>
+    ///     <li><a href="http://example.com/1">1</a></li>
+    ///     <li><a href="https://example.com/2">2</a></li>
+    ///   </ul>
+    /// </div>
+    /// "#;
+    /// let dom = parsercher::parse(&html).unwrap();
+    ///
+    /// let links = dom
+    ///     .select("div.content > ul li:nth-child(2) a[href^='https']")
+    ///     .unwrap();
+    /// assert_eq!(links.len(), 1);
+    /// assert_eq!(
+    ///     links[0].get_tag().unwrap().get_attr("href"),
+    ///     Some("https://example.com/2".to_string())
+    /// );
+    /// ```
+    pub fn select(&self, selector: &str) -> Result<Vec<&Dom>, SelectorError> {
+        let selector = Selector::parse(selector)?;
+        Ok(selector.select(self))
+    }
+
+    /// Returns the index of this Dom structure tree with links to the parents and siblings.
+    /// See `DomTree`.
+    pub fn tree(&self) -> DomTree<'_> {
+        DomTree::new(self)
+    }
+
+    /// Evaluates the XPath 1.0 expression with this dom as the context node.
+    /// See `XPath` for the supported expressions.
+    ///
+    /// # Errors
+    /// Returns `XPathError` if the expression is invalid or not supported.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use parsercher::XPathValue;
+    ///
+    /// let html = r#"
+    /// <div id="content">
+    ///   <ol>
+    ///     <li>first</li>
+    ///     <li>second</li>
+    ///   </ol>
+    /// </div>
+    /// "#;
+    /// let dom = parsercher::parse(&html).unwrap();
+    ///
+    /// let value = dom.xpath("//div[@id='content']/ol/li[2]/text()").unwrap();
+    /// assert_eq!(value.to_string(), "second");
+    ///
+    /// let value = dom.xpath("count(//li)").unwrap();
+    /// assert_eq!(value, XPathValue::Number(2.0));
+    /// ```
+    pub fn xpath(&self, expr: &str) -> Result<XPathValue<'_>, XPathError> {
+        XPath::parse(expr)?.evaluate(self)
+    }
 }
 
 #[cfg(test)]
@@ -378,7 +1006,7 @@ mod tests {
         tag.set_attr("class", "target");
         q.set_tag(tag);
 
-        assert_eq!(Dom::p_implies_q(&p, &q), true);
+        assert!(Dom::p_implies_q(&p, &q));
     }
 
     #[test]
@@ -393,7 +1021,7 @@ mod tests {
         tag.set_attr("id", "q");
         q.set_tag(tag);
 
-        assert_eq!(Dom::p_implies_q(&p, &q), false);
+        assert!(!Dom::p_implies_q(&p, &q));
     }
 
     #[test]
@@ -406,7 +1034,7 @@ mod tests {
         let text = Text::new("abcdefghi");
         q.set_text(text);
 
-        assert_eq!(Dom::p_implies_q(&p, &q), true);
+        assert!(Dom::p_implies_q(&p, &q));
     }
 
     #[test]
@@ -458,7 +1086,7 @@ mod tests {
         ul_dom.add_child(li_dom);
         q.add_child(ul_dom);
 
-        assert_eq!(Dom::p_implies_q_tree(&p, &q), true);
+        assert!(Dom::p_implies_q_tree(&p, &q));
     }
 
     #[test]
@@ -475,7 +1103,7 @@ mod tests {
           </ul>
         </body>
         "#;
-        let a_dom = parser::parse(&a).unwrap();
+        let a_dom = parser::parse(a).unwrap();
 
         let b = r#"
         <head>
@@ -489,10 +1117,10 @@ mod tests {
           </ul>
         </body>
         "#;
-        let b_dom = parser::parse(&b).unwrap();
+        let b_dom = parser::parse(b).unwrap();
 
-        assert_eq!(a_dom == b_dom, true);
-        assert_eq!(a_dom != b_dom, false);
+        assert!(a_dom == b_dom);
+        assert!(!(a_dom != b_dom));
     }
 
     #[test]
@@ -509,7 +1137,7 @@ mod tests {
           </ul>
         </body>
         "#;
-        let a_dom = parser::parse(&a).unwrap();
+        let a_dom = parser::parse(a).unwrap();
 
         let b = r#"
         <head>
@@ -523,9 +1151,9 @@ mod tests {
           </ul>
         </body>
         "#;
-        let b_dom = parser::parse(&b).unwrap();
+        let b_dom = parser::parse(b).unwrap();
 
-        assert_eq!(a_dom == b_dom, false);
-        assert_eq!(a_dom != b_dom, true);
+        assert!(!(a_dom == b_dom));
+        assert!(a_dom != b_dom);
     }
 }
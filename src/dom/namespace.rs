@@ -0,0 +1,123 @@
+//! Module of the XML namespaces.
+//!
+//! The namespace URIs of the tags and the attributes are resolved
+//! from the `xmlns` and `xmlns:prefix` attributes in scope when parsed.
+
+use crate::dom::Dom;
+use crate::dom::Tag;
+
+/// Namespace URI bound to the `xml` prefix.
+pub const XML: &str = "http://www.w3.org/XML/1998/namespace";
+/// Namespace URI of the `xmlns` and `xmlns:prefix` attributes.
+pub const XMLNS: &str = "http://www.w3.org/2000/xmlns/";
+/// Namespace URI of the HTML elements.
+pub const HTML: &str = "http://www.w3.org/1999/xhtml";
+/// Namespace URI of the SVG elements.
+pub const SVG: &str = "http://www.w3.org/2000/svg";
+/// Namespace URI of the MathML elements.
+pub const MATHML: &str = "http://www.w3.org/1998/Math/MathML";
+
+/// Returns the prefix and the local name of a qualified name.
+/// e.g. `soap:Envelope` is split into `Some("soap")` and `Envelope`.
+pub(crate) fn split_name(name: &str) -> (Option<&str>, &str) {
+    match name.find(':') {
+        Some(i) if i > 0 && i + 1 < name.len() => (Some(&name[..i]), &name[i + 1..]),
+        _ => (None, name),
+    }
+}
+
+/// The namespace declarations in scope. The last one wins.
+/// The default namespace has no prefix, and the empty URI undeclares it.
+struct Scope {
+    bindings: Vec<(Option<String>, String)>,
+}
+
+impl Scope {
+    fn lookup(&self, prefix: Option<&str>) -> Option<&str> {
+        match prefix {
+            Some("xml") => return Some(XML),
+            Some("xmlns") => return Some(XMLNS),
+            _ => (),
+        }
+        self.bindings
+            .iter()
+            .rev()
+            .find(|(bound, _)| bound.as_deref() == prefix)
+            .map(|(_, uri)| uri.as_str())
+            .filter(|uri| !uri.is_empty())
+    }
+}
+
+/// Sets the namespace URIs of the tags and the attributes in the Dom structure tree.
+pub(crate) fn resolve(dom: &mut Dom) {
+    let mut scope = Scope {
+        bindings: Vec::new(),
+    };
+    resolve_exe(dom, &mut scope);
+}
+
+fn resolve_exe(dom: &mut Dom, scope: &mut Scope) {
+    let depth = scope.bindings.len();
+    if !dom.is_root() {
+        if let Some(tag) = dom.get_tag_mut() {
+            declare(tag, scope);
+            resolve_tag(tag, scope);
+        }
+    }
+    if let Some(children) = dom.get_children_mut() {
+        for child in children.iter_mut() {
+            resolve_exe(child, scope);
+        }
+    }
+    scope.bindings.truncate(depth);
+}
+
+/// Adds the namespace declarations of the tag to the scope.
+fn declare(tag: &Tag, scope: &mut Scope) {
+    if let Some(attrs) = tag.get_attrs() {
+        for (name, value) in attrs.iter() {
+            if name == "xmlns" {
+                scope.bindings.push((None, String::from(value)));
+            } else if let Some(prefix) = name.strip_prefix("xmlns:") {
+                scope
+                    .bindings
+                    .push((Some(String::from(prefix)), String::from(value)));
+            }
+        }
+    }
+}
+
+fn resolve_tag(tag: &mut Tag, scope: &Scope) {
+    let (prefix, _) = split_name(tag.get_name());
+    if let Some(uri) = scope.lookup(prefix) {
+        tag.set_namespace_uri(uri);
+    }
+
+    if let Some(attrs) = tag.get_attrs_mut() {
+        let names: Vec<String> = attrs.iter().map(|(name, _)| String::from(name)).collect();
+        for name in names.iter() {
+            // unprefixed attributes are in no namespace except `xmlns`
+            let uri = match split_name(name) {
+                (None, "xmlns") => Some(XMLNS),
+                (None, _) => None,
+                (prefix, _) => scope.lookup(prefix),
+            };
+            if let Some(uri) = uri {
+                attrs.set_namespace_uri(name, uri);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_name_test() {
+        assert_eq!(split_name("soap:Envelope"), (Some("soap"), "Envelope"));
+        assert_eq!(split_name("Envelope"), (None, "Envelope"));
+        assert_eq!(split_name(":a"), (None, ":a"));
+        assert_eq!(split_name("a:"), (None, "a:"));
+    }
+}
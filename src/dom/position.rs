@@ -0,0 +1,96 @@
+//! Module of Position and Span structures.
+
+/// A structure that represents a location in the source document.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct Position {
+    offset: usize,
+    line: usize,
+    column: usize,
+}
+
+impl Position {
+    /// Create new Position structure.
+    ///
+    /// # Arguments
+    /// * `offset` - Byte offset from the beginning of the document.
+    /// * `line` - Line number starting from 1.
+    /// * `column` - Column number in characters starting from 1.
+    pub fn new(offset: usize, line: usize, column: usize) -> Position {
+        Position {
+            offset,
+            line,
+            column,
+        }
+    }
+
+    /// Returns the byte offset from the beginning of the document.
+    pub fn get_offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns the line number starting from 1.
+    pub fn get_line(&self) -> usize {
+        self.line
+    }
+
+    /// Returns the column number in characters starting from 1.
+    pub fn get_column(&self) -> usize {
+        self.column
+    }
+}
+
+/// A structure that represents a range of the source document.
+/// The `end` is exclusive.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct Span {
+    start: Position,
+    end: Position,
+}
+
+impl Span {
+    /// Create new Span structure.
+    ///
+    /// # Arguments
+    /// * `start` - Position of the first character.
+    /// * `end` - Position after the last character.
+    pub fn new(start: Position, end: Position) -> Span {
+        Span { start, end }
+    }
+
+    /// Returns the position of the first character.
+    pub fn get_start(&self) -> Position {
+        self.start
+    }
+
+    /// Returns the position after the last character.
+    pub fn get_end(&self) -> Position {
+        self.end
+    }
+
+    /// Returns the range of byte offsets.
+    /// It can be used to slice the source document.
+    pub fn get_range(&self) -> std::ops::Range<usize> {
+        self.start.get_offset()..self.end.get_offset()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn getter_test() {
+        let pos = Position::new(10, 2, 4);
+        assert_eq!(pos.get_offset(), 10);
+        assert_eq!(pos.get_line(), 2);
+        assert_eq!(pos.get_column(), 4);
+    }
+
+    #[test]
+    fn span_range_test() {
+        let span = Span::new(Position::new(3, 1, 4), Position::new(9, 2, 2));
+        assert_eq!(span.get_range(), 3..9);
+        assert_eq!(span.get_start().get_line(), 1);
+        assert_eq!(span.get_end().get_line(), 2);
+    }
+}
@@ -0,0 +1,73 @@
+//! Module of ProcessingInstruction structure.
+
+use std::fmt;
+
+use crate::dom::XmlDeclaration;
+
+/// A structure that represents a processing instruction.
+/// grammar: `<?target data?>`
+///
+/// The XML declaration `<?xml version="1.0"?>` is also a processing instruction
+/// whose target is `xml`. `xml_declaration()` returns it as `XmlDeclaration`.
+///
+/// `Display` writes it as a processing instruction.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ProcessingInstruction {
+    target: String,
+    data: String,
+}
+
+impl ProcessingInstruction {
+    /// Create new ProcessingInstruction structure.
+    ///
+    /// # Arguments
+    /// * `target` - If `<?xml-stylesheet href="a.css"?>`, then `xml-stylesheet`.
+    /// * `data` - If `<?xml-stylesheet href="a.css"?>`, then `href="a.css"`.
+    pub fn new(target: &str, data: &str) -> ProcessingInstruction {
+        ProcessingInstruction {
+            target: String::from(target),
+            data: String::from(data),
+        }
+    }
+
+    /// Returns the target.
+    /// If `<?xml-stylesheet href="a.css"?>`, then returns `xml-stylesheet`.
+    pub fn get_target(&self) -> &str {
+        &self.target
+    }
+
+    /// Returns the data.
+    /// If `<?xml-stylesheet href="a.css"?>`, then returns `href="a.css"`.
+    pub fn get_data(&self) -> &str {
+        &self.data
+    }
+
+    /// Returns the XML declaration if the target is `xml` and the data has the version.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use parsercher::dom::ProcessingInstruction;
+    ///
+    /// let pi = ProcessingInstruction::new("xml", r#"version="1.0" encoding="UTF-8""#);
+    /// let declaration = pi.xml_declaration().unwrap();
+    /// assert_eq!(declaration.get_version(), "1.0");
+    /// assert_eq!(declaration.get_encoding(), Some("UTF-8"));
+    /// assert_eq!(declaration.get_standalone(), None);
+    /// ```
+    pub fn xml_declaration(&self) -> Option<XmlDeclaration> {
+        if self.target != "xml" {
+            return None;
+        }
+        XmlDeclaration::parse(&self.data)
+    }
+}
+
+impl fmt::Display for ProcessingInstruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.data.is_empty() {
+            write!(f, "<?{}?>", self.target)
+        } else {
+            write!(f, "<?{} {}?>", self.target, self.data)
+        }
+    }
+}
@@ -1,15 +1,33 @@
 //! Module of Tag structure.
 
-use std::collections::HashMap;
+use crate::dom::namespace;
+use crate::dom::Attrs;
+use crate::dom::Span;
 
 /// A structure that represents a tag element.
 /// grammar: `<[/]name [attr[="value"]] [/]>`
-#[derive(Debug, PartialEq, Clone)]
+///
+/// The raw name, the raw attribute values, the namespace URIs and the spans are not compared by `==`.
+/// The order of the attributes is compared.
+#[derive(Debug, Clone)]
 pub struct Tag {
     name: String,
-    attrs: Option<HashMap<String, String>>,
+    raw_name: Option<String>,
+    namespace_uri: Option<String>,
+    attrs: Option<Attrs>,
     terminated: bool,
     terminator: bool,
+    span: Option<Span>,
+    end_span: Option<Span>,
+}
+
+impl PartialEq for Tag {
+    fn eq(&self, other: &Tag) -> bool {
+        self.name == other.name
+            && self.attrs == other.attrs
+            && self.terminated == other.terminated
+            && self.terminator == other.terminator
+    }
 }
 
 impl Tag {
@@ -20,9 +38,13 @@ impl Tag {
     pub fn new(name: &str) -> Tag {
         Tag {
             name: String::from(name),
+            raw_name: None,
+            namespace_uri: None,
             attrs: None,
             terminated: false,
             terminator: false,
+            span: None,
+            end_span: None,
         }
     }
 
@@ -32,28 +54,100 @@ impl Tag {
         &self.name
     }
 
+    /// Set the tag name.
+    /// The raw name is cleared.
+    pub fn set_name(&mut self, name: &str) {
+        self.name = String::from(name);
+        self.raw_name = None;
+    }
+
+    /// Set the tag name as written in the source document.
+    pub fn set_raw_name(&mut self, raw_name: &str) {
+        self.raw_name = Some(String::from(raw_name));
+    }
+
+    /// Returns the tag name as written in the source document.
+    /// If `<DIV>` is parsed in `ParseMode::Html`, then returns `DIV` while `get_name()` returns `div`.
+    /// If the raw name is not set, returns the same as `get_name()`.
+    pub fn get_raw_name(&self) -> &str {
+        match &self.raw_name {
+            Some(raw_name) => raw_name,
+            None => &self.name,
+        }
+    }
+
+    /// Returns the namespace prefix of the tag name.
+    /// If `<soap:Envelope>`, then returns `soap`. If `<Envelope>`, then returns `None`.
+    pub fn prefix(&self) -> Option<&str> {
+        namespace::split_name(&self.name).0
+    }
+
+    /// Returns the tag name without the namespace prefix.
+    /// If `<soap:Envelope>`, then returns `Envelope`.
+    pub fn local_name(&self) -> &str {
+        namespace::split_name(&self.name).1
+    }
+
+    /// Set the namespace URI.
+    ///
+    /// A needle with the namespace URI matches the tags in the namespace
+    /// with the same local name, regardless of the prefix.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use parsercher::dom::Tag;
+    /// use parsercher::{ParseMode, ParseOptions};
+    ///
+    /// let xml = r#"
+    /// <env:Envelope xmlns:env="http://www.w3.org/2003/05/soap-envelope">
+    ///   <env:Body></env:Body>
+    /// </env:Envelope>
+    /// "#;
+    /// let options = ParseOptions {
+    ///     mode: ParseMode::Xml,
+    ///     ..Default::default()
+    /// };
+    /// let (dom, _) = parsercher::parse_with(&xml, options).unwrap();
+    ///
+    /// let mut needle = Tag::new("soap:Body");
+    /// needle.set_namespace_uri("http://www.w3.org/2003/05/soap-envelope");
+    /// let tags = parsercher::search_tag(&dom, &needle).unwrap();
+    /// assert_eq!(tags[0].get_name(), "env:Body");
+    /// assert_eq!(tags[0].prefix(), Some("env"));
+    /// assert_eq!(tags[0].local_name(), "Body");
+    /// ```
+    pub fn set_namespace_uri(&mut self, namespace_uri: &str) {
+        self.namespace_uri = Some(String::from(namespace_uri));
+    }
+
+    /// Returns the namespace URI resolved from the `xmlns` and `xmlns:prefix` attributes in scope.
+    /// If the tag is not in a namespace, returns `None`.
+    pub fn namespace_uri(&self) -> Option<&str> {
+        self.namespace_uri.as_deref()
+    }
+
     /// Set attributes.
     ///
     /// # Arguments
-    /// * `attr` - Attribute name is key. Attribute value is value.
+    /// * `attrs` - Attributes in order.
     ///
     /// # Examples
     /// For `<h1 id="title" class="section1">`:
     /// ```rust
-    /// use std::collections::HashMap;
+    /// use parsercher::dom::Attrs;
     /// use parsercher::dom::Tag;
     ///
     /// let mut tag = Tag::new("h1");
-    /// let mut attrs: HashMap<String, String> = HashMap::new();
-    /// attrs.insert("id".to_string(), "title".to_string());
-    /// attrs.insert("class".to_string(), "section1".to_string());
+    /// let attrs: Attrs = vec![("id", "title"), ("class", "section1")]
+    ///     .into_iter()
+    ///     .collect();
     /// tag.set_attrs(attrs);
     /// ```
-    pub fn set_attrs(&mut self, attrs: HashMap<String, String>) {
+    pub fn set_attrs(&mut self, attrs: Attrs) {
         self.attrs = Some(attrs);
     }
 
-    /// Returns attributes.
+    /// Returns attributes in source order.
     /// If there is no attribute, `None` is returned.
     ///
     /// # Examples
@@ -66,15 +160,24 @@ impl Tag {
     /// tag.set_attr("class", "section1");
     ///
     /// if let Some(attrs) = tag.get_attrs() {
-    ///     assert_eq!(attrs.get(&"id".to_string()), Some(&"title".to_string()));
-    ///     assert_eq!(attrs.get(&"class".to_string()), Some(&"section1".to_string()));
+    ///     assert_eq!(attrs.get("id"), Some("title"));
+    ///     assert_eq!(attrs.get("class"), Some("section1"));
+    ///
+    ///     let names: Vec<&str> = attrs.iter().map(|(name, _)| name).collect();
+    ///     assert_eq!(names, vec!["id", "class"]);
     /// }
     /// ```
-    pub fn get_attrs(&self) -> Option<&HashMap<String, String>> {
+    pub fn get_attrs(&self) -> Option<&Attrs> {
         self.attrs.as_ref()
     }
 
+    /// Returns the mutable attributes.
+    pub(crate) fn get_attrs_mut(&mut self) -> Option<&mut Attrs> {
+        self.attrs.as_mut()
+    }
+
     /// Set attribute.
+    /// If the attribute exists, its value is replaced. Otherwise it is added to the end.
     ///
     /// # Examples
     /// For `<h1 id="title" class="section1">`:
@@ -86,19 +189,13 @@ impl Tag {
     /// tag.set_attr("class", "section1");
     /// ```
     pub fn set_attr(&mut self, attr: &str, value: &str) {
-        match self.attrs.as_mut() {
-            Some(attrs) => {
-                attrs.insert(String::from(attr), String::from(value));
-            }
-            None => {
-                let mut attrs = HashMap::new();
-                attrs.insert(String::from(attr), String::from(value));
-                self.attrs = Some(attrs);
-            }
-        };
+        self.attrs
+            .get_or_insert_with(Attrs::new)
+            .insert(attr, value);
     }
 
     /// Returns the value of the specified attribute.
+    /// If the attribute is duplicated, the first one is returned.
     ///
     /// # Examples
     /// For `<h1 id="title" class="section1">`:
@@ -114,12 +211,99 @@ impl Tag {
     /// }
     /// ```
     pub fn get_attr(&self, attr: &str) -> Option<String> {
-        if let Some(attrs) = &self.attrs {
-            if let Some(v) = attrs.get(attr) {
-                return Some(v.to_string());
-            }
+        self.attrs
+            .as_ref()
+            .and_then(|attrs| attrs.get(attr))
+            .map(String::from)
+    }
+
+    /// Remove the specified attribute including duplicates.
+    /// Returns the value of the first one.
+    ///
+    /// # Examples
+    /// ```
+    /// use parsercher::dom::Tag;
+    ///
+    /// let mut tag = Tag::new("a");
+    /// tag.set_attr("href", "/");
+    /// tag.set_attr("onclick", "track()");
+    ///
+    /// assert_eq!(tag.remove_attr("onclick"), Some("track()".to_string()));
+    /// assert_eq!(tag.get_attr("onclick"), None);
+    /// ```
+    pub fn remove_attr(&mut self, attr: &str) -> Option<String> {
+        let attrs = self.attrs.as_mut()?;
+        let value = attrs.remove(attr);
+        if attrs.is_empty() {
+            self.attrs = None;
+        }
+        value
+    }
+
+    /// Returns the values of the specified attribute including duplicates in source order.
+    ///
+    /// # Examples
+    /// For `<input class="a" id="i" class="b">`:
+    /// ```
+    /// let html = r#"<input class="a" id="i" class="b">"#;
+    /// let tags = parsercher::search_tag_from_name(&parsercher::parse(&html).unwrap(), "input").unwrap();
+    ///
+    /// assert_eq!(tags[0].get_attr("class"), Some("a".to_string()));
+    /// assert_eq!(tags[0].get_attr_values("class"), vec!["a".to_string(), "b".to_string()]);
+    /// ```
+    pub fn get_attr_values(&self, attr: &str) -> Vec<String> {
+        match &self.attrs {
+            Some(attrs) => attrs.get_all(attr).into_iter().map(String::from).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Returns the value of the attribute with the namespace URI and the local name
+    /// regardless of the prefix.
+    ///
+    /// # Examples
+    /// ```
+    /// let xml = r#"<a xmlns:xl="http://www.w3.org/1999/xlink" xl:href="/page">link</a>"#;
+    /// let dom = parsercher::parse(&xml).unwrap();
+    /// let tag = dom.get_children().unwrap()[0].get_tag().unwrap();
+    ///
+    /// assert_eq!(
+    ///     tag.get_attr_ns("http://www.w3.org/1999/xlink", "href"),
+    ///     Some("/page".to_string())
+    /// );
+    /// ```
+    pub fn get_attr_ns(&self, namespace_uri: &str, local_name: &str) -> Option<String> {
+        self.attrs
+            .as_ref()?
+            .get_ns(namespace_uri, local_name)
+            .map(String::from)
+    }
+
+    /// Set the value of the attribute as written in the source document.
+    /// If the attribute does not exist, nothing is done.
+    pub fn set_raw_attr(&mut self, attr: &str, raw_value: &str) {
+        if let Some(attrs) = self.attrs.as_mut() {
+            attrs.set_raw(attr, raw_value);
         }
-        None
+    }
+
+    /// Returns the value of the specified attribute as written in the source document.
+    /// If the raw value is not set, returns the same as `get_attr()`.
+    ///
+    /// # Examples
+    /// For `<a href="?a=1&amp;b=2">`:
+    /// ```
+    /// let html = r#"<a href="?a=1&amp;b=2"></a>"#;
+    /// let tags = parsercher::search_tag_from_name(&parsercher::parse(&html).unwrap(), "a").unwrap();
+    ///
+    /// assert_eq!(tags[0].get_attr("href"), Some("?a=1&b=2".to_string()));
+    /// assert_eq!(tags[0].get_raw_attr("href"), Some("?a=1&amp;b=2".to_string()));
+    /// ```
+    pub fn get_raw_attr(&self, attr: &str) -> Option<String> {
+        self.attrs
+            .as_ref()
+            .and_then(|attrs| attrs.get_raw(attr))
+            .map(String::from)
     }
 
     /// Set true to represent tags that are self-closed.
@@ -154,12 +338,39 @@ impl Tag {
         self.terminator
     }
 
+    /// Set the range of the tag in the source document.
+    pub fn set_span(&mut self, span: Span) {
+        self.span = Some(span);
+    }
+
+    /// Returns the range of the tag in the source document.
+    /// If `<h1>section</h1>`, then the range of `<h1>`.
+    /// If it was not created by parsing, it returns `None`.
+    pub fn get_span(&self) -> Option<Span> {
+        self.span
+    }
+
+    /// Set the range of the terminator tag in the source document.
+    pub fn set_end_span(&mut self, span: Span) {
+        self.end_span = Some(span);
+    }
+
+    /// Returns the range of the terminator tag in the source document.
+    /// If `<h1>section</h1>`, then the range of `</h1>`.
+    /// If there is no terminator tag, it returns `None`.
+    pub fn get_end_span(&self) -> Option<Span> {
+        self.end_span
+    }
+
     /// Returns true if p is a sufficient condition for q.
     /// `p => q`
     ///
+    /// If p has the namespace URI, the tag and the attributes with the namespace URI
+    /// are compared by the namespace URI and the local name instead of the name,
+    /// and the namespace declarations of p are not compared.
+    ///
     /// # Examples
     /// ```rust
-    /// use std::collections::HashMap;
     /// use parsercher::dom::Tag;
     ///
     /// let mut p = Tag::new("h1");
@@ -179,31 +390,42 @@ impl Tag {
     pub fn p_implies_q(p: &Tag, q: &Tag) -> bool {
         let mut satisfied = false;
         // name
-        if q.get_name() == p.get_name() {
-            satisfied = true;
+        match p.namespace_uri() {
+            Some(p_uri) => {
+                if q.namespace_uri() == Some(p_uri) && q.local_name() == p.local_name() {
+                    satisfied = true;
+                }
+            }
+            None => {
+                if q.get_name() == p.get_name() {
+                    satisfied = true;
+                }
+            }
         }
 
         // attr
         if satisfied {
             if let Some(p_attrs) = p.get_attrs() {
-                match q.get_attrs() {
-                    Some(q_attrs) => {
-                        for (p_key, p_value) in p_attrs.iter() {
-                            match q_attrs.get(p_key) {
-                                Some(q_value) => {
-                                    if p_value != "" && p_value != q_value {
-                                        satisfied = false;
-                                        break;
-                                    }
-                                }
-                                None => {
-                                    satisfied = false;
-                                    break;
-                                }
+                let empty = Attrs::new();
+                let q_attrs = q.get_attrs().unwrap_or(&empty);
+                for (p_key, p_value) in p_attrs.iter() {
+                    let q_value = match p_attrs.get_namespace_uri(p_key) {
+                        Some(namespace::XMLNS) if p.namespace_uri().is_some() => continue,
+                        Some(p_uri) => q_attrs.get_ns(p_uri, namespace::split_name(p_key).1),
+                        None => q_attrs.get(p_key),
+                    };
+                    match q_value {
+                        Some(q_value) => {
+                            if !p_value.is_empty() && p_value != q_value {
+                                satisfied = false;
+                                break;
                             }
                         }
+                        None => {
+                            satisfied = false;
+                            break;
+                        }
                     }
-                    None => satisfied = false,
                 }
             }
         }
@@ -238,7 +460,7 @@ mod tests {
         q.set_attr("id", "q");
         q.set_attr("class", "target");
 
-        assert_eq!(Tag::p_implies_q(&p, &q), true);
+        assert!(Tag::p_implies_q(&p, &q));
     }
 
     #[test]
@@ -249,7 +471,23 @@ mod tests {
         let mut q = Tag::new("h1");
         q.set_attr("id", "q");
 
-        assert_eq!(Tag::p_implies_q(&p, &q), false);
+        assert!(!Tag::p_implies_q(&p, &q));
+    }
+
+    #[test]
+    fn namespace_condition() {
+        let mut p = Tag::new("a:item");
+        p.set_namespace_uri("urn:x");
+
+        let mut q = Tag::new("b:item");
+        q.set_namespace_uri("urn:x");
+        assert!(Tag::p_implies_q(&p, &q));
+
+        q.set_namespace_uri("urn:y");
+        assert!(!Tag::p_implies_q(&p, &q));
+
+        // without the namespace URI, the names are compared
+        assert!(!Tag::p_implies_q(&Tag::new("a:item"), &q));
     }
 
     #[test]
@@ -262,8 +500,8 @@ mod tests {
         b.set_attr("id", "idA");
         b.set_attr("class", "classA");
 
-        assert_eq!(a == b, true);
-        assert_eq!(a != b, false);
+        assert!(a == b);
+        assert!(!(a != b));
     }
 
     #[test]
@@ -276,7 +514,7 @@ mod tests {
         b.set_attr("id", "idB");
         b.set_attr("class", "classB");
 
-        assert_eq!(a == b, false);
-        assert_eq!(a != b, true);
+        assert!(!(a == b));
+        assert!(a != b);
     }
 }
@@ -1,9 +1,18 @@
 //! Module of Text structure.
 
 /// A structure that expresses something other than tags and comments.
-#[derive(Debug, PartialEq, Clone)]
+///
+/// The raw text is not compared by `==`.
+#[derive(Debug, Clone)]
 pub struct Text {
     text: String,
+    raw_text: Option<String>,
+}
+
+impl PartialEq for Text {
+    fn eq(&self, other: &Text) -> bool {
+        self.text == other.text
+    }
 }
 
 impl Text {
@@ -14,14 +23,33 @@ impl Text {
     pub fn new(text: &str) -> Text {
         Text {
             text: String::from(text),
+            raw_text: None,
         }
     }
 
     /// Returns the text.
     /// If `<h1>section</h1>`, then returns `section`.
+    ///
+    /// The character references are decoded by the parser.
+    /// If `<h1>a &amp; b</h1>`, then returns `a & b`.
     pub fn get_text(&self) -> &str {
         &self.text
     }
+
+    /// Set the text as written in the source document.
+    pub fn set_raw_text(&mut self, raw_text: &str) {
+        self.raw_text = Some(String::from(raw_text));
+    }
+
+    /// Returns the text as written in the source document.
+    /// If `<h1>a &amp; b</h1>`, then returns `a &amp; b`.
+    /// If the raw text is not set, returns the same as `get_text()`.
+    pub fn get_raw_text(&self) -> &str {
+        match &self.raw_text {
+            Some(raw_text) => raw_text,
+            None => &self.text,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -32,15 +60,25 @@ mod tests {
     fn eq_test() {
         let a = Text::new("a text");
         let b = Text::new("a text");
-        assert_eq!(a == b, true);
-        assert_eq!(a != b, false);
+        assert!(a == b);
+        assert!(!(a != b));
     }
 
     #[test]
     fn ne_test() {
         let a = Text::new("a text");
         let b = Text::new("b text");
-        assert_eq!(a != b, true);
-        assert_eq!(a == b, false);
+        assert!(a != b);
+        assert!(!(a == b));
+    }
+
+    #[test]
+    fn raw_text_test() {
+        let mut a = Text::new("a & b");
+        assert_eq!(a.get_raw_text(), "a & b");
+        a.set_raw_text("a &amp; b");
+        assert_eq!(a.get_text(), "a & b");
+        assert_eq!(a.get_raw_text(), "a &amp; b");
+        assert!(a == Text::new("a & b"));
     }
 }
@@ -0,0 +1,250 @@
+//! Module of DomTree structure.
+
+use crate::dom::Dom;
+
+/// Id of a node in `DomTree`.
+/// The ids are assigned in document order, so they can be compared to sort nodes.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct NodeId(usize);
+
+/// A node of `DomTree`.
+#[derive(Debug, Clone)]
+struct Node<'a> {
+    dom: &'a Dom,
+    parent: Option<NodeId>,
+    children: Vec<NodeId>,
+    index_in_parent: Option<usize>,
+    /// Id after the last descendant.
+    subtree_end: usize,
+}
+
+/// An index over the Dom structure tree with links to the parents and siblings.
+///
+/// The nodes reference the Dom structures of the original tree, which is not copied.
+/// Each node is identified by `NodeId`.
+///
+/// # Examples
+/// ```rust
+/// use parsercher::dom::DomTree;
+///
+/// let html = r#"<ul><li>first</li><li class="target">second</li><li>third</li></ul>"#;
+/// let dom = parsercher::parse(&html).unwrap();
+/// let tree = DomTree::new(&dom);
+///
+/// let li = dom.select("li.target").unwrap()[0];
+/// let id = tree.find(li).unwrap();
+///
+/// let ul = tree.parent(id).unwrap();
+/// assert_eq!(tree.get(ul).get_tag().unwrap().get_name(), "ul");
+/// assert_eq!(tree.index_in_parent(id), Some(1));
+///
+/// let next = tree.next_sibling(id).unwrap();
+/// assert_eq!(tree.get(next).to_html(), "<li>third</li>");
+/// let prev = tree.prev_sibling(id).unwrap();
+/// assert_eq!(tree.get(prev).to_html(), "<li>first</li>");
+/// ```
+#[derive(Debug, Clone)]
+pub struct DomTree<'a> {
+    nodes: Vec<Node<'a>>,
+}
+
+impl<'a> DomTree<'a> {
+    /// Create the index of the tree whose root is `dom`.
+    pub fn new(dom: &'a Dom) -> DomTree<'a> {
+        let mut tree = DomTree { nodes: Vec::new() };
+        tree.add_node(dom, None, None);
+        tree
+    }
+
+    fn add_node(
+        &mut self,
+        dom: &'a Dom,
+        parent: Option<NodeId>,
+        index_in_parent: Option<usize>,
+    ) -> NodeId {
+        let id = NodeId(self.nodes.len());
+        self.nodes.push(Node {
+            dom,
+            parent,
+            children: Vec::new(),
+            index_in_parent,
+            subtree_end: 0,
+        });
+        if let Some(children) = dom.get_children() {
+            for (i, child) in children.iter().enumerate() {
+                let child = self.add_node(child, Some(id), Some(i));
+                self.nodes[id.0].children.push(child);
+            }
+        }
+        self.nodes[id.0].subtree_end = self.nodes.len();
+        id
+    }
+
+    /// Returns the id of the root.
+    pub fn root(&self) -> NodeId {
+        NodeId(0)
+    }
+
+    /// Returns the number of nodes including the root.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Always returns false because the tree has the root.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Returns the Dom structure of the node.
+    ///
+    /// # Panics
+    /// `id` is not of this tree.
+    pub fn get(&self, id: NodeId) -> &'a Dom {
+        self.nodes[id.0].dom
+    }
+
+    /// Returns the id of the Dom structure.
+    /// `dom` must be a reference into the tree, not a copy. (e.g. the result of `Dom::select()`)
+    /// If it is not in the tree, returns `None`.
+    pub fn find(&self, dom: &Dom) -> Option<NodeId> {
+        self.nodes
+            .iter()
+            .position(|node| std::ptr::eq(node.dom, dom))
+            .map(NodeId)
+    }
+
+    /// Returns the parent of the node.
+    /// The root has no parent.
+    pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+        self.nodes[id.0].parent
+    }
+
+    /// Returns the ancestors of the node from the parent to the root.
+    pub fn ancestors(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        std::iter::successors(self.parent(id), move |id| self.parent(*id))
+    }
+
+    /// Returns the children of the node.
+    pub fn children(&self, id: NodeId) -> &[NodeId] {
+        &self.nodes[id.0].children
+    }
+
+    /// Returns the position of the node in the children of its parent.
+    /// The root returns `None`.
+    pub fn index_in_parent(&self, id: NodeId) -> Option<usize> {
+        self.nodes[id.0].index_in_parent
+    }
+
+    /// Returns the siblings including the node, or an empty slice for the root.
+    fn siblings(&self, id: NodeId) -> &[NodeId] {
+        match self.parent(id) {
+            Some(parent) => self.children(parent),
+            None => &[],
+        }
+    }
+
+    /// Returns the next sibling of the node.
+    pub fn next_sibling(&self, id: NodeId) -> Option<NodeId> {
+        self.following_siblings(id).next()
+    }
+
+    /// Returns the previous sibling of the node.
+    pub fn prev_sibling(&self, id: NodeId) -> Option<NodeId> {
+        self.preceding_siblings(id).next()
+    }
+
+    /// Returns the siblings after the node in document order.
+    pub fn following_siblings(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        let siblings = match self.index_in_parent(id) {
+            Some(i) => &self.siblings(id)[i + 1..],
+            None => &[],
+        };
+        siblings.iter().copied()
+    }
+
+    /// Returns the siblings before the node from the nearest one.
+    pub fn preceding_siblings(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        let siblings = match self.index_in_parent(id) {
+            Some(i) => &self.siblings(id)[..i],
+            None => &[],
+        };
+        siblings.iter().rev().copied()
+    }
+
+    /// Returns the descendants of the node in document order.
+    pub fn descendants(&self, id: NodeId) -> impl Iterator<Item = NodeId> {
+        (id.0 + 1..self.nodes[id.0].subtree_end).map(NodeId)
+    }
+
+    /// Returns the nodes after the node in document order, excluding the descendants.
+    pub(crate) fn following(&self, id: NodeId) -> impl Iterator<Item = NodeId> {
+        (self.nodes[id.0].subtree_end..self.nodes.len()).map(NodeId)
+    }
+
+    /// Returns the nodes before the node in document order, excluding the ancestors.
+    pub(crate) fn preceding(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        (0..id.0)
+            .filter(move |i| self.nodes[*i].subtree_end <= id.0)
+            .map(NodeId)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn name(tree: &DomTree, id: NodeId) -> String {
+        tree.get(id).get_tag().unwrap().get_name().to_string()
+    }
+
+    #[test]
+    fn navigation_test() {
+        let dom = crate::parser::parse("<a><b><c></c></b><d></d><e></e></a>").unwrap();
+        let tree = DomTree::new(&dom);
+        assert_eq!(tree.len(), 6);
+
+        let a = tree.children(tree.root())[0];
+        let b = tree.children(a)[0];
+        let c = tree.children(b)[0];
+        let d = tree.next_sibling(b).unwrap();
+        assert_eq!(name(&tree, d), "d");
+
+        let ancestors: Vec<String> = tree.ancestors(c).map(|id| name(&tree, id)).collect();
+        assert_eq!(ancestors, vec!["b", "a", "root"]);
+
+        let following: Vec<String> = tree
+            .following_siblings(b)
+            .map(|id| name(&tree, id))
+            .collect();
+        assert_eq!(following, vec!["d", "e"]);
+        let e = tree.children(a)[2];
+        let preceding: Vec<String> = tree
+            .preceding_siblings(e)
+            .map(|id| name(&tree, id))
+            .collect();
+        assert_eq!(preceding, vec!["d", "b"]);
+
+        assert_eq!(tree.prev_sibling(b), None);
+        assert_eq!(tree.next_sibling(tree.root()), None);
+        assert_eq!(tree.index_in_parent(tree.root()), None);
+        assert_eq!(tree.index_in_parent(e), Some(2));
+    }
+
+    #[test]
+    fn document_order_test() {
+        let dom = crate::parser::parse("<a><b><c></c></b><d></d></a>").unwrap();
+        let tree = DomTree::new(&dom);
+        let ids: Vec<String> = tree
+            .descendants(tree.root())
+            .map(|id| name(&tree, id))
+            .collect();
+        assert_eq!(ids, vec!["a", "b", "c", "d"]);
+
+        let c = tree.descendants(tree.root()).nth(2).unwrap();
+        let following: Vec<String> = tree.following(c).map(|id| name(&tree, id)).collect();
+        assert_eq!(following, vec!["d"]);
+        let d = tree.following(c).next().unwrap();
+        let preceding: Vec<String> = tree.preceding(d).map(|id| name(&tree, id)).collect();
+        assert_eq!(preceding, vec!["b", "c"]);
+    }
+}
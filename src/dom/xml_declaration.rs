@@ -0,0 +1,147 @@
+//! Module of XmlDeclaration structure.
+
+use std::fmt;
+
+use crate::dom::ProcessingInstruction;
+
+/// A structure that represents the XML declaration.
+/// grammar: `<?xml version="1.0" [encoding="encoding"] [standalone="yes|no"]?>`
+///
+/// In the Dom structure tree, it is a processing instruction whose target is `xml`.
+/// `Display` writes it as a declaration.
+///
+/// # Examples
+/// ```rust
+/// use parsercher::dom::XmlDeclaration;
+///
+/// let mut declaration = XmlDeclaration::new("1.0");
+/// declaration.set_encoding("UTF-8");
+/// declaration.set_standalone(true);
+/// assert_eq!(
+///     declaration.to_string(),
+///     r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#
+/// );
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+pub struct XmlDeclaration {
+    version: String,
+    encoding: Option<String>,
+    standalone: Option<bool>,
+}
+
+impl XmlDeclaration {
+    /// Create new XmlDeclaration structure.
+    ///
+    /// # Arguments
+    /// * `version` - If `<?xml version="1.0"?>`, then `1.0`.
+    pub fn new(version: &str) -> XmlDeclaration {
+        XmlDeclaration {
+            version: String::from(version),
+            encoding: None,
+            standalone: None,
+        }
+    }
+
+    /// Parse the data of the processing instruction. e.g. `version="1.0" encoding="UTF-8"`
+    /// Returns `None` if there is no version.
+    pub(crate) fn parse(data: &str) -> Option<XmlDeclaration> {
+        let mut version = None;
+        let mut encoding = None;
+        let mut standalone = None;
+
+        let mut rest = data.trim_start();
+        while let Some(eq) = rest.find('=') {
+            let name = rest[..eq].trim();
+            let value = rest[eq + 1..].trim_start();
+            let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+            let value = &value[1..];
+            let end = value.find(quote)?;
+            match name {
+                "version" => version = Some(&value[..end]),
+                "encoding" => encoding = Some(&value[..end]),
+                "standalone" => standalone = Some(&value[..end] == "yes"),
+                _ => (),
+            }
+            rest = value[end + 1..].trim_start();
+        }
+
+        let mut declaration = XmlDeclaration::new(version?);
+        if let Some(encoding) = encoding {
+            declaration.set_encoding(encoding);
+        }
+        if let Some(standalone) = standalone {
+            declaration.set_standalone(standalone);
+        }
+        Some(declaration)
+    }
+
+    /// Returns the version.
+    /// If `<?xml version="1.0"?>`, then returns `1.0`.
+    pub fn get_version(&self) -> &str {
+        &self.version
+    }
+
+    /// Set the encoding.
+    pub fn set_encoding(&mut self, encoding: &str) {
+        self.encoding = Some(String::from(encoding));
+    }
+
+    /// Returns the encoding.
+    /// If `<?xml version="1.0" encoding="UTF-8"?>`, then returns `UTF-8`.
+    pub fn get_encoding(&self) -> Option<&str> {
+        self.encoding.as_deref()
+    }
+
+    /// Set the standalone document declaration.
+    pub fn set_standalone(&mut self, standalone: bool) {
+        self.standalone = Some(standalone);
+    }
+
+    /// Returns the standalone document declaration.
+    /// If `<?xml version="1.0" standalone="yes"?>`, then returns `true`.
+    pub fn get_standalone(&self) -> Option<bool> {
+        self.standalone
+    }
+
+    /// Returns the processing instruction that represents the declaration.
+    /// It can be added to the Dom structure tree as `DomType::ProcessingInstruction`.
+    pub fn to_processing_instruction(&self) -> ProcessingInstruction {
+        let mut data = format!("version=\"{}\"", self.version);
+        if let Some(encoding) = &self.encoding {
+            data.push_str(&format!(" encoding=\"{}\"", encoding));
+        }
+        if let Some(standalone) = self.standalone {
+            let standalone = if standalone { "yes" } else { "no" };
+            data.push_str(&format!(" standalone=\"{}\"", standalone));
+        }
+        ProcessingInstruction::new("xml", &data)
+    }
+}
+
+impl fmt::Display for XmlDeclaration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<?xml {}?>", self.to_processing_instruction().get_data())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_test() {
+        let declaration =
+            XmlDeclaration::parse(r#"version='1.1' encoding = "Shift_JIS" standalone="no""#)
+                .unwrap();
+        assert_eq!(declaration.get_version(), "1.1");
+        assert_eq!(declaration.get_encoding(), Some("Shift_JIS"));
+        assert_eq!(declaration.get_standalone(), Some(false));
+        assert_eq!(
+            declaration.to_string(),
+            r#"<?xml version="1.1" encoding="Shift_JIS" standalone="no"?>"#
+        );
+
+        assert_eq!(XmlDeclaration::parse(r#"encoding="UTF-8""#), None);
+        assert_eq!(XmlDeclaration::parse(r#"version="1.0"#), None);
+    }
+}
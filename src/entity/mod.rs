@@ -0,0 +1,306 @@
+//! Module for decoding character references. (e.g. `&amp;`, `&#39;`, `&#x27;`)
+
+mod table;
+
+use std::collections::HashMap;
+
+use table::ENTITIES;
+
+/// The longest name in the table of the named character references.
+const MAX_NAME_LEN: usize = 32;
+
+/// The predefined entities of XML.
+const XML_ENTITIES: [(&str, &str); 5] = [
+    ("amp", "&"),
+    ("apos", "'"),
+    ("gt", ">"),
+    ("lt", "<"),
+    ("quot", "\""),
+];
+
+/// Replacements of the numeric character references in the range 0x80 to 0x9F.
+const C1_REPLACEMENTS: [(u32, char); 27] = [
+    (0x80, '\u{20AC}'),
+    (0x82, '\u{201A}'),
+    (0x83, '\u{0192}'),
+    (0x84, '\u{201E}'),
+    (0x85, '\u{2026}'),
+    (0x86, '\u{2020}'),
+    (0x87, '\u{2021}'),
+    (0x88, '\u{02C6}'),
+    (0x89, '\u{2030}'),
+    (0x8A, '\u{0160}'),
+    (0x8B, '\u{2039}'),
+    (0x8C, '\u{0152}'),
+    (0x8E, '\u{017D}'),
+    (0x91, '\u{2018}'),
+    (0x92, '\u{2019}'),
+    (0x93, '\u{201C}'),
+    (0x94, '\u{201D}'),
+    (0x95, '\u{2022}'),
+    (0x96, '\u{2013}'),
+    (0x97, '\u{2014}'),
+    (0x98, '\u{02DC}'),
+    (0x99, '\u{2122}'),
+    (0x9A, '\u{0161}'),
+    (0x9B, '\u{203A}'),
+    (0x9C, '\u{0153}'),
+    (0x9E, '\u{017E}'),
+    (0x9F, '\u{0178}'),
+];
+
+/// Decodes the character references in `s` according to the HTML5 rules.
+///
+/// # Arguments
+/// * `s` - text or attribute value.
+/// * `in_attr` - If true, `s` is an attribute value. A legacy reference without ';'
+///   followed by '=' or an alphanumeric character is not decoded. (e.g. `?a=1&copy=2`)
+pub fn decode(s: &str, in_attr: bool) -> String {
+    if !s.contains('&') {
+        return String::from(s);
+    }
+
+    let mut res = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(i) = rest.find('&') {
+        res.push_str(&rest[..i]);
+        rest = &rest[i + 1..]; // after '&'
+        match decode_reference(rest, in_attr) {
+            Some((decoded, len)) => {
+                res.push_str(&decoded);
+                rest = &rest[len..];
+            }
+            None => res.push('&'),
+        }
+    }
+    res.push_str(rest);
+    res
+}
+
+/// Decodes the character references and the entity references in `s` according to the XML rules.
+/// A reference must end with ';', and a named reference must be a predefined entity
+/// or one of `entities` declared in the document type declaration.
+///
+/// # Errors
+/// Returns the character index of the '&' and the name of the first reference
+/// that is not defined or not terminated.
+pub fn decode_xml(s: &str, entities: &HashMap<String, String>) -> Result<String, (usize, String)> {
+    if !s.contains('&') {
+        return Ok(String::from(s));
+    }
+
+    let mut res = String::with_capacity(s.len());
+    let mut rest = s;
+    let mut index = 0; // character index of `rest` in `s`
+    while let Some(i) = rest.find('&') {
+        res.push_str(&rest[..i]);
+        index += rest[..i].chars().count();
+        rest = &rest[i + 1..]; // after '&'
+
+        let end = rest
+            .find(|c: char| c == ';' || c == '&' || c.is_whitespace())
+            .unwrap_or(rest.len());
+        let name = &rest[..end];
+        if !rest[end..].starts_with(';') {
+            return Err((index, String::from(name)));
+        }
+        let decoded = match name.strip_prefix('#') {
+            Some(code) => {
+                let (digits, radix) = match code.strip_prefix('x') {
+                    Some(hex) => (hex, 16),
+                    None => (code, 10),
+                };
+                if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+                    None
+                } else {
+                    u32::from_str_radix(digits, radix)
+                        .ok()
+                        .and_then(char::from_u32)
+                        .filter(|c| is_xml_char(*c))
+                        .map(String::from)
+                }
+            }
+            None => XML_ENTITIES
+                .iter()
+                .find(|(n, _)| *n == name)
+                .map(|(_, value)| String::from(*value))
+                .or_else(|| entities.get(name).cloned()),
+        };
+        match decoded {
+            Some(decoded) => res.push_str(&decoded),
+            None => return Err((index, String::from(name))),
+        }
+        index += name.chars().count() + 2;
+        rest = &rest[name.len() + 1..]; // after ';'
+    }
+    res.push_str(rest);
+    Ok(res)
+}
+
+/// Returns true if `c` matches the `Char` production of XML.
+fn is_xml_char(c: char) -> bool {
+    matches!(c, '\u{9}' | '\u{A}' | '\u{D}' | '\u{20}'..='\u{D7FF}' | '\u{E000}'..='\u{FFFD}' | '\u{10000}'..)
+}
+
+/// Decodes the character reference at the beginning of `s`.
+/// `s` is the string after '&'.
+/// Returns the decoded string and the length of `s` consumed.
+fn decode_reference(s: &str, in_attr: bool) -> Option<(String, usize)> {
+    if let Some(s) = s.strip_prefix('#') {
+        let (c, len) = decode_numeric(s)?;
+        return Some((c.to_string(), len + 1));
+    }
+    decode_named(s, in_attr)
+}
+
+/// Decodes the numeric character reference. (e.g. `39;`, `x27;`)
+/// `s` is the string after '#'.
+fn decode_numeric(s: &str) -> Option<(char, usize)> {
+    let (radix, prefix_len) = match s.chars().next() {
+        Some('x') | Some('X') => (16, 1),
+        _ => (10, 0),
+    };
+    let digits_len = s[prefix_len..]
+        .chars()
+        .take_while(|c| c.is_digit(radix))
+        .count();
+    if digits_len == 0 {
+        return None;
+    }
+
+    let mut len = prefix_len + digits_len;
+    let digits = &s[prefix_len..len];
+    if s[len..].starts_with(';') {
+        len += 1;
+    }
+
+    let code = u32::from_str_radix(digits, radix).unwrap_or(u32::MAX);
+    Some((code_to_char(code), len))
+}
+
+/// Converts the code point of the numeric character reference to a character.
+fn code_to_char(code: u32) -> char {
+    if code == 0 {
+        return '\u{FFFD}';
+    }
+    if let Some((_, c)) = C1_REPLACEMENTS.iter().find(|(n, _)| *n == code) {
+        return *c;
+    }
+    // Surrogates and the values out of range are also replaced.
+    char::from_u32(code).unwrap_or('\u{FFFD}')
+}
+
+/// Decodes the named character reference. (e.g. `amp;`, `eacute;`, `nbsp`)
+/// The longest name in the table is used.
+fn decode_named(s: &str, in_attr: bool) -> Option<(String, usize)> {
+    let name_len = s
+        .chars()
+        .take(MAX_NAME_LEN)
+        .take_while(|c| c.is_ascii_alphanumeric())
+        .count();
+    if name_len == 0 {
+        return None;
+    }
+
+    // with ';'
+    if s[name_len..].starts_with(';') {
+        if let Some(value) = lookup(&s[..name_len + 1]) {
+            return Some((String::from(value), name_len + 1));
+        }
+    }
+
+    // legacy references without ';'
+    for len in (1..=name_len).rev() {
+        if let Some(value) = lookup(&s[..len]) {
+            // The next character is an alphanumeric character or '='
+            let followed = len < name_len || s[len..].starts_with('=');
+            if in_attr && followed {
+                return None;
+            }
+            return Some((String::from(value), len));
+        }
+    }
+    None
+}
+
+/// Returns the value of the named character reference.
+fn lookup(name: &str) -> Option<&'static str> {
+    match ENTITIES.binary_search_by(|(n, _)| (*n).cmp(name)) {
+        Ok(i) => Some(ENTITIES[i].1),
+        Err(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table_is_sorted() {
+        for w in ENTITIES.windows(2) {
+            assert!(w[0].0 < w[1].0, "{} {}", w[0].0, w[1].0);
+        }
+    }
+
+    #[test]
+    fn decode_named_test() {
+        assert_eq!(decode("a &amp; b", false), "a & b");
+        assert_eq!(decode("&lt;p&gt;", false), "<p>");
+        assert_eq!(decode("caf&eacute;&nbsp;!", false), "caf\u{e9}\u{a0}!");
+        assert_eq!(decode("&notin; &notit;", false), "\u{2209} \u{ac}it;");
+        assert_eq!(decode("&NotEqualTilde;", false), "\u{2242}\u{338}");
+        assert_eq!(decode("&unknown; & &;", false), "&unknown; & &;");
+    }
+
+    #[test]
+    fn decode_numeric_test() {
+        assert_eq!(decode("&#39;&#x27;&#X27", false), "'''");
+        assert_eq!(decode("&#128512;", false), "\u{1F600}");
+        assert_eq!(
+            decode("&#0;&#xD800;&#99999999999;", false),
+            "\u{FFFD}\u{FFFD}\u{FFFD}"
+        );
+        assert_eq!(decode("&#x80;&#150;", false), "\u{20AC}\u{2013}");
+        assert_eq!(decode("&#; &#x;", false), "&#; &#x;");
+    }
+
+    #[test]
+    fn decode_xml_test() {
+        let mut entities = HashMap::new();
+        entities.insert(String::from("writer"), String::from("Tove"));
+        assert_eq!(
+            decode_xml("&lt;a&gt; &amp; &#39;&#x27; &writer;", &entities),
+            Ok(String::from("<a> & '' Tove"))
+        );
+        assert_eq!(
+            decode_xml("caf\u{e9} &eacute;", &entities),
+            Err((5, String::from("eacute")))
+        );
+        assert_eq!(decode_xml("a & b", &entities), Err((2, String::new())));
+        assert_eq!(
+            decode_xml("&#xZZ;", &entities),
+            Err((0, String::from("#xZZ")))
+        );
+        for name in [
+            "#+65", "#x+41", "#-1", "#", "#x", "#0", "#x1F", "#xFFFE", "#xD800",
+        ] {
+            assert_eq!(
+                decode_xml(&format!("&{};", name), &entities),
+                Err((0, String::from(name)))
+            );
+        }
+        assert_eq!(
+            decode_xml("&#9;&#x10FFFF;", &entities),
+            Ok(String::from("\t\u{10FFFF}"))
+        );
+    }
+
+    #[test]
+    fn decode_attr_test() {
+        assert_eq!(decode("?a=1&copy=2", true), "?a=1&copy=2");
+        assert_eq!(decode("?a=1&copy=2", false), "?a=1\u{a9}=2");
+        assert_eq!(decode("&copyright", true), "&copyright");
+        assert_eq!(decode("&copy;right", true), "\u{a9}right");
+        assert_eq!(decode("&amp &copy", true), "& \u{a9}");
+    }
+}
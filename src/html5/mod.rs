@@ -0,0 +1,94 @@
+//! Module of the HTML5 parser following the WHATWG parsing algorithm.
+//! <https://html.spec.whatwg.org/multipage/parsing.html>
+//!
+//! Unlike `parser`, it never fails and builds the same tree as web browsers.
+//! Scripting is treated as enabled, so the content of `<noscript>` is text.
+
+mod names;
+mod tokenizer;
+mod tree_builder;
+
+use crate::dom::namespace;
+use crate::dom::Attrs;
+use crate::dom::Comment;
+use crate::dom::Dom;
+use crate::dom::DomType;
+use crate::dom::Tag;
+use crate::dom::Text;
+
+use tree_builder::Namespace;
+use tree_builder::Node;
+use tree_builder::NodeData;
+
+/// Parses an HTML document with the HTML5 tree construction algorithm,
+/// and returns the Dom structure tree.
+///
+/// The `<html>`, `<head>` and `<body>` elements are always created.
+/// The namespace URI of each tag is the HTML, SVG or MathML namespace.
+/// Misnested tags are fixed up as web browsers do.
+/// (e.g. `<b><i></b></i>`, text directly in `<table>`)
+///
+/// # Examples
+/// ```rust
+/// let dom = parsercher::parse_html5("<p>a<b>b<p>c</b>d");
+/// assert_eq!(
+///     dom.to_html(),
+///     "<html><head></head><body><p>a<b>b</b></p><p><b>c</b>d</p></body></html>"
+/// );
+/// ```
+pub fn parse_html5(doc: &str) -> Dom {
+    let nodes = tree_builder::build(doc);
+    let mut root = Dom::new_root();
+    for child in nodes[tree_builder::DOCUMENT].children.iter() {
+        if let Some(dom) = to_dom(&nodes, *child) {
+            root.add_child(dom);
+        }
+    }
+    root
+}
+
+/// Converts the node and its descendants to a Dom structure.
+fn to_dom(nodes: &[Node], id: usize) -> Option<Dom> {
+    let node = &nodes[id];
+    match &node.data {
+        NodeData::Element { name, attrs, ns } => {
+            let mut tag = Tag::new(name);
+            tag.set_namespace_uri(match ns {
+                Namespace::Html => namespace::HTML,
+                Namespace::Svg => namespace::SVG,
+                Namespace::MathMl => namespace::MATHML,
+            });
+            if !attrs.is_empty() {
+                let mut tag_attrs = Attrs::new();
+                for (attr, value) in attrs.iter() {
+                    tag_attrs.push(attr, value);
+                }
+                tag.set_attrs(tag_attrs);
+            }
+            let mut dom = Dom::new(DomType::Tag);
+            dom.set_tag(tag);
+            for child in node.children.iter() {
+                if let Some(child) = to_dom(nodes, *child) {
+                    dom.add_child(child);
+                }
+            }
+            Some(dom)
+        }
+        NodeData::Text(text) => {
+            let mut dom = Dom::new(DomType::Text);
+            dom.set_text(Text::new(text));
+            Some(dom)
+        }
+        NodeData::Comment(comment) => {
+            let mut dom = Dom::new(DomType::Comment);
+            dom.set_comment(Comment::new(comment));
+            Some(dom)
+        }
+        NodeData::Doctype(doctype) => {
+            let mut dom = Dom::new(DomType::Doctype);
+            dom.set_doctype(doctype.clone());
+            Some(dom)
+        }
+        NodeData::Document => None,
+    }
+}
@@ -0,0 +1,931 @@
+//! Module of the HTML5 tokenizer.
+//! <https://html.spec.whatwg.org/multipage/parsing.html#tokenization>
+
+use std::collections::VecDeque;
+
+use crate::entity;
+
+/// A token of the HTML5 tokenizer.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Token {
+    Doctype {
+        name: Option<String>,
+        public_id: Option<String>,
+        system_id: Option<String>,
+        force_quirks: bool,
+    },
+    StartTag {
+        name: String,
+        attrs: Vec<(String, String)>,
+        self_closing: bool,
+    },
+    EndTag {
+        name: String,
+    },
+    Comment(String),
+    /// Characters. The character references are decoded.
+    Text(String),
+    Eof,
+}
+
+/// States of the tokenizer.
+/// The tree builder switches the state for the content of `<title>`, `<style>`, `<script>`, etc.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum State {
+    Data,
+    Rcdata,
+    Rawtext,
+    ScriptData,
+    Plaintext,
+    TagOpen,
+    EndTagOpen,
+    TagName,
+    /// `<` in RCDATA, RAWTEXT or script data.
+    RawLessThanSign(RawKind),
+    RawEndTagOpen(RawKind),
+    RawEndTagName(RawKind),
+    BeforeAttributeName,
+    AttributeName,
+    AfterAttributeName,
+    BeforeAttributeValue,
+    AttributeValueDoubleQuoted,
+    AttributeValueSingleQuoted,
+    AttributeValueUnquoted,
+    AfterAttributeValueQuoted,
+    SelfClosingStartTag,
+    BogusComment,
+    MarkupDeclarationOpen,
+    CommentStart,
+    CommentStartDash,
+    Comment,
+    CommentEndDash,
+    CommentEnd,
+    CommentEndBang,
+    Doctype,
+    BeforeDoctypeName,
+    DoctypeName,
+    AfterDoctypeName,
+    AfterDoctypePublicKeyword,
+    BeforeDoctypePublicIdentifier,
+    DoctypePublicIdentifier(char),
+    AfterDoctypePublicIdentifier,
+    BetweenDoctypePublicAndSystemIdentifiers,
+    AfterDoctypeSystemKeyword,
+    BeforeDoctypeSystemIdentifier,
+    DoctypeSystemIdentifier(char),
+    AfterDoctypeSystemIdentifier,
+    BogusDoctype,
+}
+
+/// The text states that end only with the appropriate end tag.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum RawKind {
+    Rcdata,
+    Rawtext,
+    ScriptData,
+}
+
+impl RawKind {
+    fn state(self) -> State {
+        match self {
+            RawKind::Rcdata => State::Rcdata,
+            RawKind::Rawtext => State::Rawtext,
+            RawKind::ScriptData => State::ScriptData,
+        }
+    }
+}
+
+/// The tag being tokenized.
+#[derive(Debug, Default)]
+struct TagBuilder {
+    end: bool,
+    name: String,
+    attrs: Vec<(String, String)>,
+    self_closing: bool,
+    /// The current attribute is a duplicate and is dropped.
+    duplicate: bool,
+}
+
+/// The doctype being tokenized.
+#[derive(Debug, Default)]
+struct DoctypeBuilder {
+    name: Option<String>,
+    public_id: Option<String>,
+    system_id: Option<String>,
+    force_quirks: bool,
+}
+
+/// The HTML5 tokenizer.
+pub struct Tokenizer {
+    input: Vec<char>,
+    pos: usize,
+    state: State,
+    tokens: VecDeque<Token>,
+    /// Characters not emitted yet.
+    text: String,
+    /// If true, the character references in `text` are decoded.
+    text_decode: bool,
+    tag: TagBuilder,
+    comment: String,
+    doctype: DoctypeBuilder,
+    /// Name of the last start tag, for the appropriate end tag.
+    last_start_tag: String,
+    /// Characters consumed by the end tag name in RCDATA, RAWTEXT or script data.
+    temp: String,
+    eof: bool,
+}
+
+fn is_whitespace(c: char) -> bool {
+    matches!(c, '\t' | '\n' | '\x0C' | ' ')
+}
+
+impl Tokenizer {
+    /// Create the tokenizer of `doc`.
+    /// CR and CRLF are normalized to LF.
+    pub fn new(doc: &str) -> Tokenizer {
+        let doc = doc.replace("\r\n", "\n").replace('\r', "\n");
+        Tokenizer {
+            input: doc.chars().collect(),
+            pos: 0,
+            state: State::Data,
+            tokens: VecDeque::new(),
+            text: String::new(),
+            text_decode: true,
+            tag: TagBuilder::default(),
+            comment: String::new(),
+            doctype: DoctypeBuilder::default(),
+            last_start_tag: String::new(),
+            temp: String::new(),
+            eof: false,
+        }
+    }
+
+    /// Switch the state. Used by the tree builder.
+    pub fn set_state(&mut self, state: State) {
+        self.state = state;
+    }
+
+    /// Returns the next token. After the end of the input, `Token::Eof` is returned.
+    pub fn next_token(&mut self) -> Token {
+        while self.tokens.is_empty() {
+            if self.eof {
+                return Token::Eof;
+            }
+            self.step();
+        }
+        self.tokens.pop_front().unwrap()
+    }
+
+    fn consume(&mut self) -> Option<char> {
+        let c = self.input.get(self.pos).copied();
+        self.pos += 1;
+        c
+    }
+
+    fn reconsume(&mut self, state: State) {
+        self.pos -= 1;
+        self.state = state;
+    }
+
+    /// Returns true and consumes `s` if the input continues with `s`. ASCII case insensitive.
+    fn consume_str_insensitive(&mut self, s: &str) -> bool {
+        let len = s.chars().count();
+        if self.input.len() < self.pos + len {
+            return false;
+        }
+        let matched = s
+            .chars()
+            .zip(self.input[self.pos..self.pos + len].iter())
+            .all(|(a, b)| a.eq_ignore_ascii_case(b));
+        if matched {
+            self.pos += len;
+        }
+        matched
+    }
+
+    fn push_text(&mut self, c: char) {
+        self.text.push(c);
+    }
+
+    fn flush_text(&mut self) {
+        if self.text.is_empty() {
+            return;
+        }
+        let text = std::mem::take(&mut self.text);
+        let text = if self.text_decode {
+            entity::decode(&text, false)
+        } else {
+            text
+        };
+        self.tokens.push_back(Token::Text(text));
+    }
+
+    fn emit(&mut self, token: Token) {
+        self.flush_text();
+        self.tokens.push_back(token);
+    }
+
+    fn emit_eof(&mut self) {
+        self.flush_text();
+        self.eof = true;
+    }
+
+    fn new_tag(&mut self, end: bool) {
+        self.tag = TagBuilder {
+            end,
+            ..Default::default()
+        };
+    }
+
+    fn emit_tag(&mut self) {
+        self.end_attr_name();
+        let tag = std::mem::take(&mut self.tag);
+        if tag.end {
+            self.emit(Token::EndTag { name: tag.name });
+        } else {
+            self.last_start_tag = tag.name.clone();
+            let attrs = tag
+                .attrs
+                .into_iter()
+                .map(|(name, value)| {
+                    let value = entity::decode(&value, true);
+                    (name, value)
+                })
+                .collect();
+            self.emit(Token::StartTag {
+                name: tag.name,
+                attrs,
+                self_closing: tag.self_closing,
+            });
+        }
+    }
+
+    fn new_attr(&mut self) {
+        self.end_attr_name();
+        self.tag.attrs.push((String::new(), String::new()));
+        self.tag.duplicate = false;
+    }
+
+    /// Drops the current attribute if its name is a duplicate.
+    fn end_attr_name(&mut self) {
+        if self.tag.duplicate {
+            self.tag.attrs.pop();
+            self.tag.duplicate = false;
+        }
+    }
+
+    /// Checks the name of the current attribute when it is complete.
+    fn check_attr_name(&mut self) {
+        if let Some((name, _)) = self.tag.attrs.last() {
+            let n = self.tag.attrs.len() - 1;
+            self.tag.duplicate = self.tag.attrs[..n].iter().any(|(other, _)| other == name);
+        }
+    }
+
+    fn push_attr_name(&mut self, c: char) {
+        if let Some((name, _)) = self.tag.attrs.last_mut() {
+            name.push(c);
+        }
+    }
+
+    fn push_attr_value(&mut self, c: char) {
+        if let Some((_, value)) = self.tag.attrs.last_mut() {
+            value.push(c);
+        }
+    }
+
+    fn emit_comment(&mut self) {
+        let comment = std::mem::take(&mut self.comment);
+        self.emit(Token::Comment(comment));
+    }
+
+    fn emit_doctype(&mut self) {
+        let doctype = std::mem::take(&mut self.doctype);
+        self.emit(Token::Doctype {
+            name: doctype.name,
+            public_id: doctype.public_id,
+            system_id: doctype.system_id,
+            force_quirks: doctype.force_quirks,
+        });
+    }
+
+    fn emit_doctype_quirks(&mut self) {
+        self.doctype.force_quirks = true;
+        self.emit_doctype();
+    }
+
+    fn is_appropriate_end_tag(&self) -> bool {
+        self.tag.end && self.tag.name == self.last_start_tag
+    }
+
+    /// Runs one step of the state machine.
+    fn step(&mut self) {
+        let c = self.consume();
+        match self.state {
+            State::Data => match c {
+                Some('<') => self.state = State::TagOpen,
+                Some('\0') => (),
+                Some(c) => {
+                    self.text_decode = true;
+                    self.push_text(c);
+                }
+                None => self.emit_eof(),
+            },
+            State::Rcdata | State::Rawtext | State::ScriptData => {
+                let kind = match self.state {
+                    State::Rcdata => RawKind::Rcdata,
+                    State::Rawtext => RawKind::Rawtext,
+                    _ => RawKind::ScriptData,
+                };
+                match c {
+                    Some('<') => self.state = State::RawLessThanSign(kind),
+                    Some('\0') => self.push_text('\u{FFFD}'),
+                    Some(c) => {
+                        self.text_decode = kind == RawKind::Rcdata;
+                        self.push_text(c);
+                    }
+                    None => self.emit_eof(),
+                }
+            }
+            State::Plaintext => match c {
+                Some('\0') => self.push_text('\u{FFFD}'),
+                Some(c) => {
+                    self.text_decode = false;
+                    self.push_text(c);
+                }
+                None => self.emit_eof(),
+            },
+            State::TagOpen => match c {
+                Some('!') => self.state = State::MarkupDeclarationOpen,
+                Some('/') => self.state = State::EndTagOpen,
+                Some(c) if c.is_ascii_alphabetic() => {
+                    self.new_tag(false);
+                    self.reconsume(State::TagName);
+                }
+                Some('?') => {
+                    self.comment.clear();
+                    self.reconsume(State::BogusComment);
+                }
+                Some(_) => {
+                    self.push_text('<');
+                    self.reconsume(State::Data);
+                }
+                None => {
+                    self.push_text('<');
+                    self.emit_eof();
+                }
+            },
+            State::EndTagOpen => match c {
+                Some(c) if c.is_ascii_alphabetic() => {
+                    self.new_tag(true);
+                    self.reconsume(State::TagName);
+                }
+                Some('>') => self.state = State::Data,
+                Some(_) => {
+                    self.comment.clear();
+                    self.reconsume(State::BogusComment);
+                }
+                None => {
+                    self.push_text('<');
+                    self.push_text('/');
+                    self.emit_eof();
+                }
+            },
+            State::TagName => match c {
+                Some(c) if is_whitespace(c) => self.state = State::BeforeAttributeName,
+                Some('/') => self.state = State::SelfClosingStartTag,
+                Some('>') => {
+                    self.state = State::Data;
+                    self.emit_tag();
+                }
+                Some('\0') => self.tag.name.push('\u{FFFD}'),
+                Some(c) => self.tag.name.push(c.to_ascii_lowercase()),
+                None => self.emit_eof(),
+            },
+            State::RawLessThanSign(kind) => match c {
+                Some('/') => {
+                    self.temp.clear();
+                    self.state = State::RawEndTagOpen(kind);
+                }
+                _ => {
+                    self.push_text('<');
+                    self.reconsume(kind.state());
+                }
+            },
+            State::RawEndTagOpen(kind) => match c {
+                Some(c) if c.is_ascii_alphabetic() => {
+                    self.new_tag(true);
+                    self.reconsume(State::RawEndTagName(kind));
+                }
+                _ => {
+                    self.push_text('<');
+                    self.push_text('/');
+                    self.reconsume(kind.state());
+                }
+            },
+            State::RawEndTagName(kind) => {
+                match c {
+                    Some(c) if is_whitespace(c) && self.is_appropriate_end_tag() => {
+                        self.state = State::BeforeAttributeName;
+                        return;
+                    }
+                    Some('/') if self.is_appropriate_end_tag() => {
+                        self.state = State::SelfClosingStartTag;
+                        return;
+                    }
+                    Some('>') if self.is_appropriate_end_tag() => {
+                        self.state = State::Data;
+                        self.emit_tag();
+                        return;
+                    }
+                    Some(c) if c.is_ascii_alphabetic() => {
+                        self.tag.name.push(c.to_ascii_lowercase());
+                        self.temp.push(c);
+                        return;
+                    }
+                    _ => (),
+                }
+                // not the end tag
+                self.push_text('<');
+                self.push_text('/');
+                let temp = std::mem::take(&mut self.temp);
+                for c in temp.chars() {
+                    self.push_text(c);
+                }
+                self.reconsume(kind.state());
+            }
+            State::BeforeAttributeName => match c {
+                Some(c) if is_whitespace(c) => (),
+                Some('/') | Some('>') | None => self.reconsume(State::AfterAttributeName),
+                Some('=') => {
+                    self.new_attr();
+                    self.push_attr_name('=');
+                    self.state = State::AttributeName;
+                }
+                Some(_) => {
+                    self.new_attr();
+                    self.reconsume(State::AttributeName);
+                }
+            },
+            State::AttributeName => match c {
+                Some(c) if is_whitespace(c) || c == '/' || c == '>' => {
+                    self.check_attr_name();
+                    self.reconsume(State::AfterAttributeName);
+                }
+                None => {
+                    self.check_attr_name();
+                    self.reconsume(State::AfterAttributeName);
+                }
+                Some('=') => {
+                    self.check_attr_name();
+                    self.state = State::BeforeAttributeValue;
+                }
+                Some('\0') => self.push_attr_name('\u{FFFD}'),
+                Some(c) => self.push_attr_name(c.to_ascii_lowercase()),
+            },
+            State::AfterAttributeName => match c {
+                Some(c) if is_whitespace(c) => (),
+                Some('/') => self.state = State::SelfClosingStartTag,
+                Some('=') => self.state = State::BeforeAttributeValue,
+                Some('>') => {
+                    self.state = State::Data;
+                    self.emit_tag();
+                }
+                Some(_) => {
+                    self.new_attr();
+                    self.reconsume(State::AttributeName);
+                }
+                None => self.emit_eof(),
+            },
+            State::BeforeAttributeValue => match c {
+                Some(c) if is_whitespace(c) => (),
+                Some('"') => self.state = State::AttributeValueDoubleQuoted,
+                Some('\'') => self.state = State::AttributeValueSingleQuoted,
+                Some('>') => {
+                    self.state = State::Data;
+                    self.emit_tag();
+                }
+                _ => self.reconsume(State::AttributeValueUnquoted),
+            },
+            State::AttributeValueDoubleQuoted | State::AttributeValueSingleQuoted => {
+                let quote = if self.state == State::AttributeValueDoubleQuoted {
+                    '"'
+                } else {
+                    '\''
+                };
+                match c {
+                    Some(c) if c == quote => self.state = State::AfterAttributeValueQuoted,
+                    Some('\0') => self.push_attr_value('\u{FFFD}'),
+                    Some(c) => self.push_attr_value(c),
+                    None => self.emit_eof(),
+                }
+            }
+            State::AttributeValueUnquoted => match c {
+                Some(c) if is_whitespace(c) => self.state = State::BeforeAttributeName,
+                Some('>') => {
+                    self.state = State::Data;
+                    self.emit_tag();
+                }
+                Some('\0') => self.push_attr_value('\u{FFFD}'),
+                Some(c) => self.push_attr_value(c),
+                None => self.emit_eof(),
+            },
+            State::AfterAttributeValueQuoted => match c {
+                Some(c) if is_whitespace(c) => self.state = State::BeforeAttributeName,
+                Some('/') => self.state = State::SelfClosingStartTag,
+                Some('>') => {
+                    self.state = State::Data;
+                    self.emit_tag();
+                }
+                Some(_) => self.reconsume(State::BeforeAttributeName),
+                None => self.emit_eof(),
+            },
+            State::SelfClosingStartTag => match c {
+                Some('>') => {
+                    self.tag.self_closing = true;
+                    self.state = State::Data;
+                    self.emit_tag();
+                }
+                Some(_) => self.reconsume(State::BeforeAttributeName),
+                None => self.emit_eof(),
+            },
+            State::BogusComment => match c {
+                Some('>') => {
+                    self.state = State::Data;
+                    self.emit_comment();
+                }
+                Some('\0') => self.comment.push('\u{FFFD}'),
+                Some(c) => self.comment.push(c),
+                None => {
+                    self.emit_comment();
+                    self.emit_eof();
+                }
+            },
+            State::MarkupDeclarationOpen => {
+                self.pos -= 1;
+                self.comment.clear();
+                if self.consume_str_insensitive("--") {
+                    self.state = State::CommentStart;
+                } else if self.consume_str_insensitive("doctype") {
+                    self.doctype = DoctypeBuilder::default();
+                    self.state = State::Doctype;
+                } else {
+                    // `<![CDATA[` is also a comment in HTML content
+                    self.state = State::BogusComment;
+                }
+            }
+            State::CommentStart => match c {
+                Some('-') => self.state = State::CommentStartDash,
+                Some('>') => {
+                    self.state = State::Data;
+                    self.emit_comment();
+                }
+                _ => self.reconsume(State::Comment),
+            },
+            State::CommentStartDash => match c {
+                Some('-') => self.state = State::CommentEnd,
+                Some('>') => {
+                    self.state = State::Data;
+                    self.emit_comment();
+                }
+                Some(_) => {
+                    self.comment.push('-');
+                    self.reconsume(State::Comment);
+                }
+                None => {
+                    self.emit_comment();
+                    self.emit_eof();
+                }
+            },
+            State::Comment => match c {
+                Some('-') => self.state = State::CommentEndDash,
+                Some('\0') => self.comment.push('\u{FFFD}'),
+                Some(c) => self.comment.push(c),
+                None => {
+                    self.emit_comment();
+                    self.emit_eof();
+                }
+            },
+            State::CommentEndDash => match c {
+                Some('-') => self.state = State::CommentEnd,
+                Some(_) => {
+                    self.comment.push('-');
+                    self.reconsume(State::Comment);
+                }
+                None => {
+                    self.emit_comment();
+                    self.emit_eof();
+                }
+            },
+            State::CommentEnd => match c {
+                Some('>') => {
+                    self.state = State::Data;
+                    self.emit_comment();
+                }
+                Some('!') => self.state = State::CommentEndBang,
+                Some('-') => self.comment.push('-'),
+                Some(_) => {
+                    self.comment.push_str("--");
+                    self.reconsume(State::Comment);
+                }
+                None => {
+                    self.emit_comment();
+                    self.emit_eof();
+                }
+            },
+            State::CommentEndBang => match c {
+                Some('-') => {
+                    self.comment.push_str("--!");
+                    self.state = State::CommentEndDash;
+                }
+                Some('>') => {
+                    self.state = State::Data;
+                    self.emit_comment();
+                }
+                Some(_) => {
+                    self.comment.push_str("--!");
+                    self.reconsume(State::Comment);
+                }
+                None => {
+                    self.emit_comment();
+                    self.emit_eof();
+                }
+            },
+            State::Doctype => match c {
+                Some(c) if is_whitespace(c) => self.state = State::BeforeDoctypeName,
+                Some(_) => self.reconsume(State::BeforeDoctypeName),
+                None => {
+                    self.emit_doctype_quirks();
+                    self.emit_eof();
+                }
+            },
+            State::BeforeDoctypeName => match c {
+                Some(c) if is_whitespace(c) => (),
+                Some('>') => {
+                    self.state = State::Data;
+                    self.emit_doctype_quirks();
+                }
+                Some(c) => {
+                    let c = if c == '\0' {
+                        '\u{FFFD}'
+                    } else {
+                        c.to_ascii_lowercase()
+                    };
+                    self.doctype.name = Some(c.to_string());
+                    self.state = State::DoctypeName;
+                }
+                None => {
+                    self.emit_doctype_quirks();
+                    self.emit_eof();
+                }
+            },
+            State::DoctypeName => match c {
+                Some(c) if is_whitespace(c) => self.state = State::AfterDoctypeName,
+                Some('>') => {
+                    self.state = State::Data;
+                    self.emit_doctype();
+                }
+                Some(c) => {
+                    let c = if c == '\0' {
+                        '\u{FFFD}'
+                    } else {
+                        c.to_ascii_lowercase()
+                    };
+                    if let Some(name) = self.doctype.name.as_mut() {
+                        name.push(c);
+                    }
+                }
+                None => {
+                    self.emit_doctype_quirks();
+                    self.emit_eof();
+                }
+            },
+            State::AfterDoctypeName => match c {
+                Some(c) if is_whitespace(c) => (),
+                Some('>') => {
+                    self.state = State::Data;
+                    self.emit_doctype();
+                }
+                Some(_) => {
+                    self.pos -= 1;
+                    if self.consume_str_insensitive("public") {
+                        self.state = State::AfterDoctypePublicKeyword;
+                    } else if self.consume_str_insensitive("system") {
+                        self.state = State::AfterDoctypeSystemKeyword;
+                    } else {
+                        self.pos += 1;
+                        self.doctype.force_quirks = true;
+                        self.state = State::BogusDoctype;
+                    }
+                }
+                None => {
+                    self.emit_doctype_quirks();
+                    self.emit_eof();
+                }
+            },
+            State::AfterDoctypePublicKeyword | State::BeforeDoctypePublicIdentifier => match c {
+                Some(c) if is_whitespace(c) => self.state = State::BeforeDoctypePublicIdentifier,
+                Some(q) if q == '"' || q == '\'' => {
+                    self.doctype.public_id = Some(String::new());
+                    self.state = State::DoctypePublicIdentifier(q);
+                }
+                Some('>') => {
+                    self.state = State::Data;
+                    self.emit_doctype_quirks();
+                }
+                Some(_) => {
+                    self.doctype.force_quirks = true;
+                    self.state = State::BogusDoctype;
+                }
+                None => {
+                    self.emit_doctype_quirks();
+                    self.emit_eof();
+                }
+            },
+            State::DoctypePublicIdentifier(quote) | State::DoctypeSystemIdentifier(quote) => {
+                let public = matches!(self.state, State::DoctypePublicIdentifier(_));
+                match c {
+                    Some(c) if c == quote => {
+                        self.state = if public {
+                            State::AfterDoctypePublicIdentifier
+                        } else {
+                            State::AfterDoctypeSystemIdentifier
+                        };
+                    }
+                    Some('>') => {
+                        self.state = State::Data;
+                        self.emit_doctype_quirks();
+                    }
+                    Some(c) => {
+                        let c = if c == '\0' { '\u{FFFD}' } else { c };
+                        let id = if public {
+                            self.doctype.public_id.as_mut()
+                        } else {
+                            self.doctype.system_id.as_mut()
+                        };
+                        if let Some(id) = id {
+                            id.push(c);
+                        }
+                    }
+                    None => {
+                        self.emit_doctype_quirks();
+                        self.emit_eof();
+                    }
+                }
+            }
+            State::AfterDoctypePublicIdentifier
+            | State::BetweenDoctypePublicAndSystemIdentifiers => match c {
+                Some(c) if is_whitespace(c) => {
+                    self.state = State::BetweenDoctypePublicAndSystemIdentifiers
+                }
+                Some('>') => {
+                    self.state = State::Data;
+                    self.emit_doctype();
+                }
+                Some(q) if q == '"' || q == '\'' => {
+                    self.doctype.system_id = Some(String::new());
+                    self.state = State::DoctypeSystemIdentifier(q);
+                }
+                Some(_) => {
+                    self.doctype.force_quirks = true;
+                    self.state = State::BogusDoctype;
+                }
+                None => {
+                    self.emit_doctype_quirks();
+                    self.emit_eof();
+                }
+            },
+            State::AfterDoctypeSystemKeyword | State::BeforeDoctypeSystemIdentifier => match c {
+                Some(c) if is_whitespace(c) => self.state = State::BeforeDoctypeSystemIdentifier,
+                Some(q) if q == '"' || q == '\'' => {
+                    self.doctype.system_id = Some(String::new());
+                    self.state = State::DoctypeSystemIdentifier(q);
+                }
+                Some('>') => {
+                    self.state = State::Data;
+                    self.emit_doctype_quirks();
+                }
+                Some(_) => {
+                    self.doctype.force_quirks = true;
+                    self.state = State::BogusDoctype;
+                }
+                None => {
+                    self.emit_doctype_quirks();
+                    self.emit_eof();
+                }
+            },
+            State::AfterDoctypeSystemIdentifier => match c {
+                Some(c) if is_whitespace(c) => (),
+                Some('>') => {
+                    self.state = State::Data;
+                    self.emit_doctype();
+                }
+                Some(_) => self.state = State::BogusDoctype,
+                None => {
+                    self.emit_doctype_quirks();
+                    self.emit_eof();
+                }
+            },
+            State::BogusDoctype => match c {
+                Some('>') => {
+                    self.state = State::Data;
+                    self.emit_doctype();
+                }
+                Some(_) => (),
+                None => {
+                    self.emit_doctype();
+                    self.emit_eof();
+                }
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(doc: &str) -> Vec<Token> {
+        let mut tokenizer = Tokenizer::new(doc);
+        let mut tokens = Vec::new();
+        loop {
+            match tokenizer.next_token() {
+                Token::Eof => break,
+                token => tokens.push(token),
+            }
+        }
+        tokens
+    }
+
+    fn start(name: &str, attrs: &[(&str, &str)]) -> Token {
+        Token::StartTag {
+            name: name.to_string(),
+            attrs: attrs
+                .iter()
+                .map(|(n, v)| (n.to_string(), v.to_string()))
+                .collect(),
+            self_closing: false,
+        }
+    }
+
+    #[test]
+    fn tag_test() {
+        assert_eq!(
+            tokens(r#"<A Href="x" b=y c d='&amp;' b=dup>t&lt;</a>"#),
+            vec![
+                start("a", &[("href", "x"), ("b", "y"), ("c", ""), ("d", "&")]),
+                Token::Text("t<".to_string()),
+                Token::EndTag {
+                    name: "a".to_string()
+                },
+            ]
+        );
+        assert_eq!(
+            tokens("<br/>a < b"),
+            vec![
+                Token::StartTag {
+                    name: "br".to_string(),
+                    attrs: Vec::new(),
+                    self_closing: true,
+                },
+                Token::Text("a < b".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn comment_and_doctype_test() {
+        assert_eq!(
+            tokens("<!DOCTYPE html><!-- a -- b --><?pi?><!---->"),
+            vec![
+                Token::Doctype {
+                    name: Some("html".to_string()),
+                    public_id: None,
+                    system_id: None,
+                    force_quirks: false,
+                },
+                Token::Comment(" a -- b ".to_string()),
+                Token::Comment("?pi?".to_string()),
+                Token::Comment(String::new()),
+            ]
+        );
+    }
+
+    #[test]
+    fn raw_text_test() {
+        let mut tokenizer = Tokenizer::new("<script>a</b></scriptx></SCRIPT>");
+        assert_eq!(tokenizer.next_token(), start("script", &[]));
+        tokenizer.set_state(State::ScriptData);
+        assert_eq!(
+            tokenizer.next_token(),
+            Token::Text("a</b></scriptx>".to_string())
+        );
+        assert_eq!(
+            tokenizer.next_token(),
+            Token::EndTag {
+                name: "script".to_string()
+            }
+        );
+        assert_eq!(tokenizer.next_token(), Token::Eof);
+    }
+}
@@ -0,0 +1,2016 @@
+//! Module of the HTML5 tree construction.
+//! <https://html.spec.whatwg.org/multipage/parsing.html#tree-construction>
+
+use crate::dom::Doctype;
+
+use super::names;
+use super::tokenizer::State;
+use super::tokenizer::Token;
+use super::tokenizer::Tokenizer;
+
+/// Namespace of an element.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Namespace {
+    Html,
+    Svg,
+    MathMl,
+}
+
+#[derive(Debug, Clone)]
+pub enum NodeData {
+    Document,
+    Doctype(Doctype),
+    Element {
+        name: String,
+        attrs: Vec<(String, String)>,
+        ns: Namespace,
+    },
+    Text(String),
+    Comment(String),
+}
+
+/// A node of the tree under construction.
+#[derive(Debug, Clone)]
+pub struct Node {
+    pub data: NodeData,
+    pub parent: Option<usize>,
+    pub children: Vec<usize>,
+}
+
+/// Id of the document node.
+pub const DOCUMENT: usize = 0;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Mode {
+    Initial,
+    BeforeHtml,
+    BeforeHead,
+    InHead,
+    AfterHead,
+    InBody,
+    Text,
+    InTable,
+    InTableText,
+    InCaption,
+    InColumnGroup,
+    InTableBody,
+    InRow,
+    InCell,
+    InSelect,
+    InSelectInTable,
+    InTemplate,
+    AfterBody,
+    InFrameset,
+    AfterFrameset,
+    AfterAfterBody,
+    AfterAfterFrameset,
+}
+
+/// An entry of the list of active formatting elements.
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Formatting {
+    Marker,
+    Element(usize),
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Scope {
+    Default,
+    ListItem,
+    Button,
+    Table,
+    Select,
+}
+
+/// Builds the tree from the tokens.
+pub struct TreeBuilder {
+    tokenizer: Tokenizer,
+    nodes: Vec<Node>,
+    mode: Mode,
+    original_mode: Mode,
+    /// Stack of open elements.
+    open: Vec<usize>,
+    /// List of active formatting elements.
+    formatting: Vec<Formatting>,
+    head: Option<usize>,
+    form: Option<usize>,
+    frameset_ok: bool,
+    foster_parenting: bool,
+    /// Ignore the LF at the beginning of `<pre>`, `<listing>` and `<textarea>`.
+    ignore_lf: bool,
+    quirks: bool,
+    /// Pending characters in a table.
+    table_text: String,
+}
+
+/// Returns the leading whitespace and the rest.
+fn split_whitespace(s: &str) -> (&str, &str) {
+    let i = s
+        .find(|c: char| !matches!(c, '\t' | '\n' | '\x0C' | '\r' | ' '))
+        .unwrap_or(s.len());
+    s.split_at(i)
+}
+
+fn is_whitespace_text(s: &str) -> bool {
+    split_whitespace(s).1.is_empty()
+}
+
+/// Returns true if the token is a start tag with one of the names.
+fn is_start(token: &Token, names: &[&str]) -> bool {
+    match token {
+        Token::StartTag { name, .. } => names.contains(&name.as_str()),
+        _ => false,
+    }
+}
+
+/// Returns true if the token is an end tag with one of the names.
+fn is_end(token: &Token, names: &[&str]) -> bool {
+    match token {
+        Token::EndTag { name } => names.contains(&name.as_str()),
+        _ => false,
+    }
+}
+
+fn start_tag(name: &str) -> Token {
+    Token::StartTag {
+        name: String::from(name),
+        attrs: Vec::new(),
+        self_closing: false,
+    }
+}
+
+fn attr_value<'a>(attrs: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    attrs
+        .iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, v)| v.as_str())
+}
+
+/// Parse `doc` and returns the nodes. The first node is the document.
+pub fn build(doc: &str) -> Vec<Node> {
+    let mut builder = TreeBuilder {
+        tokenizer: Tokenizer::new(doc),
+        nodes: vec![Node {
+            data: NodeData::Document,
+            parent: None,
+            children: Vec::new(),
+        }],
+        mode: Mode::Initial,
+        original_mode: Mode::Initial,
+        open: Vec::new(),
+        formatting: Vec::new(),
+        head: None,
+        form: None,
+        frameset_ok: true,
+        foster_parenting: false,
+        ignore_lf: false,
+        quirks: false,
+        table_text: String::new(),
+    };
+    loop {
+        let mut token = builder.tokenizer.next_token();
+        if builder.ignore_lf {
+            builder.ignore_lf = false;
+            if let Token::Text(text) = &token {
+                if let Some(rest) = text.strip_prefix('\n') {
+                    if rest.is_empty() {
+                        continue;
+                    }
+                    token = Token::Text(String::from(rest));
+                }
+            }
+        }
+        let eof = token == Token::Eof;
+        builder.dispatch(token);
+        if eof {
+            break;
+        }
+    }
+    builder.nodes
+}
+
+impl TreeBuilder {
+    // ---- nodes ----
+
+    fn name(&self, id: usize) -> &str {
+        match &self.nodes[id].data {
+            NodeData::Element { name, .. } => name,
+            _ => "",
+        }
+    }
+
+    fn ns(&self, id: usize) -> Namespace {
+        match &self.nodes[id].data {
+            NodeData::Element { ns, .. } => *ns,
+            _ => Namespace::Html,
+        }
+    }
+
+    /// Returns true if the node is an HTML element with one of the names.
+    fn is_html(&self, id: usize, names: &[&str]) -> bool {
+        self.ns(id) == Namespace::Html && names.contains(&self.name(id))
+    }
+
+    fn attrs(&self, id: usize) -> Vec<(String, String)> {
+        match &self.nodes[id].data {
+            NodeData::Element { attrs, .. } => attrs.clone(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn current(&self) -> usize {
+        *self.open.last().unwrap_or(&DOCUMENT)
+    }
+
+    fn new_node(&mut self, data: NodeData) -> usize {
+        self.nodes.push(Node {
+            data,
+            parent: None,
+            children: Vec::new(),
+        });
+        self.nodes.len() - 1
+    }
+
+    fn create_element(&mut self, name: &str, attrs: Vec<(String, String)>, ns: Namespace) -> usize {
+        self.new_node(NodeData::Element {
+            name: String::from(name),
+            attrs,
+            ns,
+        })
+    }
+
+    fn detach(&mut self, id: usize) {
+        if let Some(parent) = self.nodes[id].parent.take() {
+            self.nodes[parent].children.retain(|child| *child != id);
+        }
+    }
+
+    /// Insert `id` into `parent` before `before`, or at the end if `before` is `None`.
+    fn insert_at(&mut self, parent: usize, before: Option<usize>, id: usize) {
+        self.detach(id);
+        let children = &mut self.nodes[parent].children;
+        let index = match before.and_then(|before| children.iter().position(|c| *c == before)) {
+            Some(index) => index,
+            None => children.len(),
+        };
+        children.insert(index, id);
+        self.nodes[id].parent = Some(parent);
+    }
+
+    /// The appropriate place for inserting a node.
+    /// Returns the parent and the node to insert before.
+    fn insertion_place(&self, target: Option<usize>) -> (usize, Option<usize>) {
+        let target = target.unwrap_or_else(|| self.current());
+        if self.foster_parenting
+            && self.is_html(target, &["table", "tbody", "tfoot", "thead", "tr"])
+        {
+            // foster parenting
+            if let Some(i) = self
+                .open
+                .iter()
+                .rposition(|id| self.is_html(*id, &["table"]))
+            {
+                let table = self.open[i];
+                if let Some(parent) = self.nodes[table].parent {
+                    return (parent, Some(table));
+                }
+                return (self.open[i - 1], None);
+            }
+            return (self.open[0], None);
+        }
+        (target, None)
+    }
+
+    fn insert_element(&mut self, name: &str, attrs: Vec<(String, String)>, ns: Namespace) -> usize {
+        let (parent, before) = self.insertion_place(None);
+        let id = self.create_element(name, attrs, ns);
+        self.insert_at(parent, before, id);
+        self.open.push(id);
+        id
+    }
+
+    /// Insert an HTML element for the start tag.
+    fn insert_html(&mut self, token: &Token) -> usize {
+        match token {
+            Token::StartTag { name, attrs, .. } => {
+                self.insert_element(name, attrs.clone(), Namespace::Html)
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn insert_text(&mut self, text: &str) {
+        let (parent, before) = self.insertion_place(None);
+        if parent == DOCUMENT {
+            return;
+        }
+        let children = &self.nodes[parent].children;
+        let prev = match before {
+            Some(before) => {
+                let i = children.iter().position(|c| *c == before).unwrap();
+                if i == 0 {
+                    None
+                } else {
+                    Some(children[i - 1])
+                }
+            }
+            None => children.last().copied(),
+        };
+        if let Some(prev) = prev {
+            if let NodeData::Text(s) = &mut self.nodes[prev].data {
+                s.push_str(text);
+                return;
+            }
+        }
+        let id = self.new_node(NodeData::Text(String::from(text)));
+        self.insert_at(parent, before, id);
+    }
+
+    fn insert_comment(&mut self, comment: &str, parent: Option<usize>) {
+        let (parent, before) = match parent {
+            Some(parent) => (parent, None),
+            None => self.insertion_place(None),
+        };
+        let id = self.new_node(NodeData::Comment(String::from(comment)));
+        self.insert_at(parent, before, id);
+    }
+
+    // ---- stack of open elements ----
+
+    fn in_scope_by(&self, scope: Scope, matches: impl Fn(usize) -> bool) -> bool {
+        for id in self.open.iter().rev() {
+            if matches(*id) {
+                return true;
+            }
+            let name = self.name(*id);
+            let boundary = match self.ns(*id) {
+                Namespace::Html => match scope {
+                    Scope::Select => !matches!(name, "optgroup" | "option"),
+                    Scope::Table => matches!(name, "html" | "table" | "template"),
+                    _ => {
+                        matches!(
+                            name,
+                            "applet"
+                                | "caption"
+                                | "html"
+                                | "table"
+                                | "td"
+                                | "th"
+                                | "marquee"
+                                | "object"
+                                | "template"
+                        ) || (scope == Scope::ListItem && matches!(name, "ol" | "ul"))
+                            || (scope == Scope::Button && name == "button")
+                    }
+                },
+                Namespace::MathMl => {
+                    matches!(scope, Scope::Default | Scope::ListItem | Scope::Button)
+                        && matches!(name, "mi" | "mo" | "mn" | "ms" | "mtext" | "annotation-xml")
+                }
+                Namespace::Svg => {
+                    matches!(scope, Scope::Default | Scope::ListItem | Scope::Button)
+                        && matches!(name, "foreignObject" | "desc" | "title")
+                }
+            };
+            if boundary || scope == Scope::Select && self.ns(*id) != Namespace::Html {
+                return false;
+            }
+        }
+        false
+    }
+
+    fn in_scope(&self, names: &[&str], scope: Scope) -> bool {
+        self.in_scope_by(scope, |id| self.is_html(id, names))
+    }
+
+    fn pop(&mut self) -> Option<usize> {
+        self.open.pop()
+    }
+
+    /// Pop elements until an HTML element with one of the names has been popped.
+    fn pop_until(&mut self, names: &[&str]) {
+        while let Some(id) = self.pop() {
+            if self.is_html(id, names) {
+                break;
+            }
+        }
+    }
+
+    fn generate_implied_end_tags(&mut self, except: &[&str]) {
+        while let Some(&id) = self.open.last() {
+            if !self.is_html(id, &names::IMPLIED_END) || self.is_html(id, except) {
+                break;
+            }
+            self.pop();
+        }
+    }
+
+    fn close_p(&mut self) {
+        self.generate_implied_end_tags(&["p"]);
+        self.pop_until(&["p"]);
+    }
+
+    fn close_p_in_button_scope(&mut self) {
+        if self.in_scope(&["p"], Scope::Button) {
+            self.close_p();
+        }
+    }
+
+    /// Pop elements until the current node is one of the names or `<html>`.
+    fn clear_to_context(&mut self, names: &[&str]) {
+        while let Some(&id) = self.open.last() {
+            if self.is_html(id, names) || self.is_html(id, &["html", "template"]) {
+                break;
+            }
+            self.pop();
+        }
+    }
+
+    fn reset_insertion_mode(&mut self) {
+        for (i, id) in self.open.iter().enumerate().rev() {
+            let last = i == 0;
+            let mode = match self.name(*id) {
+                "select" => {
+                    let in_table = self.open[..i]
+                        .iter()
+                        .rev()
+                        .take_while(|id| !self.is_html(**id, &["template"]))
+                        .any(|id| self.is_html(*id, &["table"]));
+                    if in_table {
+                        Mode::InSelectInTable
+                    } else {
+                        Mode::InSelect
+                    }
+                }
+                "td" | "th" if !last => Mode::InCell,
+                "tr" => Mode::InRow,
+                "tbody" | "thead" | "tfoot" => Mode::InTableBody,
+                "caption" => Mode::InCaption,
+                "colgroup" => Mode::InColumnGroup,
+                "table" => Mode::InTable,
+                "template" => Mode::InTemplate,
+                "head" if !last => Mode::InHead,
+                "body" => Mode::InBody,
+                "frameset" => Mode::InFrameset,
+                "html" => match self.head {
+                    Some(_) => Mode::AfterHead,
+                    None => Mode::BeforeHead,
+                },
+                _ if last => Mode::InBody,
+                _ => continue,
+            };
+            self.mode = mode;
+            return;
+        }
+        self.mode = Mode::InBody;
+    }
+
+    // ---- list of active formatting elements ----
+
+    fn formatting_position(&self, id: usize) -> Option<usize> {
+        self.formatting
+            .iter()
+            .position(|entry| *entry == Formatting::Element(id))
+    }
+
+    fn push_formatting(&mut self, id: usize) {
+        // at most three elements with the same name and attributes after the last marker
+        let mut same = Vec::new();
+        for (i, entry) in self.formatting.iter().enumerate().rev() {
+            match entry {
+                Formatting::Marker => break,
+                Formatting::Element(other) => {
+                    if self.name(*other) == self.name(id) && self.attrs(*other) == self.attrs(id) {
+                        same.push(i);
+                    }
+                }
+            }
+        }
+        if same.len() >= 3 {
+            self.formatting.remove(*same.last().unwrap());
+        }
+        self.formatting.push(Formatting::Element(id));
+    }
+
+    fn reconstruct_formatting(&mut self) {
+        let is_open = |builder: &TreeBuilder, entry: &Formatting| match entry {
+            Formatting::Marker => true,
+            Formatting::Element(id) => builder.open.contains(id),
+        };
+        let last = match self.formatting.last() {
+            Some(last) => last,
+            None => return,
+        };
+        if is_open(self, last) {
+            return;
+        }
+        let mut i = self.formatting.len() - 1;
+        while i > 0 && !is_open(self, &self.formatting[i - 1]) {
+            i -= 1;
+        }
+        for i in i..self.formatting.len() {
+            let id = match self.formatting[i] {
+                Formatting::Element(id) => id,
+                Formatting::Marker => continue,
+            };
+            let name = String::from(self.name(id));
+            let attrs = self.attrs(id);
+            let new_id = self.insert_element(&name, attrs, Namespace::Html);
+            self.formatting[i] = Formatting::Element(new_id);
+        }
+    }
+
+    fn clear_formatting_to_marker(&mut self) {
+        while let Some(entry) = self.formatting.pop() {
+            if entry == Formatting::Marker {
+                break;
+            }
+        }
+    }
+
+    /// The adoption agency algorithm for the end tag `subject`.
+    /// Returns false if the end tag should be processed as "any other end tag".
+    fn adoption_agency(&mut self, subject: &str) -> bool {
+        let current = self.current();
+        if self.is_html(current, &[subject]) && self.formatting_position(current).is_none() {
+            self.pop();
+            return true;
+        }
+
+        for _ in 0..8 {
+            // the formatting element
+            let mut formatting_element = None;
+            for entry in self.formatting.iter().rev() {
+                match entry {
+                    Formatting::Marker => break,
+                    Formatting::Element(id) if self.name(*id) == subject => {
+                        formatting_element = Some(*id);
+                        break;
+                    }
+                    _ => (),
+                }
+            }
+            let fe = match formatting_element {
+                Some(fe) => fe,
+                None => return false,
+            };
+            let fe_index = match self.open.iter().position(|id| *id == fe) {
+                Some(i) => i,
+                None => {
+                    let i = self.formatting_position(fe).unwrap();
+                    self.formatting.remove(i);
+                    return true;
+                }
+            };
+            if !self.in_scope_by(Scope::Default, |id| id == fe) {
+                return true;
+            }
+
+            // the furthest block
+            let furthest_block = self.open[fe_index + 1..]
+                .iter()
+                .position(|id| self.is_special(*id))
+                .map(|i| fe_index + 1 + i);
+            let fb_index = match furthest_block {
+                Some(i) => i,
+                None => {
+                    self.open.truncate(fe_index);
+                    let i = self.formatting_position(fe).unwrap();
+                    self.formatting.remove(i);
+                    return true;
+                }
+            };
+            let furthest_block = self.open[fb_index];
+            let common_ancestor = self.open[fe_index - 1];
+            let mut bookmark = self.formatting_position(fe).unwrap();
+
+            let mut node_index = fb_index;
+            let mut last_node = furthest_block;
+            let mut inner = 0;
+            loop {
+                inner += 1;
+                node_index -= 1;
+                let node = self.open[node_index];
+                if node == fe {
+                    break;
+                }
+                let mut position = self.formatting_position(node);
+                if inner > 3 {
+                    if let Some(i) = position {
+                        self.formatting.remove(i);
+                        if i < bookmark {
+                            bookmark -= 1;
+                        }
+                        position = None;
+                    }
+                }
+                let position = match position {
+                    Some(i) => i,
+                    None => {
+                        self.open.remove(node_index);
+                        continue;
+                    }
+                };
+                let name = String::from(self.name(node));
+                let attrs = self.attrs(node);
+                let new_node = self.create_element(&name, attrs, Namespace::Html);
+                self.formatting[position] = Formatting::Element(new_node);
+                self.open[node_index] = new_node;
+                if last_node == furthest_block {
+                    bookmark = position + 1;
+                }
+                self.insert_at(new_node, None, last_node);
+                last_node = new_node;
+            }
+
+            let (parent, before) = self.insertion_place(Some(common_ancestor));
+            self.insert_at(parent, before, last_node);
+
+            let name = String::from(self.name(fe));
+            let attrs = self.attrs(fe);
+            let new_element = self.create_element(&name, attrs, Namespace::Html);
+            let children = std::mem::take(&mut self.nodes[furthest_block].children);
+            for child in children.iter() {
+                self.nodes[*child].parent = Some(new_element);
+            }
+            self.nodes[new_element].children = children;
+            self.insert_at(furthest_block, None, new_element);
+
+            let i = self.formatting_position(fe).unwrap();
+            self.formatting.remove(i);
+            if i < bookmark {
+                bookmark -= 1;
+            }
+            self.formatting.insert(
+                bookmark.min(self.formatting.len()),
+                Formatting::Element(new_element),
+            );
+
+            self.open.retain(|id| *id != fe);
+            let fb_index = self
+                .open
+                .iter()
+                .position(|id| *id == furthest_block)
+                .unwrap();
+            self.open.insert(fb_index + 1, new_element);
+        }
+        true
+    }
+
+    fn is_special(&self, id: usize) -> bool {
+        match self.ns(id) {
+            Namespace::Html => names::SPECIAL.contains(&self.name(id)),
+            Namespace::MathMl => matches!(
+                self.name(id),
+                "mi" | "mo" | "mn" | "ms" | "mtext" | "annotation-xml"
+            ),
+            Namespace::Svg => matches!(self.name(id), "foreignObject" | "desc" | "title"),
+        }
+    }
+
+    // ---- dispatch ----
+
+    fn is_html_integration_point(&self, id: usize) -> bool {
+        match self.ns(id) {
+            Namespace::Svg => matches!(self.name(id), "foreignObject" | "desc" | "title"),
+            Namespace::MathMl => {
+                self.name(id) == "annotation-xml"
+                    && attr_value(&self.attrs(id), "encoding").is_some_and(|encoding| {
+                        encoding.eq_ignore_ascii_case("text/html")
+                            || encoding.eq_ignore_ascii_case("application/xhtml+xml")
+                    })
+            }
+            Namespace::Html => false,
+        }
+    }
+
+    fn is_mathml_text_integration_point(&self, id: usize) -> bool {
+        self.ns(id) == Namespace::MathMl
+            && matches!(self.name(id), "mi" | "mo" | "mn" | "ms" | "mtext")
+    }
+
+    fn dispatch(&mut self, token: Token) {
+        if self.open.is_empty() || token == Token::Eof {
+            return self.process(token);
+        }
+        let current = self.current();
+        if self.ns(current) == Namespace::Html {
+            return self.process(token);
+        }
+        let html = match &token {
+            Token::StartTag { name, .. } => {
+                (self.is_mathml_text_integration_point(current)
+                    && name != "mglyph"
+                    && name != "malignmark")
+                    || (self.ns(current) == Namespace::MathMl
+                        && self.name(current) == "annotation-xml"
+                        && name == "svg")
+                    || self.is_html_integration_point(current)
+            }
+            Token::Text(_) => {
+                self.is_mathml_text_integration_point(current)
+                    || self.is_html_integration_point(current)
+            }
+            _ => false,
+        };
+        if html {
+            self.process(token);
+        } else {
+            self.in_foreign_content(token);
+        }
+    }
+
+    fn process(&mut self, token: Token) {
+        match self.mode {
+            Mode::Initial => self.initial(token),
+            Mode::BeforeHtml => self.before_html(token),
+            Mode::BeforeHead => self.before_head(token),
+            Mode::InHead => self.in_head(token),
+            Mode::AfterHead => self.after_head(token),
+            Mode::InBody | Mode::InTemplate => self.in_body(token),
+            Mode::Text => self.text(token),
+            Mode::InTable => self.in_table(token),
+            Mode::InTableText => self.in_table_text(token),
+            Mode::InCaption => self.in_caption(token),
+            Mode::InColumnGroup => self.in_column_group(token),
+            Mode::InTableBody => self.in_table_body(token),
+            Mode::InRow => self.in_row(token),
+            Mode::InCell => self.in_cell(token),
+            Mode::InSelect => self.in_select(token),
+            Mode::InSelectInTable => self.in_select_in_table(token),
+            Mode::AfterBody => self.after_body(token),
+            Mode::InFrameset => self.in_frameset(token),
+            Mode::AfterFrameset => self.after_frameset(token),
+            Mode::AfterAfterBody => self.after_after_body(token),
+            Mode::AfterAfterFrameset => self.after_after_frameset(token),
+        }
+    }
+
+    /// Process the leading whitespace by `ws`, then the rest by `rest`.
+    fn split_text(
+        &mut self,
+        text: &str,
+        ws: fn(&mut TreeBuilder, &str),
+        rest: fn(&mut TreeBuilder, Token),
+    ) {
+        let (space, other) = split_whitespace(text);
+        if !space.is_empty() {
+            ws(self, space);
+        }
+        if !other.is_empty() {
+            rest(self, Token::Text(String::from(other)));
+        }
+    }
+
+    /// Parse the content of the element as text with the tokenizer `state`.
+    fn parse_text_element(&mut self, token: &Token, state: State) {
+        self.insert_html(token);
+        self.tokenizer.set_state(state);
+        self.original_mode = self.mode;
+        self.mode = Mode::Text;
+    }
+
+    // ---- insertion modes ----
+
+    fn initial(&mut self, token: Token) {
+        match token {
+            Token::Text(text) => self.split_text(&text, |_, _| (), TreeBuilder::initial_else),
+            Token::Comment(comment) => self.insert_comment(&comment, Some(DOCUMENT)),
+            Token::Doctype {
+                name,
+                public_id,
+                system_id,
+                force_quirks,
+            } => {
+                self.quirks = force_quirks
+                    || name.as_deref() != Some("html")
+                    || public_id.as_deref().is_some_and(|id| {
+                        let id = id.to_ascii_lowercase();
+                        id.starts_with("-//w3o//dtd w3 html strict 3.0//en//")
+                            || id == "-/w3c/dtd html 4.0 transitional/en"
+                            || id == "html"
+                            || id.starts_with("-//ietf//dtd html")
+                            || id.starts_with("-//w3c//dtd html 3")
+                            || id.starts_with("-//w3c//dtd html 4.0 transitional//")
+                            || (system_id.is_none()
+                                && id.starts_with("-//w3c//dtd html 4.01 transitional//"))
+                    });
+                let mut doctype = Doctype::new(name.as_deref().unwrap_or(""));
+                if let Some(public_id) = public_id {
+                    doctype.set_public_id(&public_id);
+                }
+                if let Some(system_id) = system_id {
+                    doctype.set_system_id(&system_id);
+                }
+                let id = self.new_node(NodeData::Doctype(doctype));
+                self.insert_at(DOCUMENT, None, id);
+                self.mode = Mode::BeforeHtml;
+            }
+            token => self.initial_else(token),
+        }
+    }
+
+    fn initial_else(&mut self, token: Token) {
+        self.quirks = true;
+        self.mode = Mode::BeforeHtml;
+        self.process(token);
+    }
+
+    fn before_html(&mut self, token: Token) {
+        match token {
+            Token::Doctype { .. } => (),
+            Token::Comment(comment) => self.insert_comment(&comment, Some(DOCUMENT)),
+            Token::Text(text) => self.split_text(&text, |_, _| (), TreeBuilder::before_html_else),
+            Token::StartTag { ref name, .. } if name == "html" => {
+                let id = self.create_element("html", self.token_attrs(&token), Namespace::Html);
+                self.insert_at(DOCUMENT, None, id);
+                self.open.push(id);
+                self.mode = Mode::BeforeHead;
+            }
+            Token::EndTag { ref name }
+                if !matches!(name.as_str(), "head" | "body" | "html" | "br") => {}
+            token => self.before_html_else(token),
+        }
+    }
+
+    fn token_attrs(&self, token: &Token) -> Vec<(String, String)> {
+        match token {
+            Token::StartTag { attrs, .. } => attrs.clone(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn before_html_else(&mut self, token: Token) {
+        let id = self.create_element("html", Vec::new(), Namespace::Html);
+        self.insert_at(DOCUMENT, None, id);
+        self.open.push(id);
+        self.mode = Mode::BeforeHead;
+        self.process(token);
+    }
+
+    fn before_head(&mut self, token: Token) {
+        match token {
+            Token::Text(text) => self.split_text(&text, |_, _| (), TreeBuilder::before_head_else),
+            Token::Comment(comment) => self.insert_comment(&comment, None),
+            Token::Doctype { .. } => (),
+            Token::StartTag { ref name, .. } if name == "html" => self.in_body(token),
+            Token::StartTag { ref name, .. } if name == "head" => {
+                self.head = Some(self.insert_html(&token));
+                self.mode = Mode::InHead;
+            }
+            Token::EndTag { ref name }
+                if !matches!(name.as_str(), "head" | "body" | "html" | "br") => {}
+            token => self.before_head_else(token),
+        }
+    }
+
+    fn before_head_else(&mut self, token: Token) {
+        self.head = Some(self.insert_html(&start_tag("head")));
+        self.mode = Mode::InHead;
+        self.process(token);
+    }
+
+    fn in_head(&mut self, token: Token) {
+        match token {
+            Token::Text(text) => {
+                self.split_text(&text, TreeBuilder::insert_text, TreeBuilder::in_head_else)
+            }
+            Token::Comment(comment) => self.insert_comment(&comment, None),
+            Token::Doctype { .. } => (),
+            Token::StartTag { ref name, .. } if name == "html" => self.in_body(token),
+            Token::StartTag { ref name, .. }
+                if matches!(
+                    name.as_str(),
+                    "base" | "basefont" | "bgsound" | "link" | "meta"
+                ) =>
+            {
+                self.insert_html(&token);
+                self.pop();
+            }
+            Token::StartTag { ref name, .. } if name == "title" => {
+                self.parse_text_element(&token, State::Rcdata)
+            }
+            Token::StartTag { ref name, .. }
+                if matches!(name.as_str(), "noscript" | "noframes" | "style") =>
+            {
+                self.parse_text_element(&token, State::Rawtext)
+            }
+            Token::StartTag { ref name, .. } if name == "script" => {
+                self.parse_text_element(&token, State::ScriptData)
+            }
+            Token::StartTag { ref name, .. } if name == "template" => {
+                self.insert_html(&token);
+                self.formatting.push(Formatting::Marker);
+                self.frameset_ok = false;
+                self.mode = Mode::InTemplate;
+            }
+            Token::EndTag { ref name } if name == "head" => {
+                self.pop();
+                self.mode = Mode::AfterHead;
+            }
+            Token::EndTag { ref name } if name == "template" => self.end_template(),
+            Token::StartTag { ref name, .. } if name == "head" => (),
+            Token::EndTag { ref name } if !matches!(name.as_str(), "body" | "html" | "br") => (),
+            token => self.in_head_else(token),
+        }
+    }
+
+    fn in_head_else(&mut self, token: Token) {
+        self.pop();
+        self.mode = Mode::AfterHead;
+        self.process(token);
+    }
+
+    fn end_template(&mut self) {
+        if !self.open.iter().any(|id| self.is_html(*id, &["template"])) {
+            return;
+        }
+        self.generate_implied_end_tags(&[]);
+        self.pop_until(&["template"]);
+        self.clear_formatting_to_marker();
+        self.reset_insertion_mode();
+    }
+
+    fn after_head(&mut self, token: Token) {
+        match token {
+            Token::Text(text) => self.split_text(
+                &text,
+                TreeBuilder::insert_text,
+                TreeBuilder::after_head_else,
+            ),
+            Token::Comment(comment) => self.insert_comment(&comment, None),
+            Token::Doctype { .. } => (),
+            Token::StartTag { ref name, .. } if name == "html" => self.in_body(token),
+            Token::StartTag { ref name, .. } if name == "body" => {
+                self.insert_html(&token);
+                self.frameset_ok = false;
+                self.mode = Mode::InBody;
+            }
+            Token::StartTag { ref name, .. } if name == "frameset" => {
+                self.insert_html(&token);
+                self.mode = Mode::InFrameset;
+            }
+            Token::StartTag { ref name, .. }
+                if matches!(
+                    name.as_str(),
+                    "base"
+                        | "basefont"
+                        | "bgsound"
+                        | "link"
+                        | "meta"
+                        | "noframes"
+                        | "script"
+                        | "style"
+                        | "template"
+                        | "title"
+                ) =>
+            {
+                let head = match self.head {
+                    Some(head) => head,
+                    None => return,
+                };
+                self.open.push(head);
+                self.in_head(token);
+                self.open.retain(|id| *id != head);
+            }
+            Token::EndTag { ref name } if name == "template" => self.in_head(token),
+            Token::StartTag { ref name, .. } if name == "head" => (),
+            Token::EndTag { ref name } if !matches!(name.as_str(), "body" | "html" | "br") => (),
+            token => self.after_head_else(token),
+        }
+    }
+
+    fn after_head_else(&mut self, token: Token) {
+        self.insert_html(&start_tag("body"));
+        self.mode = Mode::InBody;
+        self.process(token);
+    }
+
+    fn in_body(&mut self, token: Token) {
+        match token {
+            Token::Text(text) => {
+                let text = text.replace('\0', "");
+                if text.is_empty() {
+                    return;
+                }
+                self.reconstruct_formatting();
+                self.insert_text(&text);
+                if !is_whitespace_text(&text) {
+                    self.frameset_ok = false;
+                }
+            }
+            Token::Comment(comment) => self.insert_comment(&comment, None),
+            Token::Doctype { .. } => (),
+            Token::Eof => (),
+            Token::StartTag {
+                name,
+                attrs,
+                self_closing,
+            } => self.in_body_start(name, attrs, self_closing),
+            Token::EndTag { name } => self.in_body_end(name),
+        }
+    }
+
+    fn in_body_start(&mut self, name: String, attrs: Vec<(String, String)>, self_closing: bool) {
+        let token = Token::StartTag {
+            name: name.clone(),
+            attrs: attrs.clone(),
+            self_closing,
+        };
+        match name.as_str() {
+            "html" => {
+                if self.open.iter().any(|id| self.is_html(*id, &["template"])) {
+                    return;
+                }
+                self.add_missing_attrs(self.open[0], attrs);
+            }
+            "base" | "basefont" | "bgsound" | "link" | "meta" | "noframes" | "script" | "style"
+            | "template" | "title" => self.in_head(token),
+            "body" => {
+                if self.open.len() < 2 || !self.is_html(self.open[1], &["body"]) {
+                    return;
+                }
+                self.frameset_ok = false;
+                self.add_missing_attrs(self.open[1], attrs);
+            }
+            "frameset" => {
+                if !self.frameset_ok
+                    || self.open.len() < 2
+                    || !self.is_html(self.open[1], &["body"])
+                {
+                    return;
+                }
+                let body = self.open[1];
+                self.detach(body);
+                self.open.truncate(1);
+                self.insert_html(&token);
+                self.mode = Mode::InFrameset;
+            }
+            "address" | "article" | "aside" | "blockquote" | "center" | "details" | "dialog"
+            | "dir" | "div" | "dl" | "fieldset" | "figcaption" | "figure" | "footer" | "header"
+            | "hgroup" | "main" | "menu" | "nav" | "ol" | "p" | "search" | "section"
+            | "summary" | "ul" => {
+                self.close_p_in_button_scope();
+                self.insert_html(&token);
+            }
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                self.close_p_in_button_scope();
+                if self.is_html(self.current(), &names::HEADINGS) {
+                    self.pop();
+                }
+                self.insert_html(&token);
+            }
+            "pre" | "listing" => {
+                self.close_p_in_button_scope();
+                self.insert_html(&token);
+                self.ignore_lf = true;
+                self.frameset_ok = false;
+            }
+            "form" => {
+                let in_template = self.open.iter().any(|id| self.is_html(*id, &["template"]));
+                if self.form.is_some() && !in_template {
+                    return;
+                }
+                self.close_p_in_button_scope();
+                let id = self.insert_html(&token);
+                if !in_template {
+                    self.form = Some(id);
+                }
+            }
+            "li" | "dd" | "dt" => {
+                self.frameset_ok = false;
+                let closes: &[&str] = if name == "li" { &["li"] } else { &["dd", "dt"] };
+                for i in (0..self.open.len()).rev() {
+                    let id = self.open[i];
+                    if self.is_html(id, closes) {
+                        let node_name = String::from(self.name(id));
+                        self.generate_implied_end_tags(&[&node_name]);
+                        self.pop_until(&[&node_name]);
+                        break;
+                    }
+                    if self.is_special(id) && !self.is_html(id, &["address", "div", "p"]) {
+                        break;
+                    }
+                }
+                self.close_p_in_button_scope();
+                self.insert_html(&token);
+            }
+            "plaintext" => {
+                self.close_p_in_button_scope();
+                self.insert_html(&token);
+                self.tokenizer.set_state(State::Plaintext);
+            }
+            "button" => {
+                if self.in_scope(&["button"], Scope::Default) {
+                    self.generate_implied_end_tags(&[]);
+                    self.pop_until(&["button"]);
+                }
+                self.reconstruct_formatting();
+                self.insert_html(&token);
+                self.frameset_ok = false;
+            }
+            "a" => {
+                let mut open_a = None;
+                for entry in self.formatting.iter().rev() {
+                    match entry {
+                        Formatting::Marker => break,
+                        Formatting::Element(id) if self.name(*id) == "a" => {
+                            open_a = Some(*id);
+                            break;
+                        }
+                        _ => (),
+                    }
+                }
+                if let Some(a) = open_a {
+                    self.adoption_agency("a");
+                    if let Some(i) = self.formatting_position(a) {
+                        self.formatting.remove(i);
+                    }
+                    self.open.retain(|id| *id != a);
+                }
+                self.reconstruct_formatting();
+                let id = self.insert_html(&token);
+                self.push_formatting(id);
+            }
+            "b" | "big" | "code" | "em" | "font" | "i" | "s" | "small" | "strike" | "strong"
+            | "tt" | "u" => {
+                self.reconstruct_formatting();
+                let id = self.insert_html(&token);
+                self.push_formatting(id);
+            }
+            "nobr" => {
+                self.reconstruct_formatting();
+                if self.in_scope(&["nobr"], Scope::Default) {
+                    self.adoption_agency("nobr");
+                    self.reconstruct_formatting();
+                }
+                let id = self.insert_html(&token);
+                self.push_formatting(id);
+            }
+            "applet" | "marquee" | "object" => {
+                self.reconstruct_formatting();
+                self.insert_html(&token);
+                self.formatting.push(Formatting::Marker);
+                self.frameset_ok = false;
+            }
+            "table" => {
+                if !self.quirks {
+                    self.close_p_in_button_scope();
+                }
+                self.insert_html(&token);
+                self.frameset_ok = false;
+                self.mode = Mode::InTable;
+            }
+            "area" | "br" | "embed" | "img" | "keygen" | "wbr" => {
+                self.reconstruct_formatting();
+                self.insert_html(&token);
+                self.pop();
+                self.frameset_ok = false;
+            }
+            "input" => {
+                self.reconstruct_formatting();
+                self.insert_html(&token);
+                self.pop();
+                let hidden = attr_value(&attrs, "type")
+                    .is_some_and(|value| value.eq_ignore_ascii_case("hidden"));
+                if !hidden {
+                    self.frameset_ok = false;
+                }
+            }
+            "param" | "source" | "track" => {
+                self.insert_html(&token);
+                self.pop();
+            }
+            "hr" => {
+                self.close_p_in_button_scope();
+                self.insert_html(&token);
+                self.pop();
+                self.frameset_ok = false;
+            }
+            "image" => self.in_body_start(String::from("img"), attrs, self_closing),
+            "textarea" => {
+                self.insert_html(&token);
+                self.ignore_lf = true;
+                self.tokenizer.set_state(State::Rcdata);
+                self.original_mode = self.mode;
+                self.frameset_ok = false;
+                self.mode = Mode::Text;
+            }
+            "xmp" => {
+                self.close_p_in_button_scope();
+                self.reconstruct_formatting();
+                self.frameset_ok = false;
+                self.parse_text_element(&token, State::Rawtext);
+            }
+            "iframe" => {
+                self.frameset_ok = false;
+                self.parse_text_element(&token, State::Rawtext);
+            }
+            "noembed" | "noscript" => self.parse_text_element(&token, State::Rawtext),
+            "select" => {
+                self.reconstruct_formatting();
+                self.insert_html(&token);
+                self.frameset_ok = false;
+                self.mode = match self.mode {
+                    Mode::InTable
+                    | Mode::InCaption
+                    | Mode::InTableBody
+                    | Mode::InRow
+                    | Mode::InCell => Mode::InSelectInTable,
+                    _ => Mode::InSelect,
+                };
+            }
+            "optgroup" | "option" => {
+                if self.is_html(self.current(), &["option"]) {
+                    self.pop();
+                }
+                self.reconstruct_formatting();
+                self.insert_html(&token);
+            }
+            "rb" | "rtc" => {
+                if self.in_scope(&["ruby"], Scope::Default) {
+                    self.generate_implied_end_tags(&[]);
+                }
+                self.insert_html(&token);
+            }
+            "rp" | "rt" => {
+                if self.in_scope(&["ruby"], Scope::Default) {
+                    self.generate_implied_end_tags(&["rtc"]);
+                }
+                self.insert_html(&token);
+            }
+            "math" | "svg" => {
+                self.reconstruct_formatting();
+                let ns = if name == "math" {
+                    Namespace::MathMl
+                } else {
+                    Namespace::Svg
+                };
+                self.insert_foreign(&name, attrs, ns, self_closing);
+            }
+            "caption" | "col" | "colgroup" | "frame" | "head" | "tbody" | "td" | "tfoot" | "th"
+            | "thead" | "tr" => (),
+            _ => {
+                self.reconstruct_formatting();
+                self.insert_html(&token);
+            }
+        }
+    }
+
+    fn add_missing_attrs(&mut self, id: usize, new_attrs: Vec<(String, String)>) {
+        if let NodeData::Element { attrs, .. } = &mut self.nodes[id].data {
+            for (name, value) in new_attrs.into_iter() {
+                if !attrs.iter().any(|(n, _)| *n == name) {
+                    attrs.push((name, value));
+                }
+            }
+        }
+    }
+
+    fn in_body_end(&mut self, name: String) {
+        match name.as_str() {
+            "template" => self.end_template(),
+            "body" | "html" => {
+                if !self.in_scope(&["body"], Scope::Default) {
+                    return;
+                }
+                self.mode = Mode::AfterBody;
+                if name == "html" {
+                    self.process(Token::EndTag { name });
+                }
+            }
+            "address" | "article" | "aside" | "blockquote" | "button" | "center" | "details"
+            | "dialog" | "dir" | "div" | "dl" | "fieldset" | "figcaption" | "figure" | "footer"
+            | "header" | "hgroup" | "listing" | "main" | "menu" | "nav" | "ol" | "pre"
+            | "search" | "section" | "summary" | "ul" => {
+                if !self.in_scope(&[&name], Scope::Default) {
+                    return;
+                }
+                self.generate_implied_end_tags(&[]);
+                self.pop_until(&[&name]);
+            }
+            "form" => {
+                if self.open.iter().any(|id| self.is_html(*id, &["template"])) {
+                    if !self.in_scope(&["form"], Scope::Default) {
+                        return;
+                    }
+                    self.generate_implied_end_tags(&[]);
+                    self.pop_until(&["form"]);
+                    return;
+                }
+                let form = match self.form.take() {
+                    Some(form) => form,
+                    None => return,
+                };
+                if !self.in_scope_by(Scope::Default, |id| id == form) {
+                    return;
+                }
+                self.generate_implied_end_tags(&[]);
+                self.open.retain(|id| *id != form);
+            }
+            "p" => {
+                if !self.in_scope(&["p"], Scope::Button) {
+                    self.insert_html(&start_tag("p"));
+                }
+                self.close_p();
+            }
+            "li" => {
+                if !self.in_scope(&["li"], Scope::ListItem) {
+                    return;
+                }
+                self.generate_implied_end_tags(&["li"]);
+                self.pop_until(&["li"]);
+            }
+            "dd" | "dt" => {
+                if !self.in_scope(&[&name], Scope::Default) {
+                    return;
+                }
+                self.generate_implied_end_tags(&[&name]);
+                self.pop_until(&[&name]);
+            }
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                if !self.in_scope(&names::HEADINGS, Scope::Default) {
+                    return;
+                }
+                self.generate_implied_end_tags(&[]);
+                self.pop_until(&names::HEADINGS);
+            }
+            "a" | "b" | "big" | "code" | "em" | "font" | "i" | "nobr" | "s" | "small"
+            | "strike" | "strong" | "tt" | "u" => {
+                if !self.adoption_agency(&name) {
+                    self.any_other_end_tag(&name);
+                }
+            }
+            "applet" | "marquee" | "object" => {
+                if !self.in_scope(&[&name], Scope::Default) {
+                    return;
+                }
+                self.generate_implied_end_tags(&[]);
+                self.pop_until(&[&name]);
+                self.clear_formatting_to_marker();
+            }
+            "br" => self.in_body_start(String::from("br"), Vec::new(), false),
+            _ => self.any_other_end_tag(&name),
+        }
+    }
+
+    fn any_other_end_tag(&mut self, name: &str) {
+        for i in (0..self.open.len()).rev() {
+            let id = self.open[i];
+            if self.is_html(id, &[name]) {
+                self.generate_implied_end_tags(&[name]);
+                self.open.truncate(i);
+                return;
+            }
+            if self.is_special(id) {
+                return;
+            }
+        }
+    }
+
+    fn text(&mut self, token: Token) {
+        match token {
+            Token::Text(text) => self.insert_text(&text),
+            Token::Eof => {
+                self.pop();
+                self.mode = self.original_mode;
+                self.process(token);
+            }
+            _ => {
+                self.pop();
+                self.mode = self.original_mode;
+            }
+        }
+    }
+
+    fn in_table(&mut self, token: Token) {
+        match token {
+            Token::Text(_)
+                if self.is_html(
+                    self.current(),
+                    &["table", "tbody", "template", "tfoot", "thead", "tr"],
+                ) =>
+            {
+                self.table_text.clear();
+                self.original_mode = self.mode;
+                self.mode = Mode::InTableText;
+                self.process(token);
+            }
+            Token::Comment(comment) => self.insert_comment(&comment, None),
+            Token::Doctype { .. } => (),
+            Token::StartTag { ref name, .. } if name == "caption" => {
+                self.clear_to_context(&["table"]);
+                self.formatting.push(Formatting::Marker);
+                self.insert_html(&token);
+                self.mode = Mode::InCaption;
+            }
+            Token::StartTag { ref name, .. } if name == "colgroup" => {
+                self.clear_to_context(&["table"]);
+                self.insert_html(&token);
+                self.mode = Mode::InColumnGroup;
+            }
+            Token::StartTag { ref name, .. } if name == "col" => {
+                self.clear_to_context(&["table"]);
+                self.insert_html(&start_tag("colgroup"));
+                self.mode = Mode::InColumnGroup;
+                self.process(token);
+            }
+            Token::StartTag { ref name, .. }
+                if matches!(name.as_str(), "tbody" | "tfoot" | "thead") =>
+            {
+                self.clear_to_context(&["table"]);
+                self.insert_html(&token);
+                self.mode = Mode::InTableBody;
+            }
+            Token::StartTag { ref name, .. } if matches!(name.as_str(), "td" | "th" | "tr") => {
+                self.clear_to_context(&["table"]);
+                self.insert_html(&start_tag("tbody"));
+                self.mode = Mode::InTableBody;
+                self.process(token);
+            }
+            Token::StartTag { ref name, .. } if name == "table" => {
+                if !self.in_scope(&["table"], Scope::Table) {
+                    return;
+                }
+                self.pop_until(&["table"]);
+                self.reset_insertion_mode();
+                self.process(token);
+            }
+            Token::EndTag { ref name } if name == "table" => {
+                if !self.in_scope(&["table"], Scope::Table) {
+                    return;
+                }
+                self.pop_until(&["table"]);
+                self.reset_insertion_mode();
+            }
+            Token::EndTag { ref name }
+                if matches!(
+                    name.as_str(),
+                    "body"
+                        | "caption"
+                        | "col"
+                        | "colgroup"
+                        | "html"
+                        | "tbody"
+                        | "td"
+                        | "tfoot"
+                        | "th"
+                        | "thead"
+                        | "tr"
+                ) => {}
+            Token::StartTag { ref name, .. }
+                if matches!(name.as_str(), "style" | "script" | "template") =>
+            {
+                self.in_head(token)
+            }
+            Token::EndTag { ref name } if name == "template" => self.in_head(token),
+            Token::StartTag {
+                ref name,
+                ref attrs,
+                ..
+            } if name == "input"
+                && attr_value(attrs, "type")
+                    .is_some_and(|value| value.eq_ignore_ascii_case("hidden")) =>
+            {
+                self.insert_html(&token);
+                self.pop();
+            }
+            Token::StartTag { ref name, .. } if name == "form" => {
+                let in_template = self.open.iter().any(|id| self.is_html(*id, &["template"]));
+                if self.form.is_some() || in_template {
+                    return;
+                }
+                self.form = Some(self.insert_html(&token));
+                self.pop();
+            }
+            Token::Eof => self.in_body(token),
+            token => self.in_table_else(token),
+        }
+    }
+
+    fn in_table_else(&mut self, token: Token) {
+        self.foster_parenting = true;
+        self.in_body(token);
+        self.foster_parenting = false;
+    }
+
+    fn in_table_text(&mut self, token: Token) {
+        match token {
+            Token::Text(text) => self.table_text.push_str(&text.replace('\0', "")),
+            token => {
+                let text = std::mem::take(&mut self.table_text);
+                if !is_whitespace_text(&text) {
+                    self.in_table_else(Token::Text(text));
+                } else if !text.is_empty() {
+                    self.insert_text(&text);
+                }
+                self.mode = self.original_mode;
+                self.process(token);
+            }
+        }
+    }
+
+    fn close_caption(&mut self) -> bool {
+        if !self.in_scope(&["caption"], Scope::Table) {
+            return false;
+        }
+        self.generate_implied_end_tags(&[]);
+        self.pop_until(&["caption"]);
+        self.clear_formatting_to_marker();
+        self.mode = Mode::InTable;
+        true
+    }
+
+    fn in_caption(&mut self, token: Token) {
+        if is_end(&token, &["caption"]) {
+            self.close_caption();
+        } else if is_start(
+            &token,
+            &[
+                "caption", "col", "colgroup", "tbody", "td", "tfoot", "th", "thead", "tr",
+            ],
+        ) || is_end(&token, &["table"])
+        {
+            if self.close_caption() {
+                self.process(token);
+            }
+        } else if is_end(
+            &token,
+            &[
+                "body", "col", "colgroup", "html", "tbody", "td", "tfoot", "th", "thead", "tr",
+            ],
+        ) {
+        } else {
+            self.in_body(token);
+        }
+    }
+
+    fn in_column_group(&mut self, token: Token) {
+        match token {
+            Token::Text(text) => self.split_text(
+                &text,
+                TreeBuilder::insert_text,
+                TreeBuilder::in_column_group_else,
+            ),
+            Token::Comment(comment) => self.insert_comment(&comment, None),
+            Token::Doctype { .. } => (),
+            Token::StartTag { ref name, .. } if name == "html" => self.in_body(token),
+            Token::StartTag { ref name, .. } if name == "col" => {
+                self.insert_html(&token);
+                self.pop();
+            }
+            Token::EndTag { ref name } if name == "colgroup" => {
+                if self.is_html(self.current(), &["colgroup"]) {
+                    self.pop();
+                    self.mode = Mode::InTable;
+                }
+            }
+            Token::EndTag { ref name } if name == "col" => (),
+            Token::StartTag { ref name, .. } if name == "template" => self.in_head(token),
+            Token::EndTag { ref name } if name == "template" => self.in_head(token),
+            Token::Eof => self.in_body(token),
+            token => self.in_column_group_else(token),
+        }
+    }
+
+    fn in_column_group_else(&mut self, token: Token) {
+        if !self.is_html(self.current(), &["colgroup"]) {
+            return;
+        }
+        self.pop();
+        self.mode = Mode::InTable;
+        self.process(token);
+    }
+
+    fn in_table_body(&mut self, token: Token) {
+        if is_start(&token, &["tr"]) {
+            self.clear_to_context(&["tbody", "tfoot", "thead"]);
+            self.insert_html(&token);
+            self.mode = Mode::InRow;
+        } else if is_start(&token, &["th", "td"]) {
+            self.clear_to_context(&["tbody", "tfoot", "thead"]);
+            self.insert_html(&start_tag("tr"));
+            self.mode = Mode::InRow;
+            self.process(token);
+        } else if is_end(&token, &["tbody", "tfoot", "thead"]) {
+            let name = match &token {
+                Token::EndTag { name } => name.as_str(),
+                _ => unreachable!(),
+            };
+            if !self.in_scope(&[name], Scope::Table) {
+                return;
+            }
+            self.clear_to_context(&["tbody", "tfoot", "thead"]);
+            self.pop();
+            self.mode = Mode::InTable;
+        } else if is_start(
+            &token,
+            &["caption", "col", "colgroup", "tbody", "tfoot", "thead"],
+        ) || is_end(&token, &["table"])
+        {
+            if !self.in_scope(&["tbody", "thead", "tfoot"], Scope::Table) {
+                return;
+            }
+            self.clear_to_context(&["tbody", "tfoot", "thead"]);
+            self.pop();
+            self.mode = Mode::InTable;
+            self.process(token);
+        } else if is_end(
+            &token,
+            &[
+                "body", "caption", "col", "colgroup", "html", "td", "th", "tr",
+            ],
+        ) {
+        } else {
+            self.in_table(token);
+        }
+    }
+
+    fn close_row(&mut self) -> bool {
+        if !self.in_scope(&["tr"], Scope::Table) {
+            return false;
+        }
+        self.clear_to_context(&["tr"]);
+        self.pop();
+        self.mode = Mode::InTableBody;
+        true
+    }
+
+    fn in_row(&mut self, token: Token) {
+        if is_start(&token, &["th", "td"]) {
+            self.clear_to_context(&["tr"]);
+            self.insert_html(&token);
+            self.mode = Mode::InCell;
+            self.formatting.push(Formatting::Marker);
+        } else if is_end(&token, &["tr"]) {
+            self.close_row();
+        } else if is_start(
+            &token,
+            &[
+                "caption", "col", "colgroup", "tbody", "tfoot", "thead", "tr",
+            ],
+        ) || is_end(&token, &["table"])
+        {
+            if self.close_row() {
+                self.process(token);
+            }
+        } else if is_end(&token, &["tbody", "tfoot", "thead"]) {
+            let name = match &token {
+                Token::EndTag { name } => name.as_str(),
+                _ => unreachable!(),
+            };
+            if !self.in_scope(&[name], Scope::Table) {
+                return;
+            }
+            if self.close_row() {
+                self.process(token);
+            }
+        } else if is_end(
+            &token,
+            &["body", "caption", "col", "colgroup", "html", "td", "th"],
+        ) {
+        } else {
+            self.in_table(token);
+        }
+    }
+
+    fn close_cell(&mut self) {
+        self.generate_implied_end_tags(&[]);
+        self.pop_until(&["td", "th"]);
+        self.clear_formatting_to_marker();
+        self.mode = Mode::InRow;
+    }
+
+    fn in_cell(&mut self, token: Token) {
+        if is_end(&token, &["td", "th"]) {
+            let name = match &token {
+                Token::EndTag { name } => name.as_str(),
+                _ => unreachable!(),
+            };
+            if !self.in_scope(&[name], Scope::Table) {
+                return;
+            }
+            let name = String::from(name);
+            self.generate_implied_end_tags(&[]);
+            self.pop_until(&[&name]);
+            self.clear_formatting_to_marker();
+            self.mode = Mode::InRow;
+        } else if is_start(
+            &token,
+            &[
+                "caption", "col", "colgroup", "tbody", "td", "tfoot", "th", "thead", "tr",
+            ],
+        ) {
+            if !self.in_scope(&["td", "th"], Scope::Table) {
+                return;
+            }
+            self.close_cell();
+            self.process(token);
+        } else if is_end(&token, &["body", "caption", "col", "colgroup", "html"]) {
+        } else if is_end(&token, &["table", "tbody", "tfoot", "thead", "tr"]) {
+            let name = match &token {
+                Token::EndTag { name } => name.as_str(),
+                _ => unreachable!(),
+            };
+            if !self.in_scope(&[name], Scope::Table) {
+                return;
+            }
+            self.close_cell();
+            self.process(token);
+        } else {
+            self.in_body(token);
+        }
+    }
+
+    fn in_select(&mut self, token: Token) {
+        match token {
+            Token::Text(text) => {
+                let text = text.replace('\0', "");
+                if !text.is_empty() {
+                    self.insert_text(&text);
+                }
+            }
+            Token::Comment(comment) => self.insert_comment(&comment, None),
+            Token::Doctype { .. } => (),
+            Token::StartTag { ref name, .. } if name == "html" => self.in_body(token),
+            Token::StartTag { ref name, .. } if name == "option" => {
+                if self.is_html(self.current(), &["option"]) {
+                    self.pop();
+                }
+                self.insert_html(&token);
+            }
+            Token::StartTag { ref name, .. } if name == "optgroup" => {
+                if self.is_html(self.current(), &["option"]) {
+                    self.pop();
+                }
+                if self.is_html(self.current(), &["optgroup"]) {
+                    self.pop();
+                }
+                self.insert_html(&token);
+            }
+            Token::StartTag { ref name, .. } if name == "hr" => {
+                if self.is_html(self.current(), &["option"]) {
+                    self.pop();
+                }
+                if self.is_html(self.current(), &["optgroup"]) {
+                    self.pop();
+                }
+                self.insert_html(&token);
+                self.pop();
+            }
+            Token::EndTag { ref name } if name == "optgroup" => {
+                let n = self.open.len();
+                if self.is_html(self.current(), &["option"])
+                    && n >= 2
+                    && self.is_html(self.open[n - 2], &["optgroup"])
+                {
+                    self.pop();
+                }
+                if self.is_html(self.current(), &["optgroup"]) {
+                    self.pop();
+                }
+            }
+            Token::EndTag { ref name }
+                if name == "option" && self.is_html(self.current(), &["option"]) =>
+            {
+                self.pop();
+            }
+            Token::EndTag { ref name } if name == "select" => self.close_select(),
+            Token::StartTag { ref name, .. } if name == "select" => self.close_select(),
+            Token::StartTag { ref name, .. }
+                if matches!(name.as_str(), "input" | "keygen" | "textarea") =>
+            {
+                if !self.in_scope(&["select"], Scope::Select) {
+                    return;
+                }
+                self.close_select();
+                self.process(token);
+            }
+            Token::StartTag { ref name, .. } if matches!(name.as_str(), "script" | "template") => {
+                self.in_head(token)
+            }
+            Token::EndTag { ref name } if name == "template" => self.in_head(token),
+            Token::Eof => self.in_body(token),
+            _ => (),
+        }
+    }
+
+    fn close_select(&mut self) {
+        if !self.in_scope(&["select"], Scope::Select) {
+            return;
+        }
+        self.pop_until(&["select"]);
+        self.reset_insertion_mode();
+    }
+
+    fn in_select_in_table(&mut self, token: Token) {
+        let table_tags = [
+            "caption", "table", "tbody", "tfoot", "thead", "tr", "td", "th",
+        ];
+        if is_start(&token, &table_tags) {
+            self.pop_until(&["select"]);
+            self.reset_insertion_mode();
+            self.process(token);
+        } else if is_end(&token, &table_tags) {
+            let name = match &token {
+                Token::EndTag { name } => name.as_str(),
+                _ => unreachable!(),
+            };
+            if !self.in_scope(&[name], Scope::Table) {
+                return;
+            }
+            self.pop_until(&["select"]);
+            self.reset_insertion_mode();
+            self.process(token);
+        } else {
+            self.in_select(token);
+        }
+    }
+
+    fn after_body(&mut self, token: Token) {
+        match token {
+            Token::Text(ref text) if is_whitespace_text(text) => self.in_body(token),
+            Token::Comment(comment) => {
+                let html = self.open[0];
+                self.insert_comment(&comment, Some(html));
+            }
+            Token::Doctype { .. } => (),
+            Token::StartTag { ref name, .. } if name == "html" => self.in_body(token),
+            Token::EndTag { ref name } if name == "html" => self.mode = Mode::AfterAfterBody,
+            Token::Eof => (),
+            token => {
+                self.mode = Mode::InBody;
+                self.process(token);
+            }
+        }
+    }
+
+    fn in_frameset(&mut self, token: Token) {
+        match token {
+            Token::Text(text) => {
+                let ws: String = text
+                    .chars()
+                    .filter(|c| matches!(c, '\t' | '\n' | '\x0C' | '\r' | ' '))
+                    .collect();
+                if !ws.is_empty() {
+                    self.insert_text(&ws);
+                }
+            }
+            Token::Comment(comment) => self.insert_comment(&comment, None),
+            Token::StartTag { ref name, .. } if name == "html" => self.in_body(token),
+            Token::StartTag { ref name, .. } if name == "frameset" => {
+                self.insert_html(&token);
+            }
+            Token::EndTag { ref name } if name == "frameset" => {
+                if self.open.len() == 1 {
+                    return;
+                }
+                self.pop();
+                if !self.is_html(self.current(), &["frameset"]) {
+                    self.mode = Mode::AfterFrameset;
+                }
+            }
+            Token::StartTag { ref name, .. } if name == "frame" => {
+                self.insert_html(&token);
+                self.pop();
+            }
+            Token::StartTag { ref name, .. } if name == "noframes" => self.in_head(token),
+            _ => (),
+        }
+    }
+
+    fn after_frameset(&mut self, token: Token) {
+        match token {
+            Token::Text(_) => self.in_frameset(token),
+            Token::Comment(comment) => self.insert_comment(&comment, None),
+            Token::StartTag { ref name, .. } if name == "html" => self.in_body(token),
+            Token::EndTag { ref name } if name == "html" => self.mode = Mode::AfterAfterFrameset,
+            Token::StartTag { ref name, .. } if name == "noframes" => self.in_head(token),
+            _ => (),
+        }
+    }
+
+    fn after_after_body(&mut self, token: Token) {
+        match token {
+            Token::Comment(comment) => self.insert_comment(&comment, Some(DOCUMENT)),
+            Token::Doctype { .. } | Token::Eof => self.in_body(token),
+            Token::Text(ref text) if is_whitespace_text(text) => self.in_body(token),
+            Token::StartTag { ref name, .. } if name == "html" => self.in_body(token),
+            token => {
+                self.mode = Mode::InBody;
+                self.process(token);
+            }
+        }
+    }
+
+    fn after_after_frameset(&mut self, token: Token) {
+        match token {
+            Token::Comment(comment) => self.insert_comment(&comment, Some(DOCUMENT)),
+            Token::Doctype { .. } | Token::Eof => self.in_body(token),
+            Token::Text(_) => self.in_frameset(token),
+            Token::StartTag { ref name, .. } if name == "html" => self.in_body(token),
+            Token::StartTag { ref name, .. } if name == "noframes" => self.in_head(token),
+            _ => (),
+        }
+    }
+
+    // ---- foreign content ----
+
+    fn insert_foreign(
+        &mut self,
+        name: &str,
+        attrs: Vec<(String, String)>,
+        ns: Namespace,
+        self_closing: bool,
+    ) {
+        let (name, attrs) = match ns {
+            Namespace::Svg => (
+                names::adjust(&names::SVG_TAG_NAMES, name),
+                attrs
+                    .into_iter()
+                    .map(|(n, v)| (String::from(names::adjust(&names::SVG_ATTR_NAMES, &n)), v))
+                    .collect(),
+            ),
+            Namespace::MathMl => (
+                name,
+                attrs
+                    .into_iter()
+                    .map(|(n, v)| {
+                        if n == "definitionurl" {
+                            (String::from("definitionURL"), v)
+                        } else {
+                            (n, v)
+                        }
+                    })
+                    .collect(),
+            ),
+            Namespace::Html => (name, attrs),
+        };
+        self.insert_element(name, attrs, ns);
+        if self_closing {
+            self.pop();
+        }
+    }
+
+    fn in_foreign_content(&mut self, token: Token) {
+        match token {
+            Token::Text(text) => {
+                let text = text.replace('\0', "\u{FFFD}");
+                self.insert_text(&text);
+                if !is_whitespace_text(&text) {
+                    self.frameset_ok = false;
+                }
+            }
+            Token::Comment(comment) => self.insert_comment(&comment, None),
+            Token::Doctype { .. } => (),
+            Token::StartTag {
+                ref name,
+                ref attrs,
+                ..
+            } if names::FOREIGN_BREAKOUT.contains(&name.as_str())
+                || (name == "font"
+                    && attrs
+                        .iter()
+                        .any(|(n, _)| matches!(n.as_str(), "color" | "face" | "size"))) =>
+            {
+                self.breakout(token)
+            }
+            Token::EndTag { ref name } if name == "br" || name == "p" => self.breakout(token),
+            Token::StartTag {
+                name,
+                attrs,
+                self_closing,
+            } => {
+                let ns = self.ns(self.current());
+                self.insert_foreign(&name, attrs, ns, self_closing);
+            }
+            Token::EndTag { name } => {
+                for i in (1..self.open.len()).rev() {
+                    let id = self.open[i];
+                    if self.name(id).eq_ignore_ascii_case(&name) {
+                        self.open.truncate(i);
+                        return;
+                    }
+                    if self.ns(self.open[i - 1]) == Namespace::Html {
+                        return self.process(Token::EndTag { name });
+                    }
+                }
+            }
+            Token::Eof => self.process(token),
+        }
+    }
+
+    /// Leave the foreign content and process the token as HTML.
+    fn breakout(&mut self, token: Token) {
+        while let Some(&id) = self.open.last() {
+            if self.ns(id) == Namespace::Html
+                || self.is_mathml_text_integration_point(id)
+                || self.is_html_integration_point(id)
+            {
+                break;
+            }
+            self.pop();
+        }
+        self.process(token);
+    }
+}
@@ -4,6 +4,12 @@
 //! * Create a Dom structure tree from the tag document.
 //! * Search for tags and text from the Dom structure tree.
 //! * Search subtrees from the Dom structure tree.
+//! * Select tags from the Dom structure tree with CSS selectors.
+//! * Evaluate XPath 1.0 expressions over the Dom structure tree.
+//! * Parse HTML as web browsers do with the HTML5 tree construction algorithm.
+//! * Check that XML documents are well-formed.
+//! * Read large documents as a stream of events without building the tree.
+//! * Split documents into tokens with their spans in the source.
 //!
 //! # Usage
 //! Add this to your `Cargo.toml`:
@@ -115,13 +121,13 @@
 //! ```
 //! output:
 //! ```text
-//! <ul class="targetList" id="list1">
+//! <ul id="list1" class="targetList">
 //!   <li class="key1">
 //!     TEXT: "1-1"
 //!   <li class="key2">
 //!     <span>
 //!       TEXT: "1-2"
-//! <ul class="targetList" id="list3">
+//! <ul id="list3" class="targetList">
 //!   <li class="key1">
 //!     TEXT: "3-1"
 //!   <li class="item">
@@ -130,6 +136,26 @@
 //!     TEXT: "3-3"
 //! ```
 //!
+//! **Example of selecting tags with a CSS selector.**  
+//! Get the `li` tags with the `key1` class directly under the `ul` tags with the `targetList` class.
+//! ```rust
+//! let doc = r#"
+//! <ul id="list1" class="targetList">
+//!   <li class="key1">1-1</li>
+//!   <li class="key2">1-2</li>
+//! </ul>
+//! <ul id="list2">
+//!   <li class="key1">2-1</li>
+//! </ul>
+//! "#;
+//!
+//! let root_dom = parsercher::parse(&doc).unwrap();
+//!
+//! let result = root_dom.select("ul.targetList > li.key1").unwrap();
+//! assert_eq!(result.len(), 1);
+//! assert_eq!(result[0].to_html(), r#"<li class="key1">1-1</li>"#);
+//! ```
+//!
 //! **More complex examples of Dom structure tree**
 //! ```rust
 //! use parsercher;
@@ -160,344 +186,77 @@
 //! </html>
 //! "#;
 //!
-//! if let Ok(dom) = parsercher::parse(&html) {
-//!     println!("{:#?}", dom);
-//! }
-//! ```
+//! let root_dom = parsercher::parse(&html).unwrap();
 //!
-//! output:
-//! ```text
-//! Dom {
-//!     dom_type: Tag,
-//!     tag: Some(
-//!         Tag {
-//!             name: "root",
-//!             attr: None,
-//!             terminated: false,
-//!             terminator: false,
-//!         },
-//!     ),
-//!     text: None,
-//!     comment: None,
-//!     children: Some(
-//!         [
-//!             Dom {
-//!                 dom_type: Tag,
-//!                 tag: Some(
-//!                     Tag {
-//!                         name: "!DOCTYPE",
-//!                         attr: Some(
-//!                             {
-//!                                 "html": "",
-//!                             },
-//!                         ),
-//!                         terminated: false,
-//!                         terminator: false,
-//!                     },
-//!                 ),
-//!                 text: None,
-//!                 comment: None,
-//!                 children: None,
-//!             },
-//!             Dom {
-//!                 dom_type: Tag,
-//!                 tag: Some(
-//!                     Tag {
-//!                         name: "html",
-//!                         attr: None,
-//!                         terminated: false,
-//!                         terminator: false,
-//!                     },
-//!                 ),
-//!                 text: None,
-//!                 comment: None,
-//!                 children: Some(
-//!                     [
-//!                         Dom {
-//!                             dom_type: Tag,
-//!                             tag: Some(
-//!                                 Tag {
-//!                                     name: "head",
-//!                                     attr: None,
-//!                                     terminated: false,
-//!                                     terminator: false,
-//!                                 },
-//!                             ),
-//!                             text: None,
-//!                             comment: None,
-//!                             children: Some(
-//!                                 [
-//!                                     Dom {
-//!                                         dom_type: Tag,
-//!                                         tag: Some(
-//!                                             Tag {
-//!                                                 name: "meta",
-//!                                                 attr: Some(
-//!                                                     {
-//!                                                         "charset": "UTF-8",
-//!                                                     },
-//!                                                 ),
-//!                                                 terminated: false,
-//!                                                 terminator: false,
-//!                                             },
-//!                                         ),
-//!                                         text: None,
-//!                                         comment: None,
-//!                                         children: None,
-//!                                     },
-//!                                     Dom {
-//!                                         dom_type: Tag,
-//!                                         tag: Some(
-//!                                             Tag {
-//!                                                 name: "title",
-//!                                                 attr: None,
-//!                                                 terminated: false,
-//!                                                 terminator: false,
-//!                                             },
-//!                                         ),
-//!                                         text: None,
-//!                                         comment: None,
-//!                                         children: Some(
-//!                                             [
-//!                                                 Dom {
-//!                                                     dom_type: Text,
-//!                                                     tag: None,
-//!                                                     text: Some(
-//!                                                         Text {
-//!                                                             text: "sample html",
-//!                                                         },
-//!                                                     ),
-//!                                                     comment: None,
-//!                                                     children: None,
-//!                                                 },
-//!                                             ],
-//!                                         ),
-//!                                     },
-//!                                 ],
-//!                             ),
-//!                         },
-//!                         Dom {
-//!                             dom_type: Tag,
-//!                             tag: Some(
-//!                                 Tag {
-//!                                     name: "body",
-//!                                     attr: None,
-//!                                     terminated: false,
-//!                                     terminator: false,
-//!                                 },
-//!                             ),
-//!                             text: None,
-//!                             comment: None,
-//!                             children: Some(
-//!                                 [
-//!                                     Dom {
-//!                                         dom_type: Tag,
-//!                                         tag: Some(
-//!                                             Tag {
-//!                                                 name: "h1",
-//!                                                 attr: None,
-//!                                                 terminated: false,
-//!                                                 terminator: false,
-//!                                             },
-//!                                         ),
-//!                                         text: None,
-//!                                         comment: None,
-//!                                         children: Some(
-//!                                             [
-//!                                                 Dom {
-//!                                                     dom_type: Text,
-//!                                                     tag: None,
-//!                                                     text: Some(
-//!                                                         Text {
-//!                                                             text: "Hello, world!",
-//!                                                         },
-//!                                                     ),
-//!                                                     comment: None,
-//!                                                     children: None,
-//!                                                 },
-//!                                             ],
-//!                                         ),
-//!                                     },
-//!                                     Dom {
-//!                                         dom_type: Tag,
-//!                                         tag: Some(
-//!                                             Tag {
-//!                                                 name: "div",
-//!                                                 attr: Some(
-//!                                                     {
-//!                                                         "id": "content",
-//!                                                     },
-//!                                                 ),
-//!                                                 terminated: false,
-//!                                                 terminator: false,
-//!                                             },
-//!                                         ),
-//!                                         text: None,
-//!                                         comment: None,
-//!                                         children: None,
-//!                                     },
-//!                                     Dom {
-//!                                         dom_type: Tag,
-//!                                         tag: Some(
-//!                                             Tag {
-//!                                                 name: "ol",
-//!                                                 attr: None,
-//!                                                 terminated: false,
-//!                                                 terminator: false,
-//!                                             },
-//!                                         ),
-//!                                         text: None,
-//!                                         comment: None,
-//!                                         children: Some(
-//!                                             [
-//!                                                 Dom {
-//!                                                     dom_type: Tag,
-//!                                                     tag: Some(
-//!                                                         Tag {
-//!                                                             name: "li",
-//!                                                             attr: None,
-//!                                                             terminated: false,
-//!                                                             terminator: false,
-//!                                                         },
-//!                                                     ),
-//!                                                     text: None,
-//!                                                     comment: None,
-//!                                                     children: Some(
-//!                                                         [
-//!                                                             Dom {
-//!                                                                 dom_type: Text,
-//!                                                                 tag: None,
-//!                                                                 text: Some(
-//!                                                                     Text {
-//!                                                                         text: "first",
-//!                                                                     },
-//!                                                                 ),
-//!                                                                 comment: None,
-//!                                                                 children: None,
-//!                                                             },
-//!                                                         ],
-//!                                                     ),
-//!                                                 },
-//!                                                 Dom {
-//!                                                     dom_type: Tag,
-//!                                                     tag: Some(
-//!                                                         Tag {
-//!                                                             name: "li",
-//!                                                             attr: None,
-//!                                                             terminated: false,
-//!                                                             terminator: false,
-//!                                                         },
-//!                                                     ),
-//!                                                     text: None,
-//!                                                     comment: None,
-//!                                                     children: Some(
-//!                                                         [
-//!                                                             Dom {
-//!                                                                 dom_type: Text,
-//!                                                                 tag: None,
-//!                                                                 text: Some(
-//!                                                                     Text {
-//!                                                                         text: "second",
-//!                                                                     },
-//!                                                                 ),
-//!                                                                 comment: None,
-//!                                                                 children: None,
-//!                                                             },
-//!                                                         ],
-//!                                                     ),
-//!                                                 },
-//!                                                 Dom {
-//!                                                     dom_type: Tag,
-//!                                                     tag: Some(
-//!                                                         Tag {
-//!                                                             name: "li",
-//!                                                             attr: None,
-//!                                                             terminated: false,
-//!                                                             terminator: false,
-//!                                                         },
-//!                                                     ),
-//!                                                     text: None,
-//!                                                     comment: None,
-//!                                                     children: Some(
-//!                                                         [
-//!                                                             Dom {
-//!                                                                 dom_type: Text,
-//!                                                                 tag: None,
-//!                                                                 text: Some(
-//!                                                                     Text {
-//!                                                                         text: "therd",
-//!                                                                     },
-//!                                                                 ),
-//!                                                                 comment: None,
-//!                                                                 children: None,
-//!                                                             },
-//!                                                         ],
-//!                                                     ),
-//!                                                 },
-//!                                             ],
-//!                                         ),
-//!                                     },
-//!                                     Dom {
-//!                                         dom_type: Comment,
-//!                                         tag: None,
-//!                                         text: None,
-//!                                         comment: Some(
-//!                                             Comment {
-//!                                                 comment: " All script code becomes one text ",
-//!                                             },
-//!                                         ),
-//!                                         children: None,
-//!                                     },
-//!                                     Dom {
-//!                                         dom_type: Tag,
-//!                                         tag: Some(
-//!                                             Tag {
-//!                                                 name: "script",
-//!                                                 attr: None,
-//!                                                 terminated: false,
-//!                                                 terminator: false,
-//!                                             },
-//!                                         ),
-//!                                         text: None,
-//!                                         comment: None,
-//!                                         children: Some(
-//!                                             [
-//!                                                 Dom {
-//!                                                     dom_type: Text,
-//!                                                     tag: None,
-//!                                                     text: Some(
-//!                                                         Text {
-//!                                                             text: "\n  let content = document.getElementById(\'content\');\n  content.textContent = \'content\';\n",
-//!                                                         },
-//!                                                     ),
-//!                                                     comment: None,
-//!                                                     children: None,
-//!                                                 },
-//!                                             ],
-//!                                         ),
-//!                                     },
-//!                                 ],
-//!                             ),
-//!                         },
-//!                     ],
-//!                 ),
-//!             },
-//!         ],
-//!     ),
-//! }
+//! // the document type declaration and the html tag are the children of the root
+//! let children = root_dom.get_children().unwrap();
+//! assert_eq!(children[0].get_doctype().unwrap().get_name(), "html");
+//! assert_eq!(children[1].get_tag().unwrap().get_name(), "html");
+//!
+//! // all script code becomes one text
+//! let script_dom = root_dom.select("script").unwrap()[0];
+//! let text_dom = script_dom.get_children().unwrap().first().unwrap();
+//! assert_eq!(text_dom.get_text().unwrap().get_text(), r#"
+//!   let content = document.getElementById('content');
+//!   content.textContent = 'content';
+//! "#);
+//!
+//! // each Dom structure has the range of the source document it was parsed from
+//! let li_dom = root_dom.select("li").unwrap()[1];
+//! let span = li_dom.get_span().unwrap();
+//! assert_eq!(&html[span.get_range()], "<li>second</li>");
+//! assert_eq!(span.get_start().get_line(), 15);
+//! assert_eq!(span.get_start().get_column(), 7);
 //! ```
 //!
 
 pub mod dom;
+mod entity;
+mod html5;
 mod parser;
 mod searcher;
+mod selector;
+mod serializer;
+mod visitor;
+mod xpath;
+
+pub use html5::parse_html5;
 
 pub use parser::parse;
+pub use parser::parse_with;
+pub use parser::parse_xml_strict;
 pub use parser::print_dom_tree;
+pub use parser::Event;
+pub use parser::ParseError;
+pub use parser::ParseMode;
+pub use parser::ParseOptions;
+pub use parser::ReadError;
+pub use parser::Reader;
+pub use parser::Token;
+pub use parser::Tokenizer;
 
 pub use searcher::search_attr;
 pub use searcher::search_attrs;
 pub use searcher::search_dom;
+pub use searcher::search_dom_refs;
 pub use searcher::search_tag;
 pub use searcher::search_tag_from_name;
+pub use searcher::search_tag_from_name_refs;
+pub use searcher::search_tag_refs;
 pub use searcher::search_text_from_tag_children;
+
+pub use selector::Selector;
+pub use selector::SelectorError;
+
+pub use serializer::serialize;
+pub use serializer::serialize_with;
+pub use serializer::SerializeOptions;
+
+pub use visitor::VisitAction;
+pub use visitor::Visitor;
+pub use visitor::VisitorMut;
+
+pub use xpath::XPath;
+pub use xpath::XPathError;
+pub use xpath::XPathNode;
+pub use xpath::XPathValue;
@@ -0,0 +1,230 @@
+//! Module of ParseError.
+
+use std::error::Error;
+use std::fmt;
+
+use crate::dom::Position;
+
+/// Errors that occur while parsing a tag document.
+///
+/// Each error has the position where the offending construct starts
+/// and a snippet of the source from that position.
+#[derive(Debug, PartialEq, Clone)]
+pub enum ParseError {
+    /// The document ends in the middle of a tag. e.g. `<div class="a"`
+    UnterminatedTag { position: Position, snippet: String },
+    /// The document ends in the middle of a comment. e.g. `<!-- comment`
+    UnterminatedComment { position: Position, snippet: String },
+    /// The document ends before the delimiter that closes an attribute value.
+    /// e.g. `<div class="a>`
+    UnterminatedAttrValue {
+        delimiter: char,
+        position: Position,
+        snippet: String,
+    },
+    /// The tag ends before the delimiter that closes an attribute value.
+    /// e.g. `<div class="a>text</div>`
+    MissingAttrDelimiter {
+        delimiter: char,
+        position: Position,
+        snippet: String,
+    },
+    /// The document ends before the end tag of an element whose content is text.
+    /// e.g. `<script>let a = 1;`
+    UnterminatedRawText {
+        name: String,
+        position: Position,
+        snippet: String,
+    },
+    /// The document ends in the middle of a CDATA section. e.g. `<![CDATA[ a < b`
+    UnterminatedCData { position: Position, snippet: String },
+    /// The document ends in the middle of a processing instruction.
+    /// e.g. `<?xml version="1.0"`
+    UnterminatedProcessingInstruction { position: Position, snippet: String },
+    /// The end tag does not match the innermost open element in strict XML.
+    /// e.g. `<a><b></a>`
+    MismatchedEndTag {
+        expected: String,
+        found: String,
+        position: Position,
+        snippet: String,
+    },
+    /// The end tag has no open element in strict XML. e.g. `<a></a></b>`
+    UnexpectedEndTag {
+        name: String,
+        position: Position,
+        snippet: String,
+    },
+    /// The document ends before the end tag of the element in strict XML. e.g. `<a><b></b>`
+    UnclosedElement {
+        name: String,
+        position: Position,
+        snippet: String,
+    },
+    /// The document has more than one root element in strict XML. e.g. `<a></a><b></b>`
+    MultipleRootElements { position: Position, snippet: String },
+    /// The document has no root element in strict XML.
+    MissingRootElement { position: Position, snippet: String },
+    /// The document has text outside the root element in strict XML. e.g. `<a></a>text`
+    TextOutsideRoot { position: Position, snippet: String },
+    /// The attribute appears more than once in the tag in strict XML. e.g. `<a id="1" id="2">`
+    DuplicateAttr {
+        name: String,
+        position: Position,
+        snippet: String,
+    },
+    /// The attribute value is not quoted in strict XML. e.g. `<a id=1>`, `<input checked>`
+    UnquotedAttrValue {
+        name: String,
+        position: Position,
+        snippet: String,
+    },
+    /// The attribute value contains `<` in strict XML. e.g. `<a title="a<b">`
+    LtInAttrValue {
+        name: String,
+        position: Position,
+        snippet: String,
+    },
+    /// The entity reference is not defined or not terminated in strict XML. e.g. `&nbsp;`, `a & b`
+    UndefinedEntity {
+        name: String,
+        position: Position,
+        snippet: String,
+    },
+}
+
+impl ParseError {
+    /// Returns the position where the error occurred.
+    pub fn position(&self) -> Position {
+        match self {
+            ParseError::UnterminatedTag { position, .. }
+            | ParseError::UnterminatedComment { position, .. }
+            | ParseError::UnterminatedAttrValue { position, .. }
+            | ParseError::MissingAttrDelimiter { position, .. }
+            | ParseError::UnterminatedRawText { position, .. }
+            | ParseError::UnterminatedCData { position, .. }
+            | ParseError::UnterminatedProcessingInstruction { position, .. }
+            | ParseError::MismatchedEndTag { position, .. }
+            | ParseError::UnexpectedEndTag { position, .. }
+            | ParseError::UnclosedElement { position, .. }
+            | ParseError::MultipleRootElements { position, .. }
+            | ParseError::MissingRootElement { position, .. }
+            | ParseError::TextOutsideRoot { position, .. }
+            | ParseError::DuplicateAttr { position, .. }
+            | ParseError::UnquotedAttrValue { position, .. }
+            | ParseError::LtInAttrValue { position, .. }
+            | ParseError::UndefinedEntity { position, .. } => *position,
+        }
+    }
+
+    /// Returns the snippet of the source from the position where the error occurred.
+    pub fn snippet(&self) -> &str {
+        match self {
+            ParseError::UnterminatedTag { snippet, .. }
+            | ParseError::UnterminatedComment { snippet, .. }
+            | ParseError::UnterminatedAttrValue { snippet, .. }
+            | ParseError::MissingAttrDelimiter { snippet, .. }
+            | ParseError::UnterminatedRawText { snippet, .. }
+            | ParseError::UnterminatedCData { snippet, .. }
+            | ParseError::UnterminatedProcessingInstruction { snippet, .. }
+            | ParseError::MismatchedEndTag { snippet, .. }
+            | ParseError::UnexpectedEndTag { snippet, .. }
+            | ParseError::UnclosedElement { snippet, .. }
+            | ParseError::MultipleRootElements { snippet, .. }
+            | ParseError::MissingRootElement { snippet, .. }
+            | ParseError::TextOutsideRoot { snippet, .. }
+            | ParseError::DuplicateAttr { snippet, .. }
+            | ParseError::UnquotedAttrValue { snippet, .. }
+            | ParseError::LtInAttrValue { snippet, .. }
+            | ParseError::UndefinedEntity { snippet, .. } => snippet,
+        }
+    }
+
+    fn message(&self) -> String {
+        match self {
+            ParseError::UnterminatedTag { .. } => {
+                String::from("Input ends in the middle of the tag")
+            }
+            ParseError::UnterminatedComment { .. } => {
+                String::from("Input ends in the middle of the comment")
+            }
+            ParseError::UnterminatedAttrValue { delimiter, .. } => {
+                format!("Input ends in the middle of delimiter({})", delimiter)
+            }
+            ParseError::MissingAttrDelimiter { delimiter, .. } => format!(
+                "There is no delimiter({}) to terminate the attribute",
+                delimiter
+            ),
+            ParseError::UnterminatedRawText { name, .. } => {
+                format!("Input ends before the end tag of <{}>", name)
+            }
+            ParseError::UnterminatedCData { .. } => {
+                String::from("Input ends in the middle of the CDATA section")
+            }
+            ParseError::UnterminatedProcessingInstruction { .. } => {
+                String::from("Input ends in the middle of the processing instruction")
+            }
+            ParseError::MismatchedEndTag {
+                expected, found, ..
+            } => format!("Expected </{}> but found </{}>", expected, found),
+            ParseError::UnexpectedEndTag { name, .. } => {
+                format!("There is no open element for </{}>", name)
+            }
+            ParseError::UnclosedElement { name, .. } => {
+                format!("Input ends before the end tag of <{}>", name)
+            }
+            ParseError::MultipleRootElements { .. } => {
+                String::from("There is more than one root element")
+            }
+            ParseError::MissingRootElement { .. } => String::from("There is no root element"),
+            ParseError::TextOutsideRoot { .. } => {
+                String::from("There is text outside the root element")
+            }
+            ParseError::DuplicateAttr { name, .. } => {
+                format!("The attribute({}) appears more than once", name)
+            }
+            ParseError::UnquotedAttrValue { name, .. } => {
+                format!("The value of the attribute({}) is not quoted", name)
+            }
+            ParseError::LtInAttrValue { name, .. } => {
+                format!("The value of the attribute({}) contains '<'", name)
+            }
+            ParseError::UndefinedEntity { name, .. } => {
+                format!("The entity reference(&{}) is not defined", name)
+            }
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let position = self.position();
+        write!(
+            f,
+            "{} at line {}, column {}: `{}`",
+            self.message(),
+            position.get_line(),
+            position.get_column(),
+            self.snippet()
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_test() {
+        let e = ParseError::UnterminatedTag {
+            position: Position::new(4, 2, 3),
+            snippet: String::from("<div"),
+        };
+        assert_eq!(
+            e.to_string(),
+            "Input ends in the middle of the tag at line 2, column 3: `<div`"
+        );
+    }
+}
@@ -1,20 +1,191 @@
-#[derive(Debug)]
-pub struct Input {
+use std::io;
+use std::io::Read;
+use std::str;
+
+use crate::dom::Position;
+use crate::dom::Span;
+
+/// Maximum number of characters in the snippet attached to an error.
+const SNIPPET_LEN: usize = 30;
+
+/// Number of bytes read from the source at once.
+const CHUNK_SIZE: usize = 8192;
+
+/// The characters of the tag document.
+///
+/// Created by `Input::new()`, it has the whole document.
+/// Created by `Input::from_reader()`, it reads the source in chunks when the characters
+/// after the loaded ones are needed, and `discard()` drops the characters already parsed.
+/// The cursors are indexes of the loaded characters.
+pub struct Input<'a> {
     input: Vec<char>,
     cursor: usize,
+    /// Byte offset of each character. The last element is the end in bytes.
+    offsets: Vec<usize>,
+    /// Index of the first character of each line. The first element is the first character.
+    line_starts: Vec<usize>,
+    /// Number of lines before the first character.
+    base_line: usize,
+    /// Number of characters before the first character in its line.
+    base_column: usize,
+    /// The rest of the document. `None` if it has been read to the end.
+    source: Option<Box<dyn Read + 'a>>,
+    /// Bytes of an incomplete UTF-8 sequence at the end of the last chunk.
+    pending: Vec<u8>,
+    /// Whitespace at the end of the last chunk.
+    held: String,
+    /// If true, `discard()` drops the characters already parsed.
+    bounded: bool,
+    /// The error that stopped reading the source.
+    error: Option<io::Error>,
 }
 
-impl Input {
-    pub fn new(input: &str) -> Input {
+impl<'a> Input<'a> {
+    pub fn new(input: &str) -> Input<'a> {
+        let mut res = Input::empty(None);
+        res.push_str(input.trim_end());
+        res
+    }
+
+    /// Create the Input that reads `source` in chunks.
+    /// Invalid UTF-8 sequences are replaced with U+FFFD.
+    pub fn from_reader<R: Read + 'a>(source: R) -> Input<'a> {
+        let mut res = Input::empty(Some(Box::new(source)));
+        res.bounded = true;
+        res
+    }
+
+    fn empty(source: Option<Box<dyn Read + 'a>>) -> Input<'a> {
         Input {
-            input: input.trim_end().chars().collect(),
+            input: Vec::new(),
             cursor: 0,
+            offsets: vec![0],
+            line_starts: vec![0],
+            base_line: 0,
+            base_column: 0,
+            source,
+            pending: Vec::new(),
+            held: String::new(),
+            bounded: false,
+            error: None,
+        }
+    }
+
+    fn push_str(&mut self, s: &str) {
+        let mut offset = self.offsets.pop().unwrap();
+        for c in s.chars() {
+            self.input.push(c);
+            self.offsets.push(offset);
+            offset += c.len_utf8();
+            if c == '\n' {
+                self.line_starts.push(self.input.len());
+            }
         }
+        self.offsets.push(offset);
+    }
+
+    /// Read the next chunk from the source.
+    /// Returns false if the source has been read to the end.
+    ///
+    /// The whitespace at the end of the chunk is held until the next chunk,
+    /// so that the whitespace at the end of the document is never loaded.
+    fn read_chunk(&mut self) -> bool {
+        let source = match &mut self.source {
+            Some(source) => source,
+            None => return false,
+        };
+
+        let mut buf = [0; CHUNK_SIZE];
+        let len = loop {
+            match source.read(&mut buf) {
+                Ok(len) => break len,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    self.error = Some(e);
+                    break 0;
+                }
+            }
+        };
+
+        let mut s = std::mem::take(&mut self.held);
+        let bytes = std::mem::take(&mut self.pending);
+        if len == 0 {
+            self.source = None;
+            s.push_str(&String::from_utf8_lossy(&bytes));
+            self.push_str(s.trim_end());
+            return false;
+        }
+
+        let mut bytes = bytes;
+        bytes.extend_from_slice(&buf[..len]);
+        let mut rest = &bytes[..];
+        loop {
+            match str::from_utf8(rest) {
+                Ok(valid) => {
+                    s.push_str(valid);
+                    break;
+                }
+                Err(e) => {
+                    let (valid, after) = rest.split_at(e.valid_up_to());
+                    s.push_str(str::from_utf8(valid).unwrap());
+                    match e.error_len() {
+                        Some(invalid) => {
+                            s.push(char::REPLACEMENT_CHARACTER);
+                            rest = &after[invalid..];
+                        }
+                        None => {
+                            // the sequence continues in the next chunk
+                            self.pending = after.to_vec();
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        let end = s.trim_end().len();
+        self.push_str(&s[..end]);
+        self.held = s.split_off(end);
+        true
+    }
+
+    /// Read the source until `len` characters are loaded or the source ends.
+    fn fill_to(&mut self, len: usize) {
+        while self.input.len() < len && self.read_chunk() {}
+    }
+
+    /// Drop the characters before the cursor if many of them have been parsed.
+    /// The cursors before it become invalid.
+    /// Does nothing if created by `Input::new()`.
+    pub fn discard(&mut self) {
+        let k = self.cursor;
+        if !self.bounded || k < CHUNK_SIZE {
+            return;
+        }
+        let line = self.line_index(k);
+        if line == 0 {
+            self.base_column += k;
+        } else {
+            self.base_column = k - self.line_starts[line];
+        }
+        self.base_line += line;
+        let mut line_starts = vec![0];
+        line_starts.extend(self.line_starts[line + 1..].iter().map(|start| start - k));
+        self.line_starts = line_starts;
+        self.input.drain(..k);
+        self.offsets.drain(..k);
+        self.cursor = 0;
+    }
+
+    /// Returns the error that stopped reading the source.
+    pub fn take_error(&mut self) -> Option<io::Error> {
+        self.error.take()
     }
 
     pub fn set_cursor(&mut self, cursor: usize) {
+        self.fill_to(cursor + 1);
         if self.input.len() <= cursor {
-            self.cursor = self.input.len() - 1;
+            self.cursor = self.input.len().saturating_sub(1);
             return;
         }
         self.cursor = cursor;
@@ -24,9 +195,23 @@ impl Input {
         self.cursor
     }
 
+    /// Returns the number of characters in the `self.input`.
+    /// The source is read to the end.
+    pub fn get_len(&mut self) -> usize {
+        while self.read_chunk() {}
+        self.input.len()
+    }
+
+    /// Returns true if there is no character.
+    pub fn is_empty(&mut self) -> bool {
+        self.fill_to(1);
+        self.input.is_empty()
+    }
+
     /// Move the `self.cursor` to the next character.
     pub fn next(&mut self) {
-        if self.cursor < self.input.len() - 1 {
+        self.fill_to(self.cursor + 2);
+        if self.cursor + 1 < self.input.len() {
             self.cursor += 1;
         }
     }
@@ -34,50 +219,37 @@ impl Input {
     /// Move the self.cursor to the next character.
     /// Skip ' ' and '\n'.
     pub fn next_char(&mut self) {
-        if self.cursor < self.input.len() - 1 {
-            self.cursor += 1;
-        }
-
-        let bgn = self.cursor;
-        for i in bgn..self.input.len() {
-            if self.input[i] == ' ' || self.input[i] == '\n' {
-                if self.cursor == self.input.len() - 1 {
-                    break;
-                }
-                self.cursor += 1;
-            } else {
+        self.next();
+        while self.expect(' ') || self.expect('\n') {
+            self.fill_to(self.cursor + 2);
+            if self.cursor + 1 == self.input.len() {
                 break;
             }
+            self.cursor += 1;
         }
     }
 
     /// Returns true if the `self.cursor` has reached the end of the `self.input`.
     pub fn is_end(&mut self) -> bool {
-        if self.cursor == self.input.len() - 1 {
-            return true;
-        }
-        false
+        self.fill_to(self.cursor + 2);
+        self.cursor + 1 >= self.input.len()
     }
 
     /// Returns true if the character pointed to by `self.cursor` is equal to `exp`.
     pub fn expect(&self, exp: char) -> bool {
-        if self.input[self.cursor] == exp {
-            return true;
-        } else {
-            return false;
-        }
+        self.input.get(self.cursor) == Some(&exp)
     }
 
     /// Returns true if the string pointed to by the `self.cursor` is equal to `exp`.
-    pub fn expect_str(&self, exp: &str) -> bool {
+    pub fn expect_str(&mut self, exp: &str) -> bool {
+        self.fill_to(self.cursor + exp.len());
         if self.input.len() < self.cursor + exp.len() {
             return false;
         }
 
         let cursor = self.cursor;
-        let exp: Vec<char> = exp.chars().collect();
-        for i in 0..exp.len() {
-            if exp[i] != self.input[cursor + i] {
+        for (i, c) in exp.chars().enumerate() {
+            if c != self.input[cursor + i] {
                 return false;
             }
         }
@@ -86,84 +258,78 @@ impl Input {
 
     /// Returns true if the string pointed to by the `self.cursor` is equal to `exp`.
     /// case insensitive.
-    pub fn expect_str_insensitive(&self, exp: &str) -> bool {
+    pub fn expect_str_insensitive(&mut self, exp: &str) -> bool {
+        self.fill_to(self.cursor + exp.len());
         if self.input.len() < self.cursor + exp.len() {
             return false;
         }
 
         let cursor = self.cursor;
-        let exp: Vec<char> = exp.to_lowercase().chars().collect();
-        for i in 0..exp.len() {
-            if exp[i] != self.input[cursor + i].to_ascii_lowercase() {
+        for (i, c) in exp.to_lowercase().chars().enumerate() {
+            if c != self.input[cursor + i].to_ascii_lowercase() {
                 return false;
             }
         }
         true
     }
 
-    /// If there is a `needle` after the `self.cursor` position, that position is returned.
-    pub fn find(&mut self, needle: char) -> Option<usize> {
-        let bgn = self.cursor;
-        if self.input.len() <= bgn {
-            return None;
-        }
-        for i in bgn..self.input.len() {
-            if self.input[i] == needle {
-                return Some(i);
+    /// Returns the position of the first `len` characters after the `self.cursor`
+    /// for which `matches` returns true. The source is read until it is found.
+    fn find_with<F: Fn(&[char]) -> bool>(&mut self, len: usize, matches: F) -> Option<usize> {
+        let mut bgn = self.cursor;
+        loop {
+            if len <= self.input.len() {
+                let last = self.input.len() - len;
+                if let Some(i) = (bgn..=last).find(|&i| matches(&self.input[i..i + len])) {
+                    return Some(i);
+                }
+                bgn = bgn.max(last + 1);
+            }
+            if !self.read_chunk() {
+                return None;
             }
         }
-        return None;
+    }
+
+    /// If there is a `needle` after the `self.cursor` position, that position is returned.
+    pub fn find(&mut self, needle: char) -> Option<usize> {
+        self.find_with(1, |s| s[0] == needle)
+    }
+
+    /// If there is a `needle` after the `self.cursor` position and before `end`,
+    /// that position is returned.
+    pub fn find_before(&mut self, needle: char, end: usize) -> Option<usize> {
+        self.fill_to(end);
+        let end = end.min(self.input.len());
+        (self.cursor..end).find(|&i| self.input[i] == needle)
     }
 
     /// If there is a `needle` after the `self.cursor` position, that position is returned.
     pub fn find_str(&mut self, needle: &str) -> Option<usize> {
         let needle: Vec<char> = needle.chars().collect();
-        let mut i = self.cursor;
-        if self.input.len() <= i {
+        if needle.is_empty() {
             return None;
         }
+        self.find_with(needle.len(), |s| s == &needle[..])
+    }
 
-        let mut bgn_idx;
-        while i < self.input.len() {
-            // first character
-            if self.input[i] == needle[0] {
-                if needle.len() == 1 {
-                    return Some(i);
-                }
-                bgn_idx = i;
-                i += 1;
-            } else {
-                i += 1;
-                continue;
-            }
-
-            // second and subsequent characters
-            let mut j = 1;
-            while j < needle.len() {
-                if self.input.len() <= i {
-                    return None;
-                }
-
-                if self.input[i] == needle[j] {
-                    if j == needle.len() - 1 {
-                        return Some(bgn_idx);
-                    }
-                } else {
-                    break;
-                }
-                i += 1;
-                j += 1;
-            }
-
-            i = bgn_idx + 1;
+    /// If there is a `needle` after the `self.cursor` position, that position is returned.
+    /// case insensitive.
+    pub fn find_str_insensitive(&mut self, needle: &str) -> Option<usize> {
+        let needle: Vec<char> = needle.to_lowercase().chars().collect();
+        if needle.is_empty() {
+            return None;
         }
-
-        None
+        self.find_with(needle.len(), |s| {
+            s.iter()
+                .zip(needle.iter())
+                .all(|(c, n)| c.to_ascii_lowercase() == *n)
+        })
     }
 
     /// Returns the character at the `cursor` position.
-    #[allow(dead_code)]
-    pub fn get_char(&self, cursor: usize) -> Result<char, String> {
+    pub fn get_char(&mut self, cursor: usize) -> Result<char, String> {
+        self.fill_to(cursor + 1);
         if self.input.len() <= cursor {
             return Err(String::from("out of input"));
         }
@@ -171,20 +337,55 @@ impl Input {
     }
 
     /// Returns from `bgn` to `end` as a String.
-    pub fn get_string(&self, bgn: usize, end: usize) -> Result<String, String> {
+    /// If the range is empty, returns an empty String.
+    pub fn get_string(&self, bgn: usize, end: usize) -> String {
+        let end = end.min(self.input.len());
         if end <= bgn {
-            return Err(String::from("invalid range"));
+            return String::new();
         }
+        self.input[bgn..end].iter().collect()
+    }
 
-        if self.input.len() <= end {
-            return Err(format!("out of input {:?}:{:?}", bgn, end));
+    /// Returns the index of the line of the `cursor` in `self.line_starts`.
+    fn line_index(&self, cursor: usize) -> usize {
+        match self.line_starts.binary_search(&cursor) {
+            Ok(line) => line,
+            Err(line) => line - 1,
         }
+    }
 
-        let mut s = String::new();
-        for i in bgn..end {
-            s.push(self.input[i]);
+    /// Returns the Position of the `cursor`.
+    pub fn get_position(&self, cursor: usize) -> Position {
+        let cursor = cursor.min(self.input.len());
+        let line = self.line_index(cursor);
+        let column = match line {
+            0 => self.base_column + cursor,
+            _ => cursor - self.line_starts[line],
+        };
+        Position::new(self.offsets[cursor], self.base_line + line + 1, column + 1)
+    }
+
+    /// Returns the cursor of the character at the byte `offset`.
+    pub fn get_cursor_at(&self, offset: usize) -> usize {
+        match self.offsets.binary_search(&offset) {
+            Ok(cursor) => cursor,
+            Err(cursor) => cursor,
         }
+    }
+
+    /// Returns the Span from `bgn` to `end`.
+    pub fn get_span(&self, bgn: usize, end: usize) -> Span {
+        Span::new(self.get_position(bgn), self.get_position(end))
+    }
 
-        Ok(s)
+    /// Returns the source from the `cursor` to the end of the line.
+    /// It is truncated to `SNIPPET_LEN` characters.
+    pub fn get_snippet(&self, cursor: usize) -> String {
+        self.input
+            .iter()
+            .skip(cursor)
+            .take_while(|c| **c != '\n')
+            .take(SNIPPET_LEN)
+            .collect()
     }
 }
@@ -1,13 +1,84 @@
+mod error;
 mod input;
+mod options;
+mod reader;
+mod tokenizer;
 
 use std::collections::HashMap;
 
+use crate::dom::cdata::CData;
 use crate::dom::comment::Comment;
+use crate::dom::doctype::Doctype;
+use crate::dom::elements::ESCAPABLE_RAW_TEXT_ELEMENTS;
+use crate::dom::elements::RAW_TEXT_ELEMENTS;
+use crate::dom::elements::VOID_ELEMENTS;
+use crate::dom::namespace;
+use crate::dom::processing_instruction::ProcessingInstruction;
 use crate::dom::tag::Tag;
 use crate::dom::text::Text;
+use crate::dom::Attrs;
 use crate::dom::Dom;
 use crate::dom::DomType;
+use crate::dom::Position;
+use crate::dom::Span;
+use crate::entity;
+pub use error::ParseError;
 use input::Input;
+pub use options::ParseMode;
+pub use options::ParseOptions;
+pub use reader::Event;
+pub use reader::ReadError;
+pub use reader::Reader;
+use tokenizer::DomStream;
+pub use tokenizer::Token;
+pub use tokenizer::Tokenizer;
+
+/// HTML elements whose end tag can be omitted.
+const OPTIONAL_END_TAG_ELEMENTS: [&str; 21] = [
+    "html", "head", "body", "li", "dt", "dd", "p", "rb", "rt", "rtc", "rp", "optgroup", "option",
+    "colgroup", "caption", "thead", "tbody", "tfoot", "tr", "td", "th",
+];
+
+/// Start tags that end an open `<p>`.
+const P_CLOSING_ELEMENTS: [&str; 37] = [
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "center",
+    "details",
+    "dialog",
+    "dir",
+    "div",
+    "dl",
+    "dd",
+    "dt",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hgroup",
+    "hr",
+    "li",
+    "main",
+    "menu",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "table",
+    "ul",
+    "summary",
+];
 
 /// Parses the tag document and returns a Dom structure tree.
 ///
@@ -15,7 +86,10 @@ use input::Input;
 /// * `doc` - tag document
 ///
 /// # Errors
-/// * If the document ends in the middle of a tag or double quote.
+/// Returns a `ParseError` with the position where the parsing failed.
+/// * If the document ends in the middle of a tag, comment or attribute value.
+/// * If the tag ends before the delimiter that closes an attribute value.
+/// * If the document ends before the end tag of a raw text element. (e.g. `<script>`, `<style>`)
 ///
 /// # Examples
 /// ```rust
@@ -23,94 +97,195 @@ use input::Input;
 /// <body>
 ///   <h1 class="h1">Hello</h1>
 /// </body>
-/// }"#;
+/// "#;
 ///
-/// if let Ok(dom) = parsercher::parse(&html) {
-///     println!("{:#?}", dom);
-/// }
-/// ```
+/// let root_dom = parsercher::parse(&html).unwrap();
+/// let body_dom = root_dom.get_children().unwrap().first().unwrap();
+/// let h1_dom = body_dom.get_children().unwrap().first().unwrap();
 ///
-/// output:
+/// let tag = h1_dom.get_tag().unwrap();
+/// assert_eq!(tag.get_name(), "h1");
+/// assert_eq!(tag.get_attr("class"), Some("h1".to_string()));
+/// let text_dom = h1_dom.get_children().unwrap().first().unwrap();
+/// assert_eq!(text_dom.get_text().unwrap().get_text(), "Hello");
 ///
-/// ```text
-/// Dom {
-///     dom_type: Tag,
-///     tag: Some(
-///         Tag {
-///             name: "root",
-///             attr: None,
-///             terminated: false,
-///             terminator: false,
-///         },
-///     ),
-///     text: None,
-///     comment: None,
-///     children: Some(
-///         [
-///             Dom {
-///                 dom_type: Tag,
-///                 tag: Some(
-///                     Tag {
-///                         name: "body",
-///                         attr: None,
-///                         terminated: false,
-///                         terminator: false,
-///                     },
-///                 ),
-///                 text: None,
-///                 comment: None,
-///                 children: Some(
-///                     [
-///                         Dom {
-///                             dom_type: Tag,
-///                             tag: Some(
-///                                 Tag {
-///                                     name: "h1",
-///                                     attr: Some(
-///                                         {
-///                                             "class": "h1",
-///                                         },
-///                                     ),
-///                                     terminated: false,
-///                                     terminator: false,
-///                                 },
-///                             ),
-///                             text: None,
-///                             comment: None,
-///                             children: Some(
-///                                 [
-///                                     Dom {
-///                                         dom_type: Text,
-///                                         tag: None,
-///                                         text: Some(
-///                                             Text {
-///                                                 text: "Hello",
-///                                             },
-///                                         ),
-///                                         comment: None,
-///                                         children: None,
-///                                     },
-///                                 ],
-///                             ),
-///                         },
-///                     ],
-///                 ),
-///             },
-///         ],
-///     ),
-/// }
+/// // the Dom structures know where they are in the source document
+/// let span = h1_dom.get_span().unwrap();
+/// assert_eq!(&html[span.get_range()], r#"<h1 class="h1">Hello</h1>"#);
+/// assert_eq!(span.get_start().get_line(), 3);
+/// assert_eq!(span.get_start().get_column(), 3);
+/// assert_eq!(&html[tag.get_end_span().unwrap().get_range()], "</h1>");
 /// ```
+pub fn parse(doc: &str) -> Result<Dom, ParseError> {
+    let (dom, _) = parse_with(doc, ParseOptions::default())?;
+    Ok(dom)
+}
+
+/// Parses the tag document with `options` and returns a Dom structure tree
+/// and the errors recovered from.
+///
+/// If `options.recover` is true, malformed tags, comments and attribute values
+/// do not stop the parsing. They are parsed as well as possible, and the errors
+/// are returned with the Dom structure tree instead.
+///
+/// # Errors
+/// Same as `parsercher::parse()`. It never fails if `options.recover` is true.
+///
+/// # Examples
+/// ```rust
+/// use parsercher::ParseOptions;
+///
+/// let html = r#"
+/// <ul>
+///   <li class="first>first</li>
+///   <li>second</li>
+/// </ul>
+/// "#;
 ///
-pub fn parse(doc: &str) -> Result<Dom, String> {
-    let mut input = Input::new(doc);
-    let mut dom_vec = create_dom_vec(&mut input)?;
-    //debug_print_dom_vec(&dom_vec);
+/// assert!(parsercher::parse(&html).is_err());
+///
+/// let options = ParseOptions {
+///     recover: true,
+///     ..Default::default()
+/// };
+/// let (dom, errors) = parsercher::parse_with(&html, options).unwrap();
+/// assert_eq!(errors.len(), 2);
+///
+/// let texts = parsercher::search_text_from_tag_children(&dom, &parsercher::dom::Tag::new("li")).unwrap();
+/// assert_eq!(texts, vec!["first".to_string(), "second".to_string()]);
+/// ```
+pub fn parse_with(doc: &str, options: ParseOptions) -> Result<(Dom, Vec<ParseError>), ParseError> {
+    let mut root_dom = Dom::new_root();
+    if doc.trim_end().is_empty() {
+        return Ok((root_dom, Vec::new()));
+    }
+
+    let mut stream = DomStream::new(Input::new(doc), Context::new(options));
+    let dom_vec = create_dom_vec(&mut stream)?;
+
+    let ctx = stream.ctx;
+    create_dom_tree(dom_vec, &mut root_dom, ctx.is_html());
+    namespace::resolve(&mut root_dom);
+    Ok((root_dom, ctx.errors))
+}
 
+/// Parses the XML document and returns a Dom structure tree
+/// only if the document is well-formed.
+///
+/// In addition to `ParseMode::Xml`, the following are errors:
+/// * An end tag that does not match the open element, and an element without the end tag.
+/// * No root element or more than one root element.
+/// * Text other than whitespace outside the root element.
+/// * A duplicate attribute, an attribute value without quotes, and `<` in an attribute value.
+/// * An entity reference other than `&amp;`, `&lt;`, `&gt;`, `&quot;`, `&apos;`,
+///   the numeric character references and the entities declared in the `<!DOCTYPE>`.
+///
+/// # Errors
+/// Returns the first `ParseError` in the document.
+///
+/// # Examples
+/// ```rust
+/// use parsercher::ParseError;
+///
+/// let dom = parsercher::parse_xml_strict(r#"<note id="1"><to>Tove &amp; Jani</to></note>"#).unwrap();
+/// assert_eq!(dom.to_html(), r#"<note id="1"><to>Tove &amp; Jani</to></note>"#);
+///
+/// let err = parsercher::parse_xml_strict("<note>\n  <to>Tove</from>\n</note>").unwrap_err();
+/// assert!(matches!(err, ParseError::MismatchedEndTag { .. }));
+/// assert_eq!(err.position().get_line(), 2);
+/// assert_eq!(err.position().get_column(), 11);
+/// ```
+pub fn parse_xml_strict(doc: &str) -> Result<Dom, ParseError> {
     let mut root_dom = Dom::new_root();
-    create_dom_tree(&mut dom_vec, &mut root_dom);
+    if doc.trim_end().is_empty() {
+        return Err(ParseError::MissingRootElement {
+            position: Position::new(0, 1, 1),
+            snippet: String::new(),
+        });
+    }
+
+    let options = ParseOptions {
+        mode: ParseMode::Xml,
+        ..Default::default()
+    };
+    let mut ctx = Context::new(options);
+    ctx.strict = true;
+    let mut stream = DomStream::new(Input::new(doc), ctx);
+    let dom_vec = create_dom_vec(&mut stream)?;
+    check_well_formed(&mut stream.input, &dom_vec)?;
+    check_trailing_whitespace(doc)?;
+
+    create_dom_tree(dom_vec, &mut root_dom, false);
+    namespace::resolve(&mut root_dom);
     Ok(root_dom)
 }
 
+/// The options and the errors recovered from while parsing.
+struct Context {
+    options: ParseOptions,
+    errors: Vec<ParseError>,
+    /// If true, the XML well-formedness constraints are checked.
+    strict: bool,
+    /// The entities declared in the document type declaration. Used if `strict` is true.
+    entities: HashMap<String, String>,
+}
+
+impl Context {
+    fn new(options: ParseOptions) -> Context {
+        Context {
+            options,
+            errors: Vec::new(),
+            strict: false,
+            entities: HashMap::new(),
+        }
+    }
+
+    /// If recovering, records `err` and returns `Ok`, otherwise returns `err`.
+    fn report(&mut self, err: ParseError) -> Result<(), ParseError> {
+        if !self.options.recover {
+            return Err(err);
+        }
+        self.errors.push(err);
+        Ok(())
+    }
+
+    fn is_html(&self) -> bool {
+        self.options.mode == ParseMode::Html
+    }
+}
+
+/// Returns the lowercase name in `ParseMode::Html` if it is not lowercase.
+/// The names of declarations such as `<!ELEMENT>` are kept.
+fn lowercase_name(ctx: &Context, name: &str) -> Option<String> {
+    if !ctx.is_html() || name.starts_with('!') {
+        return None;
+    }
+    let lowercase = name.to_ascii_lowercase();
+    if lowercase == name {
+        return None;
+    }
+    Some(lowercase)
+}
+
+/// Decodes the character references in the text or the attribute value that starts at `bgn`.
+/// In strict XML, only the XML references are decoded and an undefined one is an error.
+fn decode_entities(
+    input: &Input,
+    ctx: &Context,
+    s: &str,
+    bgn: usize,
+    in_attr: bool,
+) -> Result<String, ParseError> {
+    if !ctx.strict {
+        return Ok(entity::decode(s, in_attr));
+    }
+    entity::decode_xml(s, &ctx.entities).map_err(|(index, name)| ParseError::UndefinedEntity {
+        name,
+        position: input.get_position(bgn + index),
+        snippet: input.get_snippet(bgn + index),
+    })
+}
+
 /// Returns the value of the tag's attribute.
 ///
 /// State to receive:
@@ -120,28 +295,52 @@ pub fn parse(doc: &str) -> Result<Dom, String> {
 /// '<value>'
 /// or
 /// value
-fn parse_tag_attr_value(input: &mut Input, tag_end: usize, dlmt: char) -> Result<String, String> {
+///
+/// If recovering, a value without the closing delimiter ends at the end of the tag.
+fn parse_tag_attr_value(
+    input: &mut Input,
+    ctx: &mut Context,
+    tag_end: usize,
+    dlmt: char,
+) -> Result<String, ParseError> {
+    let dlmt_pos = input.get_cursor();
     if dlmt != ' ' {
         input.next(); // move cursor to after '"' or '\''
     }
     let value_bgn = input.get_cursor();
 
+    // a value without quotes does not continue after the tag
+    let found = match dlmt {
+        ' ' => input.find_before(dlmt, tag_end),
+        _ => input.find(dlmt),
+    };
     let value_end;
-    match input.find(dlmt) {
+    match found {
         Some(cursor) => {
             if cursor < tag_end {
                 value_end = cursor;
             } else if dlmt == ' ' {
                 value_end = tag_end;
             } else {
-                return Err(format!(
-                    "There is no delimiter({}) to terminate the attribute.",
-                    dlmt
-                ));
+                ctx.report(ParseError::MissingAttrDelimiter {
+                    delimiter: dlmt,
+                    position: input.get_position(dlmt_pos),
+                    snippet: input.get_snippet(dlmt_pos),
+                })?;
+                value_end = tag_end;
             }
         }
         None => {
-            return Err(format!("Input ends in the middle of delimiter({})", dlmt));
+            if dlmt == ' ' {
+                value_end = tag_end;
+            } else {
+                ctx.report(ParseError::UnterminatedAttrValue {
+                    delimiter: dlmt,
+                    position: input.get_position(dlmt_pos),
+                    snippet: input.get_snippet(dlmt_pos),
+                })?;
+                value_end = tag_end;
+            }
         }
     }
 
@@ -151,15 +350,18 @@ fn parse_tag_attr_value(input: &mut Input, tag_end: usize, dlmt: char) -> Result
     }
 
     input.set_cursor(value_end);
-    input.get_string(value_bgn, value_end)
+    Ok(input.get_string(value_bgn, value_end))
 }
 
 /// Get the cursor position at the end of tag.
+/// `tag_bgn` is the position of the first '<' and is used for the error.
+///
+/// If recovering and the '"' is not closed, the first '>' is the end of tag.
 ///
 /// <tag attr="value" >
 ///                   ^
 ///                   Return this position.
-fn get_tag_end(input: &mut Input) -> Result<usize, String> {
+fn get_tag_end(input: &mut Input, ctx: &mut Context, tag_bgn: usize) -> Result<usize, ParseError> {
     let save_cursol_pos = input.get_cursor();
     let mut res = 0;
 
@@ -176,10 +378,19 @@ fn get_tag_end(input: &mut Input) -> Result<usize, String> {
     }
 
     input.set_cursor(save_cursol_pos);
-    match res {
-        0 => return Err(String::from("Input ends in the middle of the tag")),
-        _ => return Ok(res),
+    if res != 0 {
+        return Ok(res);
+    }
+
+    let err = ParseError::UnterminatedTag {
+        position: input.get_position(tag_bgn),
+        snippet: input.get_snippet(tag_bgn),
+    };
+    if let Some(cursor) = input.find('>') {
+        ctx.report(err)?;
+        return Ok(cursor);
     }
+    Err(err)
 }
 
 /// Parse tag attributes.
@@ -191,11 +402,14 @@ fn get_tag_end(input: &mut Input) -> Result<usize, String> {
 /// <attr>[ = '<value>'] [/]>
 /// or
 /// <attr>[ = <value>] [/]>
-fn parse_tag_attr(input: &mut Input, mut tag: Tag) -> Result<Tag, String> {
-    // get the end position of the tag
-    let tag_end = get_tag_end(input)?;
-
-    let mut attr_map = HashMap::new();
+fn parse_tag_attr(
+    input: &mut Input,
+    ctx: &mut Context,
+    mut tag: Tag,
+    tag_end: usize,
+) -> Result<Tag, ParseError> {
+    // attributes in source order including duplicates, with the start of the values
+    let mut attr_list: Vec<(String, String, usize)> = Vec::new();
 
     // get attributes and their value
     // The terminal '/' is also an attribute
@@ -209,52 +423,52 @@ fn parse_tag_attr(input: &mut Input, mut tag: Tag) -> Result<Tag, String> {
         let mut attr_end = tag_end;
 
         // If the tag contains '=', that position is the end position of the attribute name
-        if let Some(cursor) = input.find('=') {
-            if cursor < tag_end {
-                attr_end = cursor;
-            }
+        if let Some(cursor) = input.find_before('=', tag_end) {
+            attr_end = cursor;
         }
 
         // If the tag contains an ' ' and it precedes '=',
         // make that position the end position of the attribute name.
-        if let Some(cursor) = input.find(' ') {
-            if cursor < attr_end {
-                attr_end = cursor;
-            }
+        if let Some(cursor) = input.find_before(' ', attr_end) {
+            attr_end = cursor;
         }
 
         input.set_cursor(attr_end);
-        let attr_name = input.get_string(attr_bgn, attr_end)?;
+        let attr_name = input.get_string(attr_bgn, attr_end);
 
         // get value
         let mut value = String::new();
+        let mut quoted = false;
         if input.get_cursor() != tag_end {
             // If the tag contains an '='
-            if let Some(cursor) = input.find('=') {
-                if cursor < tag_end {
-                    input.set_cursor(cursor); // move cursor to '='
-                    input.next_char(); // move cursor to after '='
-                    if input.expect('"') {
-                        match parse_tag_attr_value(input, tag_end, '"') {
-                            Ok(v) => value = v,
-                            Err(e) => return Err(e),
-                        }
-                    } else if input.expect('\'') {
-                        match parse_tag_attr_value(input, tag_end, '\'') {
-                            Ok(v) => value = v,
-                            Err(e) => return Err(e),
-                        }
-                    } else {
-                        match parse_tag_attr_value(input, tag_end, ' ') {
-                            Ok(v) => value = v,
-                            Err(e) => return Err(e),
-                        }
-                    }
+            if let Some(cursor) = input.find_before('=', tag_end) {
+                input.set_cursor(cursor); // move cursor to '='
+                input.next_char(); // move cursor to after '='
+                if input.expect('"') {
+                    value = parse_tag_attr_value(input, ctx, tag_end, '"')?;
+                    quoted = true;
+                } else if input.expect('\'') {
+                    value = parse_tag_attr_value(input, ctx, tag_end, '\'')?;
+                    quoted = true;
+                } else {
+                    value = parse_tag_attr_value(input, ctx, tag_end, ' ')?;
                 }
             }
         }
 
-        attr_map.insert(attr_name, value);
+        // the cursor points to the end of the value
+        let value_bgn = input.get_cursor() - value.chars().count();
+        if ctx.strict && attr_name != "/" {
+            check_strict_attr(input, &attr_list, &attr_name, attr_bgn, quoted)?;
+            if let Some(index) = value.chars().position(|c| c == '<') {
+                return Err(ParseError::LtInAttrValue {
+                    name: attr_name,
+                    position: input.get_position(value_bgn + index),
+                    snippet: input.get_snippet(value_bgn + index),
+                });
+            }
+        }
+        attr_list.push((attr_name, value, value_bgn));
 
         if input.expect('>') {
             input.next();
@@ -265,15 +479,67 @@ fn parse_tag_attr(input: &mut Input, mut tag: Tag) -> Result<Tag, String> {
     }
 
     // If the attribute contains '/', remove it
-    if let Some(_) = attr_map.remove("/") {
+    let len = attr_list.len();
+    attr_list.retain(|(attr, _, _)| attr != "/");
+    if attr_list.len() != len {
         tag.set_terminated(true);
     }
+    if attr_list.is_empty() {
+        return Ok(tag);
+    }
 
-    tag.set_attrs(attr_map);
+    let declaration = tag.get_name().starts_with('!');
+    let mut attrs = Attrs::new();
+    for (raw_attr, value, value_bgn) in attr_list.iter() {
+        let lowercase = match declaration {
+            true => None,
+            false => lowercase_name(ctx, raw_attr),
+        };
+        let attr = lowercase.as_ref().unwrap_or(raw_attr);
+        let decoded = match ctx.options.decode_entities {
+            true => decode_entities(input, ctx, value, *value_bgn, true)?,
+            false => value.clone(),
+        };
+        if decoded != *value {
+            attrs.push_raw(attr, &decoded, value);
+        } else {
+            attrs.push(attr, value);
+        }
+        if lowercase.is_some() {
+            attrs.set_last_raw_name(raw_attr);
+        }
+    }
+    tag.set_attrs(attrs);
 
     Ok(tag)
 }
 
+/// Checks the attribute `name` that starts at `attr_bgn` in strict XML.
+/// It must not be a duplicate and its value must be quoted.
+fn check_strict_attr(
+    input: &Input,
+    attr_list: &[(String, String, usize)],
+    name: &str,
+    attr_bgn: usize,
+    quoted: bool,
+) -> Result<(), ParseError> {
+    if attr_list.iter().any(|(attr, _, _)| attr == name) {
+        return Err(ParseError::DuplicateAttr {
+            name: String::from(name),
+            position: input.get_position(attr_bgn),
+            snippet: input.get_snippet(attr_bgn),
+        });
+    }
+    if !quoted {
+        return Err(ParseError::UnquotedAttrValue {
+            name: String::from(name),
+            position: input.get_position(attr_bgn),
+            snippet: input.get_snippet(attr_bgn),
+        });
+    }
+    Ok(())
+}
+
 /// Parse the tag name.
 /// the tag name is trimmed.
 ///
@@ -282,27 +548,40 @@ fn parse_tag_attr(input: &mut Input, mut tag: Tag) -> Result<Tag, String> {
 /// <tag_name> [<attr>[="<value>"]] [/]>
 /// or
 /// <tag_name> [<attr>[='<value>']] [/]>
-fn parse_tag_name(input: &mut Input, terminator: bool) -> Result<Tag, String> {
+fn parse_tag_name(
+    input: &mut Input,
+    ctx: &mut Context,
+    terminator: bool,
+    tag_end: usize,
+) -> Result<Tag, ParseError> {
     // Get the start position of the tag name
     let name_bgn = input.get_cursor();
 
-    // get the end position of the tag
-    let tag_end = get_tag_end(input)?;
-
     let mut name_end = tag_end;
 
     // If the tag contains ' ', make that position the end position of the tag name.
-    if let Some(cursor) = input.find(' ') {
-        if cursor < tag_end {
-            name_end = cursor;
-        }
+    if let Some(cursor) = input.find_before(' ', tag_end) {
+        name_end = cursor;
     }
 
     input.set_cursor(name_end);
-    let tag_name = input.get_string(name_bgn, name_end)?;
-    let tag_name = tag_name.trim();
+    let tag_name = input.get_string(name_bgn, name_end);
+    let mut tag_name = tag_name.trim();
+    // `<br/>`
+    let mut terminated = false;
+    if let Some(name) = tag_name.strip_suffix('/') {
+        if !name.is_empty() {
+            tag_name = name;
+            terminated = true;
+        }
+    }
     let mut tag = Tag::new(tag_name);
+    if let Some(name) = lowercase_name(ctx, tag_name) {
+        tag.set_name(&name);
+        tag.set_raw_name(tag_name);
+    }
     tag.set_terminator(terminator);
+    tag.set_terminated(terminated);
 
     if input.expect('>') {
         input.next(); // move cursor to after '>'
@@ -317,7 +596,7 @@ fn parse_tag_name(input: &mut Input, terminator: bool) -> Result<Tag, String> {
         return Ok(tag);
     }
 
-    return parse_tag_attr(input, tag);
+    parse_tag_attr(input, ctx, tag, tag_end)
 }
 
 /// Parses the tag and returns a Dom structure.
@@ -327,7 +606,8 @@ fn parse_tag_name(input: &mut Input, terminator: bool) -> Result<Tag, String> {
 /// <[/]<tag_name> [<attr>[="<value>"]] [/]>
 /// or
 /// <[/]<tag_name> [<attr>[='<value>']] [/]>
-fn parse_tag(input: &mut Input) -> Result<Dom, String> {
+fn parse_tag(input: &mut Input, ctx: &mut Context) -> Result<Dom, ParseError> {
+    let tag_bgn = input.get_cursor();
     input.next(); // move cursor to after '<'
 
     let mut terminator = false;
@@ -336,12 +616,18 @@ fn parse_tag(input: &mut Input) -> Result<Dom, String> {
         terminator = true;
     }
 
-    let tag = parse_tag_name(input, terminator)?;
+    // get the end position of the tag
+    let tag_end = get_tag_end(input, ctx, tag_bgn)?;
+
+    let mut tag = parse_tag_name(input, ctx, terminator, tag_end)?;
+    let span = input.get_span(tag_bgn, tag_end + 1);
+    tag.set_span(span);
     // TODO debug
     //println!("{:#?}", tag);
     let mut dom = Dom::new(DomType::Tag);
     dom.set_tag(tag);
-    return Ok(dom);
+    dom.set_span(span);
+    Ok(dom)
 }
 
 /// Parse comment.
@@ -349,233 +635,659 @@ fn parse_tag(input: &mut Input) -> Result<Dom, String> {
 /// State to receive:
 /// The cursor points to the first '<'.
 /// <!-- <comment> -->
-fn parse_comment(input: &mut Input) -> Result<Dom, String> {
+///
+/// If recovering, a comment without "-->" continues to the end of the document.
+fn parse_comment(input: &mut Input, ctx: &mut Context) -> Result<Dom, ParseError> {
+    let comment_bgn = input.get_cursor();
     // get the position after "<!--"
-    let bgn = input.get_cursor() + "<!--".len();
+    let bgn = comment_bgn + "<!--".len();
 
-    match input.find_str("-->") {
+    let (end, comment_end) = match input.find_str("-->") {
         Some(cursor) => {
             input.set_cursor(cursor + "-->".len()); // move cursor after "-->"
-            let comment = Comment::new(&input.get_string(bgn, cursor)?);
-            // TODO debug
-            //println!("{:#?}", comment);
-            let mut dom = Dom::new(DomType::Comment);
-            dom.set_comment(comment);
-            return Ok(dom);
+            (cursor, cursor + "-->".len())
         }
-        None => return Err(String::from("Input ends in the middle of the comment")),
-    }
+        None => {
+            ctx.report(ParseError::UnterminatedComment {
+                position: input.get_position(comment_bgn),
+                snippet: input.get_snippet(comment_bgn),
+            })?;
+            let len = input.get_len();
+            input.set_cursor(len); // move cursor to the end
+            (len, len)
+        }
+    };
+
+    let comment = Comment::new(&input.get_string(bgn, end));
+    // TODO debug
+    //println!("{:#?}", comment);
+    let mut dom = Dom::new(DomType::Comment);
+    dom.set_comment(comment);
+    dom.set_span(input.get_span(comment_bgn, comment_end));
+    Ok(dom)
+}
+
+/// Parse the CDATA section.
+///
+/// State to receive:
+/// The cursor points to the first '<'.
+/// <![CDATA[<data>]]>
+///
+/// If recovering, a section without "]]>" continues to the end of the document.
+fn parse_cdata(input: &mut Input, ctx: &mut Context) -> Result<Dom, ParseError> {
+    let cdata_bgn = input.get_cursor();
+    let bgn = cdata_bgn + "<![CDATA[".len();
+
+    let (end, cdata_end) = match input.find_str("]]>") {
+        Some(cursor) => {
+            input.set_cursor(cursor + "]]>".len()); // move cursor after "]]>"
+            (cursor, cursor + "]]>".len())
+        }
+        None => {
+            ctx.report(ParseError::UnterminatedCData {
+                position: input.get_position(cdata_bgn),
+                snippet: input.get_snippet(cdata_bgn),
+            })?;
+            let len = input.get_len();
+            input.set_cursor(len); // move cursor to the end
+            (len, len)
+        }
+    };
+
+    let mut dom = Dom::new(DomType::CData);
+    dom.set_cdata(CData::new(&input.get_string(bgn, end)));
+    dom.set_span(input.get_span(cdata_bgn, cdata_end));
+    Ok(dom)
+}
+
+/// Parse the processing instruction and the XML declaration.
+///
+/// State to receive:
+/// The cursor points to the first '<'.
+/// <?<target> <data>?>
+///
+/// If recovering, an instruction without "?>" continues to the end of the document.
+fn parse_processing_instruction(input: &mut Input, ctx: &mut Context) -> Result<Dom, ParseError> {
+    let pi_bgn = input.get_cursor();
+    let bgn = pi_bgn + "<?".len();
+    input.set_cursor(bgn); // "<?>" is not terminated by its own '?'
+
+    let (end, pi_end) = match input.find_str("?>") {
+        Some(cursor) => {
+            input.set_cursor(cursor + "?>".len()); // move cursor after "?>"
+            (cursor, cursor + "?>".len())
+        }
+        None => {
+            ctx.report(ParseError::UnterminatedProcessingInstruction {
+                position: input.get_position(pi_bgn),
+                snippet: input.get_snippet(pi_bgn),
+            })?;
+            let len = input.get_len();
+            input.set_cursor(len); // move cursor to the end
+            (len, len)
+        }
+    };
+
+    let content = input.get_string(bgn, end);
+    let target_end = content.find(char::is_whitespace).unwrap_or(content.len());
+    let pi = ProcessingInstruction::new(&content[..target_end], content[target_end..].trim());
+    let mut dom = Dom::new(DomType::ProcessingInstruction);
+    dom.set_processing_instruction(pi);
+    dom.set_span(input.get_span(pi_bgn, pi_end));
+    Ok(dom)
 }
 
 /// Tet text.
-fn parse_text(input: &mut Input) -> Result<Dom, String> {
+/// The character references are decoded if `ctx.options.decode_entities` is true.
+fn parse_text(input: &mut Input, ctx: &Context) -> Result<Dom, ParseError> {
     let bgn = input.get_cursor();
 
-    let end;
-    match input.find('<') {
+    let end = match input.find('<') {
         Some(cursor) => {
             input.set_cursor(cursor);
-            end = cursor;
+            cursor
         }
         None => {
-            input.next_char();
-            end = input.get_cursor();
+            // the rest of the document is text
+            let len = input.get_len();
+            input.set_cursor(len);
+            len
         }
-    }
-
-    let text = Text::new(&input.get_string(bgn, end)?);
+    };
+
+    let raw_text = input.get_string(bgn, end);
+    let text = if ctx.options.decode_entities {
+        let mut text = Text::new(&decode_entities(input, ctx, &raw_text, bgn, false)?);
+        text.set_raw_text(&raw_text);
+        text
+    } else {
+        Text::new(&raw_text)
+    };
     // TODO debug
     //println!("{:#?}", text);
     let mut dom = Dom::new(DomType::Text);
     dom.set_text(text);
-    return Ok(dom);
+    dom.set_span(input.get_span(bgn, end));
+    Ok(dom)
+}
+
+/// Get the code after the `bgn` position to the end of the document as text.
+fn parse_text_rest(input: &mut Input, bgn: usize) -> Dom {
+    let len = input.get_len();
+    let text = Text::new(&input.get_string(bgn, len));
+    input.set_cursor(len); // move cursor to the end
+    let mut dom = Dom::new(DomType::Text);
+    dom.set_text(text);
+    dom.set_span(input.get_span(bgn, len));
+    dom
+}
+
+/// Returns the lowercase name if the tag starts an element whose content is text.
+/// e.g. `<script>`, `<style>`, `<textarea>`
+fn raw_text_name(tag: &Tag) -> Option<&'static str> {
+    if tag.is_terminator() || tag.is_terminated() {
+        return None;
+    }
+    RAW_TEXT_ELEMENTS
+        .iter()
+        .chain(ESCAPABLE_RAW_TEXT_ELEMENTS.iter())
+        .find(|name| name.eq_ignore_ascii_case(tag.get_name()))
+        .copied()
+}
+
+/// Find the end tag of the raw text element `name` after the cursor and return its position.
+/// case insensitive.
+fn find_raw_text_end(input: &mut Input, name: &str) -> Option<usize> {
+    let bgn = input.get_cursor();
+    let needle = format!("</{}", name);
+    let mut res = None;
+    while let Some(cursor) = input.find_str_insensitive(&needle) {
+        // `</scripts>` does not end `<script>`
+        match input.get_char(cursor + needle.len()) {
+            Ok(c) if !(c.is_whitespace() || c == '/' || c == '>') => {
+                input.set_cursor(cursor + 1);
+            }
+            _ => {
+                res = Some(cursor);
+                break;
+            }
+        }
+    }
+    input.set_cursor(bgn);
+    res
 }
 
-/// Get the code of the script tag as text.
-fn parse_text_script(input: &mut Input) -> Result<Dom, String> {
+/// Get the content of the raw text element `name` as text.
+/// The character references are decoded only in `<textarea>` and `<title>`.
+/// `<plaintext>` continues to the end of the document.
+///
+/// State to receive:
+/// The cursor points to the character after the start tag.
+///
+/// Returns `None` if the content is empty.
+/// If recovering, the content without the end tag continues to the end of the document.
+fn parse_raw_text(
+    input: &mut Input,
+    ctx: &mut Context,
+    name: &str,
+) -> Result<Option<Dom>, ParseError> {
     let bgn = input.get_cursor();
-    let end;
+    if input.is_end() && input.expect('>') {
+        // the start tag is at the end of the document
+        if name != "plaintext" {
+            ctx.report(ParseError::UnterminatedRawText {
+                name: String::from(name),
+                position: input.get_position(bgn),
+                snippet: input.get_snippet(bgn),
+            })?;
+        }
+        return Ok(None);
+    }
 
-    match input.find_str("</script") {
+    let end = if name == "plaintext" {
+        None
+    } else {
+        find_raw_text_end(input, name)
+    };
+    let end = match end {
         Some(cursor) => {
             input.set_cursor(cursor);
-            end = cursor;
+            cursor
         }
-        None => return Err(String::from("Input ends in the middle of the tag")),
+        None => {
+            if name != "plaintext" {
+                ctx.report(ParseError::UnterminatedRawText {
+                    name: String::from(name),
+                    position: input.get_position(bgn),
+                    snippet: input.get_snippet(bgn),
+                })?;
+            }
+            let len = input.get_len();
+            input.set_cursor(len); // move cursor to the end
+            len
+        }
+    };
+    if end == bgn {
+        return Ok(None);
     }
 
-    let text = Text::new(&input.get_string(bgn, end)?);
+    let raw_text = input.get_string(bgn, end);
+    let text = if ctx.options.decode_entities && ESCAPABLE_RAW_TEXT_ELEMENTS.contains(&name) {
+        let mut text = Text::new(&entity::decode(&raw_text, false));
+        text.set_raw_text(&raw_text);
+        text
+    } else {
+        Text::new(&raw_text)
+    };
     let mut dom = Dom::new(DomType::Text);
     dom.set_text(text);
-    return Ok(dom);
+    dom.set_span(input.get_span(bgn, end));
+    Ok(Some(dom))
 }
 
-/// Parse "<!doctype html>".
+/// Parse the document type declaration.
 /// case insensitive.
 ///
 /// State to receive:
 /// The cursor points to the first '<'.
-/// <!doctype html>
-#[allow(dead_code)]
-fn parse_doctype(input: &mut Input) -> Result<Dom, String> {
-    if !input.expect_str_insensitive("<!doctype html>") {
-        return Err(String::from("Input is not html"));
+/// <!DOCTYPE <name> [PUBLIC "<public_id>" ["<system_id>"] | SYSTEM "<system_id>"] [[<internal_subset>]]>
+///
+/// If recovering, a declaration without '>' continues to the end of the document.
+fn parse_doctype(input: &mut Input, ctx: &mut Context) -> Result<Dom, ParseError> {
+    let doctype_bgn = input.get_cursor();
+    let bgn = doctype_bgn + "<!DOCTYPE".len();
+
+    // '>' in the quotes and the internal subset does not end the declaration
+    let mut end = None;
+    let mut quote = None;
+    let mut in_subset = false;
+    let mut cursor = bgn;
+    while let Ok(c) = input.get_char(cursor) {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => (),
+            None => match c {
+                '"' | '\'' => quote = Some(c),
+                '[' => in_subset = true,
+                ']' => in_subset = false,
+                '>' if !in_subset => {
+                    end = Some(cursor);
+                    break;
+                }
+                _ => (),
+            },
+        }
+        cursor += 1;
     }
+    let (end, doctype_end) = match end {
+        Some(cursor) => {
+            input.set_cursor(cursor + 1); // move cursor after '>'
+            (cursor, cursor + 1)
+        }
+        None => {
+            ctx.report(ParseError::UnterminatedTag {
+                position: input.get_position(doctype_bgn),
+                snippet: input.get_snippet(doctype_bgn),
+            })?;
+            let len = input.get_len();
+            input.set_cursor(len); // move cursor to the end
+            (len, len)
+        }
+    };
+
+    let content = input.get_string(bgn, end);
+    let mut dom = Dom::new(DomType::Doctype);
+    let doctype = parse_doctype_content(ctx, &content);
+    if ctx.strict {
+        if let Some(subset) = doctype.get_internal_subset() {
+            declare_entities(ctx, subset);
+        }
+    }
+    dom.set_doctype(doctype);
+    dom.set_span(input.get_span(doctype_bgn, doctype_end));
+    Ok(dom)
+}
 
-    // Set the tag name to "doctype"
-    input.next(); // move cursor to '!'
-    input.next(); // move cursor to 'd'
-    let bgn = input.get_cursor();
-    let end = bgn + "doctype".len();
-    input.set_cursor(end); // move cursor to the ' ' before the "html"
-    let mut tag = Tag::new(&input.get_string(bgn, end)?);
-
-    input.next(); // move cursor to 'h'
-
-    // Set the attribute to "html"
-    // The value of attribute is ""
-    let mut attr: HashMap<String, String> = HashMap::new();
-    let bgn = input.get_cursor();
-    let end = bgn + "html".len();
-    input.set_cursor(end); // move cursor to '>'
-    attr.insert(input.get_string(bgn, end)?, String::new());
-    tag.set_attrs(attr);
+/// Parse the content of the document type declaration after "<!DOCTYPE".
+fn parse_doctype_content(ctx: &Context, content: &str) -> Doctype {
+    let content = content.trim_start();
+    let name_end = content
+        .find(|c: char| c.is_whitespace() || c == '[')
+        .unwrap_or(content.len());
+    let name = &content[..name_end];
+    let mut doctype = match lowercase_name(ctx, name) {
+        Some(name) => Doctype::new(&name),
+        None => Doctype::new(name),
+    };
+
+    let mut rest = content[name_end..].trim_start();
+    let keyword = rest.get(..6).map(|keyword| keyword.to_ascii_uppercase());
+    if keyword.as_deref() == Some("PUBLIC") {
+        rest = rest[6..].trim_start();
+        if let Some((public_id, next)) = split_quoted(rest) {
+            doctype.set_public_id(public_id);
+            rest = next;
+            if let Some((system_id, next)) = split_quoted(rest) {
+                doctype.set_system_id(system_id);
+                rest = next;
+            }
+        }
+    } else if keyword.as_deref() == Some("SYSTEM") {
+        rest = rest[6..].trim_start();
+        if let Some((system_id, next)) = split_quoted(rest) {
+            doctype.set_system_id(system_id);
+            rest = next;
+        }
+    }
 
-    let mut dom = Dom::new(DomType::Tag);
-    dom.set_tag(tag);
+    if let Some(subset) = rest.strip_prefix('[') {
+        let subset_end = subset.rfind(']').unwrap_or(subset.len());
+        doctype.set_internal_subset(&subset[..subset_end]);
+    }
+    doctype
+}
 
-    input.next(); // move cursor to after '>'
+/// Adds the general entities declared in the internal subset to `ctx.entities`.
+/// e.g. `<!ENTITY writer "Tove">`
+/// The value of an external entity is empty.
+fn declare_entities(ctx: &mut Context, subset: &str) {
+    for declaration in subset.split("<!ENTITY").skip(1) {
+        let declaration = declaration.trim_start();
+        if declaration.starts_with('%') {
+            // parameter entity
+            continue;
+        }
+        let name_end = declaration
+            .find(char::is_whitespace)
+            .unwrap_or(declaration.len());
+        let value = split_quoted(declaration[name_end..].trim_start())
+            .map(|(value, _)| value)
+            .unwrap_or("");
+        ctx.entities
+            .entry(String::from(&declaration[..name_end]))
+            .or_insert_with(|| String::from(value));
+    }
+}
 
-    Ok(dom)
+/// Returns the string in the quotes at the start of `s` and the rest after the whitespace.
+fn split_quoted(s: &str) -> Option<(&str, &str)> {
+    let quote = s.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let s = &s[1..];
+    let end = s.find(quote).unwrap_or(s.len());
+    let rest = s.get(end + 1..).unwrap_or("");
+    Some((&s[..end], rest.trim_start()))
 }
 
 /// Parses the tag document and returns the Vec of the Dom structure.
-fn create_dom_vec(input: &mut Input) -> Result<Vec<Dom>, String> {
+fn create_dom_vec(stream: &mut DomStream) -> Result<Vec<Dom>, ParseError> {
     let mut dom_vec: Vec<Dom> = Vec::new();
-
-    // move cursor to the first '<'
-    while !input.expect('<') {
-        input.next_char();
-    }
-
-    /*
-    // "<!doctype html>"
-    match parse_doctype(input) {
-        Ok(dom) => dom_vec.push(dom),
-        Err(e) => return Err(e),
+    while let Some(dom) = stream.next_dom()? {
+        dom_vec.push(dom);
     }
-    */
+    Ok(dom_vec)
+}
 
-    while !input.is_end() {
-        // TODO debug
-        //println!("check: {}", input.get_char(input.get_cursor())?);
-        if input.expect_str("<!--") {
-            // comment
-            match parse_comment(input) {
-                Ok(dom) => dom_vec.push(dom),
-                Err(e) => return Err(e),
-            }
-        } else if input.expect('<') {
-            // tag
-            match parse_tag(input) {
-                Ok(dom) => {
-                    // if the dom is script tag
-                    let mut is_bgn_script = false;
-                    if let DomType::Tag = dom.dom_type {
-                        let tag = dom.get_tag().unwrap();
-                        if tag.get_name() == "script" && !tag.is_terminator() {
-                            is_bgn_script = true;
-                        }
-                    }
-
-                    dom_vec.push(dom);
-
-                    // if the dom is script tag and has text
-                    if is_bgn_script && !input.expect('<') {
-                        match parse_text_script(input) {
-                            Ok(dom) => dom_vec.push(dom),
-                            Err(e) => return Err(e),
-                        }
-                    }
+/// Checks that the elements in `dom_vec` are properly nested in a single root element
+/// and that there is no text outside it.
+fn check_well_formed(input: &mut Input, dom_vec: &[Dom]) -> Result<(), ParseError> {
+    let len = input.get_len();
+    let input = &*input;
+    // open elements from the outermost
+    let mut stack: Vec<&Tag> = Vec::new();
+    let mut has_root = false;
+    // the end of the last markup outside the root element
+    let mut text_bgn = 0;
+    for dom in dom_vec.iter() {
+        let span = dom.get_span().unwrap();
+        let cursor = input.get_cursor_at(span.get_start().get_offset());
+        let end = input.get_cursor_at(span.get_end().get_offset());
+        let tag = match dom.get_tag() {
+            Some(tag) => tag,
+            None => {
+                // texts and CDATA sections outside the root element are checked as the source
+                if stack.is_empty() && !matches!(dom.dom_type, DomType::Text | DomType::CData) {
+                    check_text_outside_root(input, text_bgn, cursor)?;
+                    text_bgn = end;
                 }
-                Err(e) => return Err(e),
+                continue;
             }
-        } else {
-            if input.expect(' ') || input.expect('\n') {
-                input.next_char(); // skip ' ' and '\n'
+        };
+        if stack.is_empty() {
+            check_text_outside_root(input, text_bgn, cursor)?;
+        }
+        let position = input.get_position(cursor);
+        let snippet = input.get_snippet(cursor);
+
+        if tag.is_terminator() {
+            match stack.pop() {
+                Some(open) if open.get_name() == tag.get_name() => text_bgn = end,
+                Some(open) => {
+                    return Err(ParseError::MismatchedEndTag {
+                        expected: String::from(open.get_name()),
+                        found: String::from(tag.get_name()),
+                        position,
+                        snippet,
+                    })
+                }
+                None => {
+                    return Err(ParseError::UnexpectedEndTag {
+                        name: String::from(tag.get_name()),
+                        position,
+                        snippet,
+                    })
+                }
             }
+            continue;
+        }
 
-            if !input.expect('<') {
-                // text
-                match parse_text(input) {
-                    Ok(dom) => dom_vec.push(dom),
-                    Err(e) => return Err(e),
-                }
+        if stack.is_empty() {
+            if has_root {
+                return Err(ParseError::MultipleRootElements { position, snippet });
             }
+            has_root = true;
+        }
+        if tag.is_terminated() {
+            text_bgn = end;
+        } else {
+            stack.push(tag);
         }
     }
-    Ok(dom_vec)
-}
 
-/// dom_vec debugging function
-#[allow(dead_code)]
-fn debug_print_dom_vec(dom_vec: &Vec<Dom>) {
-    for dom in dom_vec.iter() {
-        match dom.dom_type {
-            DomType::Tag => println!("{:#?}", dom.get_tag().unwrap()),
-            DomType::Text => println!("{:#?}", dom.get_text().unwrap()),
-            DomType::Comment => println!("{:#?}", dom.get_comment().unwrap()),
-        }
+    if let Some(open) = stack.last() {
+        let cursor = input.get_cursor_at(open.get_span().unwrap().get_start().get_offset());
+        return Err(ParseError::UnclosedElement {
+            name: String::from(open.get_name()),
+            position: input.get_position(cursor),
+            snippet: input.get_snippet(cursor),
+        });
     }
+    if !has_root {
+        return Err(ParseError::MissingRootElement {
+            position: input.get_position(0),
+            snippet: input.get_snippet(0),
+        });
+    }
+    check_text_outside_root(input, text_bgn, len)
 }
 
-/// Find the end tag paired with `starter` from dom_vec and return its index.
-fn search_terminator(dom_vec: &mut Vec<Dom>, starter: &Tag) -> Option<usize> {
-    let mut i = 0;
-    while i < dom_vec.len() {
-        let dom = dom_vec.get(i).unwrap();
-        if let DomType::Tag = dom.dom_type {
-            let tag = dom.get_tag().unwrap();
-            if tag.is_terminator() {
-                if starter.get_name() == tag.get_name() {
-                    return Some(i);
-                }
-            }
-        }
-        i += 1;
+/// Checks that there is only whitespace from `bgn` to `end` outside the root element.
+fn check_text_outside_root(input: &Input, bgn: usize, end: usize) -> Result<(), ParseError> {
+    let text = input.get_string(bgn, end);
+    match text.chars().position(|c| !is_xml_space(c)) {
+        Some(index) => Err(ParseError::TextOutsideRoot {
+            position: input.get_position(bgn + index),
+            snippet: input.get_snippet(bgn + index),
+        }),
+        None => Ok(()),
     }
-    None
 }
 
-/// If the tag is not terminated, add it to the child, otherwise add it to the child.
-fn create_dom_tree(dom_vec: &mut Vec<Dom>, parent: &mut Dom) {
-    while !dom_vec.is_empty() {
-        let mut dom = dom_vec.remove(0);
+/// The whitespace at the end of the document is not parsed.
+/// Returns an error if it is not the whitespace of XML.
+fn check_trailing_whitespace(doc: &str) -> Result<(), ParseError> {
+    let bgn = doc.trim_end().len();
+    let offset = match doc[bgn..].char_indices().find(|(_, c)| !is_xml_space(*c)) {
+        Some((i, _)) => bgn + i,
+        None => return Ok(()),
+    };
+    let line_bgn = doc[..offset].rfind('\n').map_or(0, |i| i + 1);
+    Err(ParseError::TextOutsideRoot {
+        position: Position::new(
+            offset,
+            doc[..offset].matches('\n').count() + 1,
+            doc[line_bgn..offset].chars().count() + 1,
+        ),
+        snippet: doc[offset..].lines().next().unwrap_or("").to_string(),
+    })
+}
 
-        if let DomType::Tag = dom.dom_type {
-            let tag = dom.get_tag().unwrap();
+/// Returns true if `c` matches the `S` production of XML.
+fn is_xml_space(c: char) -> bool {
+    matches!(c, ' ' | '\t' | '\r' | '\n')
+}
+
+/// Returns true if the tag is an HTML void element that has no end tag. (e.g. `<br>`, `<img>`)
+fn is_void(tag: &Tag) -> bool {
+    VOID_ELEMENTS
+        .iter()
+        .any(|name| name.eq_ignore_ascii_case(tag.get_name()))
+}
+
+/// Returns true if the end tag of the HTML element can be omitted.
+fn has_optional_end_tag(tag: &Tag) -> bool {
+    OPTIONAL_END_TAG_ELEMENTS
+        .iter()
+        .any(|name| name.eq_ignore_ascii_case(tag.get_name()))
+}
 
+/// Returns true if the start tag `name` implies the end of the open element `open` in HTML.
+/// e.g. `<li>` ends the previous `<li>`, and `<div>` ends `<p>`.
+fn implies_end(open: &str, name: &str) -> bool {
+    let open = open.to_ascii_lowercase();
+    let name = name.to_ascii_lowercase();
+    let name = name.as_str();
+    match open.as_str() {
+        "li" => name == "li",
+        "dt" | "dd" => matches!(name, "dt" | "dd"),
+        "p" => P_CLOSING_ELEMENTS.contains(&name),
+        "option" => matches!(name, "option" | "optgroup"),
+        "optgroup" => name == "optgroup",
+        "rb" | "rt" | "rtc" | "rp" => matches!(name, "rb" | "rt" | "rtc" | "rp"),
+        "tr" => matches!(name, "tr" | "thead" | "tbody" | "tfoot"),
+        "td" | "th" => matches!(name, "td" | "th" | "tr" | "thead" | "tbody" | "tfoot"),
+        "thead" | "tbody" | "tfoot" => matches!(name, "thead" | "tbody" | "tfoot"),
+        "colgroup" => name != "col",
+        "head" => name == "body",
+        _ => false,
+    }
+}
+
+/// Build the Dom structure tree from `dom_vec` and add it to the children of `root`.
+///
+/// A tag is opened if its end tag comes later or its end tag can be omitted in HTML.
+/// It is closed by its end tag, the end tag of an ancestor, a start tag that implies
+/// its end, or the end of the document.
+/// An end tag without an open tag is ignored.
+/// If `html` is false, the HTML rules for void elements and omitted end tags are not applied.
+fn create_dom_tree(dom_vec: Vec<Dom>, root: &mut Dom, html: bool) {
+    // number of the end tags remaining for each tag name
+    let mut end_tags: HashMap<String, usize> = HashMap::new();
+    for dom in dom_vec.iter() {
+        if let Some(tag) = dom.get_tag() {
             if tag.is_terminator() {
-                // If tag is terminator. `</ tag>`
-                return;
+                *end_tags.entry(tag.get_name().to_string()).or_insert(0) += 1;
             }
+        }
+    }
 
-            if !tag.is_terminated() {
-                // If not self-terminating. not `<tag />`
-                if let Some(terminator_idx) = search_terminator(dom_vec, tag) {
-                    // If there is terminator tag
-                    if terminator_idx == 0 {
-                        // If there are no children, delete the terminator tag
-                        dom_vec.remove(0);
-                    } else {
-                        // If there are children, recurse
-                        create_dom_tree(dom_vec, &mut dom);
-                    }
+    // open tags from the outermost
+    let mut stack: Vec<Dom> = Vec::new();
+    for dom in dom_vec.into_iter() {
+        let tag = match dom.get_tag() {
+            Some(tag) => tag,
+            None => {
+                add_to_open(&mut stack, root, dom);
+                continue;
+            }
+        };
+
+        if tag.is_terminator() {
+            // If tag is terminator. `</ tag>`
+            if let Some(count) = end_tags.get_mut(tag.get_name()) {
+                *count -= 1;
+            }
+            if html && is_void(tag) {
+                // The end tag of a void element is ignored. `</br>`
+                continue;
+            }
+            let name = tag.get_name();
+            if let Some(i) = stack
+                .iter()
+                .rposition(|open| open.get_tag().unwrap().get_name() == name)
+            {
+                // The tags opened inside are closed as well
+                while stack.len() > i + 1 {
+                    close_open(&mut stack, root, None);
                 }
+                close_open(&mut stack, root, Some(&dom));
             }
+            continue;
+        }
+
+        while let Some(open) = stack.last() {
+            if !html || !implies_end(open.get_tag().unwrap().get_name(), tag.get_name()) {
+                break;
+            }
+            close_open(&mut stack, root, None);
+        }
+
+        // If not self-terminating. not `<tag />`
+        let has_end_tag = end_tags.get(tag.get_name()).is_some_and(|count| *count > 0);
+        let void = html && is_void(tag);
+        let optional_end_tag = html && has_optional_end_tag(tag);
+        if !tag.is_terminated() && !void && (has_end_tag || optional_end_tag) {
+            stack.push(dom);
+        } else {
+            add_to_open(&mut stack, root, dom);
         }
+    }
+
+    while !stack.is_empty() {
+        close_open(&mut stack, root, None);
+    }
+}
 
-        parent.add_child(dom);
+/// Add `dom` to the children of the innermost open tag, or `root` if there is no open tag.
+fn add_to_open(stack: &mut [Dom], root: &mut Dom, dom: Dom) {
+    match stack.last_mut() {
+        Some(parent) => parent.add_child(dom),
+        None => root.add_child(dom),
+    }
+}
+
+/// Close the innermost open tag and add it to its parent.
+/// `terminator` is the end tag if it is closed explicitly.
+fn close_open(stack: &mut Vec<Dom>, root: &mut Dom, terminator: Option<&Dom>) {
+    let mut dom = stack.pop().unwrap();
+    if let Some(terminator) = terminator {
+        set_end_span(&mut dom, terminator);
+    }
+    add_to_open(stack, root, dom);
+}
+
+/// Set the span of the `terminator` as the end of the `dom`.
+fn set_end_span(dom: &mut Dom, terminator: &Dom) {
+    let end_span = match terminator.get_span() {
+        Some(span) => span,
+        None => return,
+    };
+    let tag = dom.get_tag_mut().unwrap();
+    if tag.get_name() != terminator.get_tag().unwrap().get_name() {
+        return;
+    }
+    tag.set_end_span(end_span);
+    if let Some(span) = dom.get_span() {
+        dom.set_span(Span::new(span.get_start(), end_span.get_end()));
     }
 }
 
@@ -649,5 +1361,14 @@ fn print_dom_tree_exe(dom: &Dom, depth: usize) {
             let comment = comment.replace("\n", "\\n");
             println!("<!--\"{}\"-->", comment);
         }
+        DomType::Doctype => println!("{}", dom.get_doctype().unwrap()),
+        DomType::CData => {
+            let cdata = dom.get_cdata().unwrap();
+            let data = cdata.get_data().replace("\n", "\\n");
+            println!("CDATA: \"{}\"", data);
+        }
+        DomType::ProcessingInstruction => {
+            println!("{}", dom.get_processing_instruction().unwrap())
+        }
     }
 }
@@ -0,0 +1,70 @@
+//! Module of ParseOptions.
+
+/// Options for `parsercher::parse_with()`.
+///
+/// # Examples
+/// ```rust
+/// use parsercher::ParseOptions;
+///
+/// let options = ParseOptions {
+///     recover: true,
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+pub struct ParseOptions {
+    /// If true, the parser recovers from errors and never fails.
+    /// The errors recovered from are returned with the Dom structure tree.
+    /// Default is false.
+    pub recover: bool,
+    /// If true, the character references in texts and attribute values are decoded.
+    /// (e.g. `&amp;`, `&#39;`, `&#x27;`, `&eacute;`)
+    /// The source is kept in `Text::get_raw_text()` and `Tag::get_raw_attr()`.
+    /// Default is true.
+    pub decode_entities: bool,
+    /// How tag and attribute names and the content of tags are parsed.
+    /// Default is `ParseMode::Html`.
+    pub mode: ParseMode,
+}
+
+impl Default for ParseOptions {
+    fn default() -> ParseOptions {
+        ParseOptions {
+            recover: false,
+            decode_entities: true,
+            mode: ParseMode::Html,
+        }
+    }
+}
+
+/// The mode of `ParseOptions`.
+///
+/// # Examples
+/// ```rust
+/// use parsercher::{ParseMode, ParseOptions};
+///
+/// let options = ParseOptions {
+///     mode: ParseMode::Xml,
+///     ..Default::default()
+/// };
+/// let (dom, _) = parsercher::parse_with("<Item><br><Name>desk</Name></br></Item>", options).unwrap();
+/// let item = &dom.get_children().unwrap()[0];
+/// assert_eq!(item.get_tag().unwrap().get_name(), "Item");
+///
+/// // `<br>` is not a void element in XML
+/// let br = &item.get_children().unwrap()[0];
+/// assert_eq!(br.get_children().unwrap().len(), 1);
+///
+/// let dom = parsercher::parse("<DIV Class=a><BR></div>").unwrap();
+/// assert_eq!(dom.to_html(), r#"<div class="a"><br></div>"#);
+/// ```
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ParseMode {
+    /// Tag and attribute names are case-insensitive and converted to lowercase.
+    /// The spelling in the source is kept in `Tag::get_raw_name()` and `Attrs::get_raw_name()`.
+    /// The HTML rules for void elements, raw text elements and implied end tags are applied.
+    Html,
+    /// Tag and attribute names are case-sensitive.
+    /// A tag without an end tag has no children, and the content of every tag is parsed.
+    Xml,
+}
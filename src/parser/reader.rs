@@ -0,0 +1,212 @@
+//! Module of the streaming Reader.
+
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::io::Read;
+
+use super::input::Input;
+use super::tokenizer::DomStream;
+use super::Context;
+use super::ParseError;
+use super::ParseOptions;
+use crate::dom::cdata::CData;
+use crate::dom::comment::Comment;
+use crate::dom::doctype::Doctype;
+use crate::dom::processing_instruction::ProcessingInstruction;
+use crate::dom::tag::Tag;
+use crate::dom::text::Text;
+use crate::dom::Dom;
+use crate::dom::DomType;
+
+/// An event of the tag document returned by `Reader`.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Event {
+    /// A start tag. e.g. `<div class="a">`
+    StartTag(Tag),
+    /// An end tag. e.g. `</div>`
+    EndTag(Tag),
+    /// A self-closing tag. e.g. `<br/>`
+    EmptyTag(Tag),
+    Text(Text),
+    Comment(Comment),
+    Doctype(Doctype),
+    CData(CData),
+    ProcessingInstruction(ProcessingInstruction),
+}
+
+impl Event {
+    pub(super) fn from_dom(dom: Dom) -> Event {
+        match dom.dom_type {
+            DomType::Tag => {
+                let tag = dom.get_tag().unwrap().clone();
+                if tag.is_terminator() {
+                    Event::EndTag(tag)
+                } else if tag.is_terminated() {
+                    Event::EmptyTag(tag)
+                } else {
+                    Event::StartTag(tag)
+                }
+            }
+            DomType::Text => Event::Text(dom.get_text().unwrap().clone()),
+            DomType::Comment => Event::Comment(dom.get_comment().unwrap().clone()),
+            DomType::Doctype => Event::Doctype(dom.get_doctype().unwrap().clone()),
+            DomType::CData => Event::CData(dom.get_cdata().unwrap().clone()),
+            DomType::ProcessingInstruction => {
+                Event::ProcessingInstruction(dom.get_processing_instruction().unwrap().clone())
+            }
+        }
+    }
+}
+
+/// Errors that occur while reading a tag document with `Reader`.
+#[derive(Debug)]
+pub enum ReadError {
+    /// Reading the source failed.
+    Io(io::Error),
+    /// Parsing the document failed.
+    Parse(ParseError),
+}
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReadError::Io(e) => write!(f, "Reading the input failed: {}", e),
+            ReadError::Parse(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for ReadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ReadError::Io(e) => Some(e),
+            ReadError::Parse(e) => Some(e),
+        }
+    }
+}
+
+impl From<io::Error> for ReadError {
+    fn from(e: io::Error) -> ReadError {
+        ReadError::Io(e)
+    }
+}
+
+impl From<ParseError> for ReadError {
+    fn from(e: ParseError) -> ReadError {
+        ReadError::Parse(e)
+    }
+}
+
+/// Reads the tag document from `std::io::Read` in chunks and returns its events in document order.
+///
+/// The document is parsed as `parsercher::parse_with()` does, but no Dom structure tree is built
+/// and only the part of the document being parsed is kept in memory.
+/// Missing end tags are not completed and the namespaces are not resolved.
+/// Invalid UTF-8 sequences are replaced with U+FFFD.
+///
+/// # Examples
+/// ```rust
+/// use parsercher::{Event, Reader};
+///
+/// let xml = "<list>\n  <item id=\"1\">desk</item>\n  <item id=\"2\"/>\n</list>";
+///
+/// let mut names = Vec::new();
+/// for event in Reader::new(xml.as_bytes()) {
+///     match event.unwrap() {
+///         Event::StartTag(tag) | Event::EmptyTag(tag) => names.push(tag.get_name().to_string()),
+///         _ => (),
+///     }
+/// }
+/// assert_eq!(names, vec!["list", "item", "item"]);
+/// ```
+pub struct Reader<'a> {
+    stream: DomStream<'a>,
+    finished: bool,
+}
+
+impl<'a> Reader<'a> {
+    /// Create new Reader with the default options.
+    ///
+    /// # Arguments
+    /// * `source` - tag document. e.g. `std::fs::File`, `std::io::BufReader`, `&[u8]`
+    pub fn new<R: Read + 'a>(source: R) -> Reader<'a> {
+        Reader::with_options(source, ParseOptions::default())
+    }
+
+    /// Create new Reader with `options`.
+    pub fn with_options<R: Read + 'a>(source: R, options: ParseOptions) -> Reader<'a> {
+        Reader {
+            stream: DomStream::new(Input::from_reader(source), Context::new(options)),
+            finished: false,
+        }
+    }
+
+    /// Returns the next event, or `None` at the end of the document.
+    ///
+    /// # Errors
+    /// * `ReadError::Io` if reading the source fails.
+    /// * `ReadError::Parse` in the same cases as `parsercher::parse_with()`.
+    ///
+    /// No more event is returned after an error.
+    pub fn read_event(&mut self) -> Result<Option<Event>, ReadError> {
+        if self.finished {
+            return Ok(None);
+        }
+        let res = self.stream.next_dom();
+        if let Some(e) = self.stream.input.take_error() {
+            self.finished = true;
+            return Err(ReadError::Io(e));
+        }
+        match res {
+            Ok(Some(dom)) => Ok(Some(Event::from_dom(dom))),
+            Ok(None) => {
+                self.finished = true;
+                Ok(None)
+            }
+            Err(e) => {
+                self.finished = true;
+                Err(ReadError::Parse(e))
+            }
+        }
+    }
+
+    /// Calls `handler` with each event until the end of the document.
+    ///
+    /// # Errors
+    /// Same as `read_event()`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use parsercher::{Event, Reader};
+    ///
+    /// let mut texts = Vec::new();
+    /// Reader::new("<p>Hello</p><p>world</p>".as_bytes())
+    ///     .read_to_end(|event| {
+    ///         if let Event::Text(text) = event {
+    ///             texts.push(text.get_text().to_string());
+    ///         }
+    ///     })
+    ///     .unwrap();
+    /// assert_eq!(texts, vec!["Hello", "world"]);
+    /// ```
+    pub fn read_to_end<F: FnMut(Event)>(&mut self, mut handler: F) -> Result<(), ReadError> {
+        while let Some(event) = self.read_event()? {
+            handler(event);
+        }
+        Ok(())
+    }
+
+    /// Returns the errors recovered from if `ParseOptions::recover` is true.
+    pub fn get_errors(&self) -> &[ParseError] {
+        &self.stream.ctx.errors
+    }
+}
+
+impl Iterator for Reader<'_> {
+    type Item = Result<Event, ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_event().transpose()
+    }
+}
@@ -0,0 +1,270 @@
+//! Module of the tokenizer that splits the tag document into tokens.
+
+use std::collections::VecDeque;
+
+use super::input::Input;
+use super::parse_cdata;
+use super::parse_comment;
+use super::parse_doctype;
+use super::parse_processing_instruction;
+use super::parse_raw_text;
+use super::parse_tag;
+use super::parse_text;
+use super::parse_text_rest;
+use super::raw_text_name;
+use super::reader::Event;
+use super::Context;
+use super::ParseError;
+use super::ParseOptions;
+use crate::dom::tag::Tag;
+use crate::dom::Dom;
+use crate::dom::DomType;
+use crate::dom::Span;
+
+/// Returns the Dom structures of the tag document one by one, in document order.
+/// The Dom structures have no children, and the end tags are Dom structures as well.
+///
+/// If recovering, a tag without '>' and the rest of the document are parsed as text.
+pub(crate) struct DomStream<'a> {
+    pub(super) input: Input<'a>,
+    pub(super) ctx: Context,
+    /// Dom structures parsed but not returned yet.
+    queue: VecDeque<Dom>,
+    /// Byte offset of the end of the source already parsed.
+    end: usize,
+    started: bool,
+    finished: bool,
+}
+
+impl<'a> DomStream<'a> {
+    pub(super) fn new(input: Input<'a>, ctx: Context) -> DomStream<'a> {
+        DomStream {
+            input,
+            ctx,
+            queue: VecDeque::new(),
+            end: 0,
+            started: false,
+            finished: false,
+        }
+    }
+
+    /// Returns the next Dom structure, or `None` at the end of the document.
+    ///
+    /// # Errors
+    /// Same as `parsercher::parse_with()`. No more Dom structure is returned after an error.
+    pub(super) fn next_dom(&mut self) -> Result<Option<Dom>, ParseError> {
+        loop {
+            if let Some(dom) = self.queue.pop_front() {
+                return Ok(Some(dom));
+            }
+            if self.finished {
+                return Ok(None);
+            }
+            if let Err(e) = self.parse_next() {
+                self.finished = true;
+                return Err(e);
+            }
+        }
+    }
+
+    /// Parses the next part of the document and adds the Dom structures to `self.queue`.
+    fn parse_next(&mut self) -> Result<(), ParseError> {
+        let input = &mut self.input;
+        let ctx = &mut self.ctx;
+
+        if !self.started {
+            self.started = true;
+            if input.is_empty() {
+                self.finished = true;
+                return Ok(());
+            }
+            // the whitespace at the beginning of the document is not text, as at the end
+            while input
+                .get_char(input.get_cursor())
+                .is_ok_and(char::is_whitespace)
+            {
+                input.next();
+            }
+        }
+
+        // the cursor stays on the last character after it is parsed
+        let cursor = input.get_cursor();
+        if input.is_end() && input.get_position(cursor).get_offset() < self.end {
+            self.finished = true;
+            return Ok(());
+        }
+        input.discard();
+
+        if input.expect_str("<!--") {
+            // comment
+            self.queue.push_back(parse_comment(input, ctx)?);
+        } else if input.expect_str_insensitive("<!DOCTYPE") {
+            // document type declaration
+            self.queue.push_back(parse_doctype(input, ctx)?);
+        } else if input.expect_str("<![CDATA[") {
+            // CDATA section
+            self.queue.push_back(parse_cdata(input, ctx)?);
+        } else if input.expect_str("<?") {
+            // processing instruction
+            self.queue
+                .push_back(parse_processing_instruction(input, ctx)?);
+        } else if input.expect('<') {
+            // tag
+            let tag_bgn = input.get_cursor();
+            let dom = match parse_tag(input, ctx) {
+                Ok(dom) => dom,
+                Err(e) => {
+                    ctx.report(e)?;
+                    self.queue.push_back(parse_text_rest(input, tag_bgn));
+                    self.finished = true;
+                    return Ok(());
+                }
+            };
+
+            let raw_text = match dom.get_tag() {
+                Some(tag) if ctx.is_html() => raw_text_name(tag),
+                _ => None,
+            };
+            self.queue.push_back(dom);
+
+            // if the dom starts a raw text element, its content is text
+            if let Some(name) = raw_text {
+                if let Some(text) = parse_raw_text(input, ctx, name)? {
+                    self.queue.push_back(text);
+                }
+                if name == "plaintext" {
+                    // <plaintext> has no end tag
+                    let mut tag = Tag::new(name);
+                    tag.set_terminator(true);
+                    let mut dom = Dom::new(DomType::Tag);
+                    dom.set_tag(tag);
+                    self.queue.push_back(dom);
+                }
+            }
+        } else {
+            if input.expect(' ') || input.expect('\n') {
+                input.next_char(); // skip ' ' and '\n'
+            }
+
+            if !input.expect('<') {
+                // text
+                self.queue.push_back(parse_text(input, ctx)?);
+            }
+        }
+
+        if let Some(span) = self.queue.iter().rev().find_map(|dom| dom.get_span()) {
+            self.end = span.get_end().get_offset();
+        }
+        Ok(())
+    }
+}
+
+/// A token of the tag document returned by `Tokenizer`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Token<'a> {
+    event: Event,
+    span: Span,
+    raw: &'a str,
+}
+
+impl<'a> Token<'a> {
+    /// Returns the parsed token. e.g. `Event::StartTag` with the tag name and the attributes.
+    pub fn get_event(&self) -> &Event {
+        &self.event
+    }
+
+    /// Returns the parsed token and drops the rest.
+    pub fn into_event(self) -> Event {
+        self.event
+    }
+
+    /// Returns the span of the token in the source document.
+    pub fn get_span(&self) -> Span {
+        self.span
+    }
+
+    /// Returns the source of the token as written in the document.
+    /// If `<DIV class = a>`, then returns `<DIV class = a>`.
+    pub fn get_raw(&self) -> &'a str {
+        self.raw
+    }
+}
+
+/// Splits the tag document into tokens in document order without building the Dom structure tree.
+///
+/// A token is a start tag, an end tag, a text, a comment and so on, as `parsercher::parse_with()`
+/// finds them before building the tree. The whitespace between tags is not a token.
+/// Tokens that are not written in the document, such as the end of `<plaintext>`, are not returned.
+///
+/// # Examples
+/// ```rust
+/// use parsercher::{Event, Tokenizer};
+///
+/// let html = "<ul>\n  <li class=\"a\">first</li>\n</ul>";
+///
+/// let tokens: Vec<_> = Tokenizer::new(html).map(|token| token.unwrap()).collect();
+/// assert_eq!(tokens.len(), 5);
+///
+/// assert!(matches!(tokens[1].get_event(), Event::StartTag(tag) if tag.get_name() == "li"));
+/// assert_eq!(tokens[1].get_raw(), r#"<li class="a">"#);
+/// assert_eq!(tokens[1].get_span().get_start().get_line(), 2);
+/// assert_eq!(tokens[1].get_span().get_start().get_column(), 3);
+///
+/// let raws: Vec<&str> = tokens.iter().map(|token| token.get_raw()).collect();
+/// assert_eq!(raws, vec!["<ul>", r#"<li class="a">"#, "first", "</li>", "</ul>"]);
+/// ```
+pub struct Tokenizer<'a> {
+    doc: &'a str,
+    stream: DomStream<'a>,
+}
+
+impl<'a> Tokenizer<'a> {
+    /// Create new Tokenizer with the default options.
+    ///
+    /// # Arguments
+    /// * `doc` - tag document
+    pub fn new(doc: &'a str) -> Tokenizer<'a> {
+        Tokenizer::with_options(doc, ParseOptions::default())
+    }
+
+    /// Create new Tokenizer with `options`.
+    pub fn with_options(doc: &'a str, options: ParseOptions) -> Tokenizer<'a> {
+        Tokenizer {
+            doc,
+            stream: DomStream::new(Input::new(doc), Context::new(options)),
+        }
+    }
+
+    /// Returns the next token, or `None` at the end of the document.
+    ///
+    /// # Errors
+    /// Same as `parsercher::parse_with()`. No more token is returned after an error.
+    pub fn next_token(&mut self) -> Result<Option<Token<'a>>, ParseError> {
+        while let Some(dom) = self.stream.next_dom()? {
+            let span = match dom.get_span() {
+                Some(span) => span,
+                None => continue,
+            };
+            let raw = &self.doc[span.get_start().get_offset()..span.get_end().get_offset()];
+            return Ok(Some(Token {
+                event: Event::from_dom(dom),
+                span,
+                raw,
+            }));
+        }
+        Ok(None)
+    }
+
+    /// Returns the errors recovered from if `ParseOptions::recover` is true.
+    pub fn get_errors(&self) -> &[ParseError] {
+        &self.stream.ctx.errors
+    }
+}
+
+impl<'a> Iterator for Tokenizer<'a> {
+    type Item = Result<Token<'a>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_token().transpose()
+    }
+}
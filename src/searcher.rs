@@ -48,23 +48,56 @@ use crate::dom::DomType;
 /// ]
 /// ```
 pub fn search_tag(dom: &Dom, needle: &Tag) -> Option<Vec<Tag>> {
-    let mut res: Vec<Tag> = Vec::new();
-    search_tag_exe(&mut res, dom, needle);
+    let res = search_tag_refs(dom, needle);
     if res.is_empty() {
         return None;
     }
-    Some(res)
+    Some(
+        res.iter()
+            .map(|dom| dom.get_tag().unwrap().clone())
+            .collect(),
+    )
+}
+
+/// Returns references to the Dom structures of the tags for which `needle` is a sufficient condition.
+/// Unlike `search_tag()`, nothing is copied and the children of the found tags can be used.
+///
+/// # Examples
+/// ```rust
+/// use parsercher::dom::Tag;
+///
+/// let html = r#"
+/// <ol>
+///    <li class="target">first</li>
+///    <li>second</li>
+///    <li id="third" class="target">therd</li>
+/// </ol>
+/// "#;
+/// let dom = parsercher::parse(&html).unwrap();
+///
+/// let mut needle = Tag::new("li");
+/// needle.set_attr("class", "target");
+/// let doms = parsercher::search_tag_refs(&dom, &needle);
+/// assert_eq!(doms.len(), 2);
+///
+/// let text = doms[1].get_children().unwrap()[0].get_text().unwrap();
+/// assert_eq!(text.get_text(), "therd");
+/// ```
+pub fn search_tag_refs<'a>(dom: &'a Dom, needle: &Tag) -> Vec<&'a Dom> {
+    let mut res = Vec::new();
+    search_tag_refs_exe(&mut res, dom, needle);
+    res
 }
 
-fn search_tag_exe(res: &mut Vec<Tag>, dom: &Dom, needle: &Tag) {
+fn search_tag_refs_exe<'a>(res: &mut Vec<&'a Dom>, dom: &'a Dom, needle: &Tag) {
     if let Some(tag) = dom.get_tag() {
         if Tag::p_implies_q(needle, tag) {
-            res.push(tag.clone());
+            res.push(dom);
         }
 
         if let Some(children) = dom.get_children() {
             for child in children {
-                search_tag_exe(res, child, needle);
+                search_tag_refs_exe(res, child, needle);
             }
         }
     }
@@ -104,30 +137,58 @@ fn search_tag_exe(res: &mut Vec<Tag>, dom: &Dom, needle: &Tag) {
 /// ]
 /// ```
 pub fn search_tag_from_name(dom: &Dom, name: &str) -> Option<Vec<Tag>> {
-    let mut res: Vec<Tag> = Vec::new();
-    search_tag_from_name_exe(&mut res, dom, name);
+    let res = search_tag_from_name_refs(dom, name);
     if res.is_empty() {
         return None;
     }
-    Some(res)
+    Some(
+        res.iter()
+            .map(|dom| dom.get_tag().unwrap().clone())
+            .collect(),
+    )
 }
 
-fn search_tag_from_name_exe(res: &mut Vec<Tag>, dom: &Dom, name: &str) {
+/// Returns references to the Dom structures of the tags with a tag name equal to `name`.
+/// Unlike `search_tag_from_name()`, nothing is copied and the children of the found tags can be used.
+///
+/// # Examples
+/// ```rust
+/// let html = r#"
+/// <body>
+///    <h1 class="h1">section1</h1>
+///    <h2 class="h2">section2</h2>
+///    <h3 class="h3">section3</h3>
+/// </body>
+/// "#;
+/// let dom = parsercher::parse(&html).unwrap();
+///
+/// let doms = parsercher::search_tag_from_name_refs(&dom, "h2");
+/// assert_eq!(doms.len(), 1);
+/// assert_eq!(doms[0].to_html(), r#"<h2 class="h2">section2</h2>"#);
+/// ```
+pub fn search_tag_from_name_refs<'a>(dom: &'a Dom, name: &str) -> Vec<&'a Dom> {
+    let mut res = Vec::new();
+    search_tag_from_name_refs_exe(&mut res, dom, name);
+    res
+}
+
+fn search_tag_from_name_refs_exe<'a>(res: &mut Vec<&'a Dom>, dom: &'a Dom, name: &str) {
     if let DomType::Tag = dom.dom_type {
         let tag = dom.get_tag().unwrap();
         if name == tag.get_name() {
-            res.push(tag.clone());
+            res.push(dom);
         }
 
         if let Some(children) = dom.get_children() {
             for child in children {
-                search_tag_from_name_exe(res, child, name);
+                search_tag_from_name_refs_exe(res, child, name);
             }
         }
     }
 }
 
 /// Returns texts of the child of the Tag structure for which `needle` is a sufficient condition from the Dom structure tree.
+/// The data of CDATA sections are also returned as texts.
 ///
 /// # Examples
 /// Get just texts of `li` tags that `class` attribute value is `target` from the following HTML.
@@ -166,6 +227,8 @@ fn search_text_from_tag_children_exe(res: &mut Vec<String>, dom: &Dom, needle: &
                 for child in children {
                     if let Some(text) = child.get_text() {
                         res.push(text.get_text().to_string());
+                    } else if let Some(cdata) = child.get_cdata() {
+                        res.push(cdata.get_data().to_string());
                     }
                 }
             }
@@ -255,7 +318,7 @@ fn search_text_from_tag_children_exe(res: &mut Vec<String>, dom: &Dom, needle: &
 ///     <li class="key2">
 ///       <span>
 ///         TEXT: "1-2"
-///   <ul class="targetList" id="list3">
+///   <ul id="list3" class="targetList">
 ///     <li class="key1">
 ///       TEXT: "3-1"
 ///     <li class="item">
@@ -264,28 +327,59 @@ fn search_text_from_tag_children_exe(res: &mut Vec<String>, dom: &Dom, needle: &
 ///       TEXT: "3-3"
 /// ```
 pub fn search_dom(dom: &Dom, needle: &Dom) -> Option<Dom> {
-    let mut res = Dom::new_root();
-    search_dom_exe(&mut res, dom, needle);
-    match res.get_children() {
-        Some(_) => return Some(res),
-        None => return None,
+    let res = search_dom_refs(dom, needle);
+    if res.is_empty() {
+        return None;
     }
+    let mut root = Dom::new_root();
+    for dom in res.into_iter() {
+        root.add_child(dom.clone());
+    }
+    Some(root)
 }
 
-fn search_dom_exe(res: &mut Dom, dom: &Dom, needle: &Dom) {
-    if Dom::p_implies_q(needle, dom) {
-        if Dom::p_implies_q_tree(needle, dom) {
-            res.add_child(dom.clone());
-            return;
-        }
+/// Returns references to the roots of the subtrees that match the `needle` tree.
+/// Unlike `search_dom()`, the subtrees are not copied.
+/// The subtrees inside a matched subtree are not returned.
+///
+/// # Examples
+/// ```rust
+/// let html = r#"
+/// <body>
+///   <ul id="list1" class="targetList">
+///     <li class="key1">1-1</li>
+///     <li class="key2">1-2</li>
+///   </ul>
+///   <ul id="list2">
+///     <li class="key1">2-1</li>
+///   </ul>
+/// </body>
+/// "#;
+/// let root_dom = parsercher::parse(&html).unwrap();
+///
+/// let needle = r#"<ul class="targetList"><li class="key2"></li></ul>"#;
+/// let needle_dom = parsercher::parse(&needle).unwrap();
+/// let needle_dom = needle_dom.get_children().unwrap().first().unwrap();
+///
+/// let doms = parsercher::search_dom_refs(&root_dom, &needle_dom);
+/// assert_eq!(doms.len(), 1);
+/// assert_eq!(doms[0].get_tag().unwrap().get_attr("id"), Some("list1".to_string()));
+/// ```
+pub fn search_dom_refs<'a>(dom: &'a Dom, needle: &Dom) -> Vec<&'a Dom> {
+    let mut res = Vec::new();
+    search_dom_refs_exe(&mut res, dom, needle);
+    res
+}
+
+fn search_dom_refs_exe<'a>(res: &mut Vec<&'a Dom>, dom: &'a Dom, needle: &Dom) {
+    if Dom::p_implies_q(needle, dom) && Dom::p_implies_q_tree(needle, dom) {
+        res.push(dom);
+        return;
     }
-    match dom.get_children() {
-        Some(children) => {
-            for child in children.iter() {
-                search_dom_exe(res, child, needle);
-            }
+    if let Some(children) = dom.get_children() {
+        for child in children.iter() {
+            search_dom_refs_exe(res, child, needle);
         }
-        None => return,
     }
 }
 
@@ -394,7 +488,7 @@ fn search_attr_exe(res: &mut Vec<String>, dom: &Dom, attr: &str) {
 /// assert_eq!(values[3], "class2".to_string());
 /// assert_eq!(values[4], "class3".to_string());
 /// ```
-pub fn search_attrs<'a>(dom: &Dom, attrs: &Vec<&str>) -> Option<Vec<String>> {
+pub fn search_attrs(dom: &Dom, attrs: &Vec<&str>) -> Option<Vec<String>> {
     let mut res: Vec<String> = Vec::new();
     search_attrs_exe(&mut res, dom, attrs);
     if res.is_empty() {
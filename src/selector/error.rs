@@ -0,0 +1,44 @@
+//! Module of SelectorError.
+
+use std::error::Error;
+use std::fmt;
+
+/// An error that occurs while parsing a CSS selector.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SelectorError {
+    message: String,
+    offset: usize,
+    selector: String,
+}
+
+impl SelectorError {
+    pub(crate) fn new(message: &str, offset: usize, selector: &str) -> SelectorError {
+        SelectorError {
+            message: String::from(message),
+            offset,
+            selector: String::from(selector),
+        }
+    }
+
+    /// Returns the byte offset in the selector where the error occurred.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns the selector that failed to parse.
+    pub fn selector(&self) -> &str {
+        &self.selector
+    }
+}
+
+impl fmt::Display for SelectorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at offset {}: `{}`",
+            self.message, self.offset, self.selector
+        )
+    }
+}
+
+impl Error for SelectorError {}
@@ -0,0 +1,353 @@
+//! Module for querying the Dom structure tree with CSS selectors.
+
+mod error;
+mod parser;
+
+pub use error::SelectorError;
+
+use crate::dom::Dom;
+use crate::dom::DomType;
+use crate::dom::Tag;
+
+/// A parsed CSS selector group.
+///
+/// Supported selectors:
+/// * type `div`, universal `*`, id `#id`, class `.class`
+/// * attribute `[attr]`, `[attr=v]`, `[attr~=v]`, `[attr|=v]`, `[attr^=v]`, `[attr$=v]`, `[attr*=v]`
+/// * combinators: descendant `a b`, child `a > b`, adjacent sibling `a + b`, general sibling `a ~ b`
+/// * `:first-child`, `:last-child`, `:nth-child(an+b)`, `:not(selector)`
+/// * selector group `a, b`
+///
+/// Tag names and attribute names are compared case-insensitively.
+///
+/// # Examples
+/// ```rust
+/// use parsercher::Selector;
+///
+/// let html = r#"<ul><li class="a">first</li><li class="b">second</li></ul>"#;
+/// let dom = parsercher::parse(&html).unwrap();
+///
+/// let selector = Selector::parse("ul > li:not(.a)").unwrap();
+/// let li = selector.select(&dom);
+/// assert_eq!(li.len(), 1);
+/// assert_eq!(li[0].get_tag().unwrap().get_attr("class"), Some("b".to_string()));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Selector {
+    complexes: Vec<Complex>,
+}
+
+/// Compound selectors joined by combinators.
+/// `combinators[i]` joins `compounds[i]` and `compounds[i + 1]`.
+#[derive(Debug, Clone)]
+struct Complex {
+    compounds: Vec<Compound>,
+    combinators: Vec<Combinator>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Combinator {
+    /// `a b`
+    Descendant,
+    /// `a > b`
+    Child,
+    /// `a + b`
+    Adjacent,
+    /// `a ~ b`
+    Sibling,
+}
+
+/// A type selector and conditions on the same element.
+/// If `name` is `None`, any element matches. (`*`)
+#[derive(Debug, Clone)]
+struct Compound {
+    name: Option<String>,
+    conditions: Vec<Condition>,
+}
+
+#[derive(Debug, Clone)]
+enum Condition {
+    Id(String),
+    Class(String),
+    Attr {
+        name: String,
+        op: AttrOp,
+        value: String,
+    },
+    FirstChild,
+    LastChild,
+    /// `:nth-child(an+b)`
+    NthChild(i64, i64),
+    Not(Selector),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum AttrOp {
+    /// `[attr]`
+    Exists,
+    /// `[attr=v]`
+    Equals,
+    /// `[attr~=v]`
+    Includes,
+    /// `[attr|=v]`
+    DashMatch,
+    /// `[attr^=v]`
+    Prefix,
+    /// `[attr$=v]`
+    Suffix,
+    /// `[attr*=v]`
+    Substring,
+}
+
+/// An element and its position in the tree while matching.
+#[derive(Clone, Copy)]
+struct Node<'a, 'p> {
+    dom: &'a Dom,
+    parent: Option<&'p Node<'a, 'p>>,
+    /// `None` if the element is the dom that the search started from.
+    siblings: Option<&'a [Box<Dom>]>,
+    index: usize,
+}
+
+impl<'a, 'p> Node<'a, 'p> {
+    /// Returns the element siblings before this element, the nearest first.
+    fn prev_elements(&self) -> impl Iterator<Item = Node<'a, 'p>> + '_ {
+        let siblings = self.siblings.unwrap_or(&[]);
+        siblings[..self.index.min(siblings.len())]
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, dom)| dom.dom_type == DomType::Tag)
+            .map(move |(index, dom)| Node {
+                dom,
+                parent: self.parent,
+                siblings: self.siblings,
+                index,
+            })
+    }
+}
+
+impl Selector {
+    /// Parse the CSS selector group.
+    ///
+    /// # Errors
+    /// Returns `SelectorError` if the selector is invalid or not supported.
+    pub fn parse(selector: &str) -> Result<Selector, SelectorError> {
+        parser::parse(selector)
+    }
+
+    /// Returns the descendants of `dom` that match the selector in document order.
+    /// `dom` itself is not returned, but it can match the ancestor part of the selector.
+    pub fn select<'a>(&self, dom: &'a Dom) -> Vec<&'a Dom> {
+        let mut res = Vec::new();
+        let scope = Node {
+            dom,
+            parent: None,
+            siblings: None,
+            index: 0,
+        };
+        self.select_exe(&mut res, &scope);
+        res
+    }
+
+    fn select_exe<'a>(&self, res: &mut Vec<&'a Dom>, node: &Node<'a, '_>) {
+        if let Some(children) = node.dom.get_children() {
+            for (index, child) in children.iter().enumerate() {
+                let child = Node {
+                    dom: child,
+                    parent: Some(node),
+                    siblings: Some(children.as_slice()),
+                    index,
+                };
+                if self.matches(&child) {
+                    res.push(child.dom);
+                }
+                self.select_exe(res, &child);
+            }
+        }
+    }
+
+    fn matches(&self, node: &Node) -> bool {
+        self.complexes
+            .iter()
+            .any(|complex| match_complex(complex, complex.compounds.len() - 1, node))
+    }
+}
+
+/// Returns true if `node` matches `complex.compounds[i]` and the compounds before it.
+fn match_complex(complex: &Complex, i: usize, node: &Node) -> bool {
+    if !match_compound(&complex.compounds[i], node) {
+        return false;
+    }
+    if i == 0 {
+        return true;
+    }
+
+    match complex.combinators[i - 1] {
+        Combinator::Descendant => {
+            let mut parent = node.parent;
+            while let Some(p) = parent {
+                if match_complex(complex, i - 1, p) {
+                    return true;
+                }
+                parent = p.parent;
+            }
+            false
+        }
+        Combinator::Child => match node.parent {
+            Some(p) => match_complex(complex, i - 1, p),
+            None => false,
+        },
+        Combinator::Adjacent => match node.prev_elements().next() {
+            Some(sibling) => match_complex(complex, i - 1, &sibling),
+            None => false,
+        },
+        Combinator::Sibling => node
+            .prev_elements()
+            .any(|sibling| match_complex(complex, i - 1, &sibling)),
+    }
+}
+
+fn match_compound(compound: &Compound, node: &Node) -> bool {
+    let tag = match node.dom.get_tag() {
+        Some(tag) if !node.dom.is_root() => tag,
+        _ => return false,
+    };
+    if let Some(name) = &compound.name {
+        if !name.eq_ignore_ascii_case(tag.get_name()) {
+            return false;
+        }
+    }
+    compound
+        .conditions
+        .iter()
+        .all(|condition| match_condition(condition, tag, node))
+}
+
+fn match_condition(condition: &Condition, tag: &Tag, node: &Node) -> bool {
+    match condition {
+        Condition::Id(id) => attr_value(tag, "id") == Some(id),
+        Condition::Class(class) => match attr_value(tag, "class") {
+            Some(value) => value.split_whitespace().any(|c| c == class),
+            None => false,
+        },
+        Condition::Attr { name, op, value } => match attr_value(tag, name) {
+            Some(v) => match_attr(*op, v, value),
+            None => false,
+        },
+        Condition::FirstChild => node.siblings.is_some() && node.prev_elements().next().is_none(),
+        Condition::LastChild => match node.siblings {
+            Some(siblings) => siblings[node.index + 1..]
+                .iter()
+                .all(|dom| dom.dom_type != DomType::Tag),
+            None => false,
+        },
+        Condition::NthChild(a, b) => {
+            if node.siblings.is_none() {
+                return false;
+            }
+            let position = node.prev_elements().count() as i128 + 1;
+            // i128 does not overflow with any a and b
+            let (a, b) = (*a as i128, *b as i128);
+            match a {
+                0 => position == b,
+                _ => (position - b) % a == 0 && (position - b) / a >= 0,
+            }
+        }
+        Condition::Not(selector) => !selector.matches(node),
+    }
+}
+
+fn match_attr(op: AttrOp, attr_value: &str, value: &str) -> bool {
+    match op {
+        AttrOp::Exists => true,
+        AttrOp::Equals => attr_value == value,
+        AttrOp::Includes => attr_value.split_whitespace().any(|v| v == value),
+        AttrOp::DashMatch => {
+            attr_value == value
+                || (attr_value.starts_with(value) && attr_value[value.len()..].starts_with('-'))
+        }
+        AttrOp::Prefix => !value.is_empty() && attr_value.starts_with(value),
+        AttrOp::Suffix => !value.is_empty() && attr_value.ends_with(value),
+        AttrOp::Substring => !value.is_empty() && attr_value.contains(value),
+    }
+}
+
+/// Returns the value of the first attribute whose name matches `name` case-insensitively.
+fn attr_value<'t>(tag: &'t Tag, name: &str) -> Option<&'t str> {
+    tag.get_attrs()?
+        .iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case(name))
+        .map(|(_, v)| v)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(doms: Vec<&Dom>) -> Vec<String> {
+        doms.iter()
+            .map(|dom| {
+                let tag = dom.get_tag().unwrap();
+                match tag.get_attr("id") {
+                    Some(id) => format!("{}#{}", tag.get_name(), id),
+                    None => tag.get_name().to_string(),
+                }
+            })
+            .collect()
+    }
+
+    fn select(html: &str, selector: &str) -> Vec<String> {
+        let dom = crate::parser::parse(html).unwrap();
+        names(Selector::parse(selector).unwrap().select(&dom))
+    }
+
+    #[test]
+    fn combinator_test() {
+        let html = r#"<div id="d"><p id="p1"><span id="s1"></span></p><span id="s2"></span><p id="p2"></p><p id="p3"></p></div>"#;
+        assert_eq!(select(html, "div span"), vec!["span#s1", "span#s2"]);
+        assert_eq!(select(html, "div > span"), vec!["span#s2"]);
+        assert_eq!(select(html, "p + span"), vec!["span#s2"]);
+        assert_eq!(select(html, "span + p"), vec!["p#p2"]);
+        assert_eq!(select(html, "span ~ p"), vec!["p#p2", "p#p3"]);
+        assert_eq!(
+            select(html, "#p1 span, #d > p:last-child"),
+            vec!["span#s1", "p#p3"]
+        );
+    }
+
+    #[test]
+    fn nth_child_test() {
+        let html = "<ul><li id=\"1\"></li>text<li id=\"2\"></li><li id=\"3\"></li><li id=\"4\"></li><li id=\"5\"></li></ul>";
+        assert_eq!(select(html, "li:nth-child(2)"), vec!["li#2"]);
+        assert_eq!(
+            select(html, "li:nth-child(odd)"),
+            vec!["li#1", "li#3", "li#5"]
+        );
+        assert_eq!(select(html, "li:nth-child(2n)"), vec!["li#2", "li#4"]);
+        assert_eq!(select(html, "li:nth-child(-n+2)"), vec!["li#1", "li#2"]);
+        // a and b near the limits do not overflow
+        assert!(select(html, "li:nth-child(n-9223372036854775807)").len() == 5);
+        assert_eq!(
+            select(html, "li:nth-child(2n-9223372036854775807)"),
+            vec!["li#1", "li#3", "li#5"]
+        );
+        assert!(select(html, "li:nth-child(-1n+9223372036854775807)").len() == 5);
+        assert!(select(html, "li:nth-child(-9223372036854775808n)").is_empty());
+        assert_eq!(select(html, "li:first-child"), vec!["li#1"]);
+        assert_eq!(select(html, "ul:first-child"), vec!["ul"]);
+    }
+
+    #[test]
+    fn attr_test() {
+        assert!(match_attr(AttrOp::Includes, "a b c", "b"));
+        assert!(!match_attr(AttrOp::Includes, "abc", "b"));
+        assert!(match_attr(AttrOp::DashMatch, "en-US", "en"));
+        assert!(match_attr(AttrOp::DashMatch, "en", "en"));
+        assert!(!match_attr(AttrOp::DashMatch, "english", "en"));
+        assert!(match_attr(AttrOp::Prefix, "https://a", "https"));
+        assert!(match_attr(AttrOp::Suffix, "a.pdf", ".pdf"));
+        assert!(match_attr(AttrOp::Substring, "abc", "b"));
+        assert!(!match_attr(AttrOp::Substring, "abc", ""));
+    }
+}
@@ -0,0 +1,378 @@
+//! Module for parsing CSS selectors.
+
+use super::AttrOp;
+use super::Combinator;
+use super::Complex;
+use super::Compound;
+use super::Condition;
+use super::Selector;
+use super::SelectorError;
+
+/// A cursor over the characters of a selector.
+struct SelectorParser<'s> {
+    src: &'s str,
+    chars: Vec<(usize, char)>,
+    cursor: usize,
+}
+
+/// Parse the selector group.
+/// grammar: `complex [, complex]*`
+pub fn parse(src: &str) -> Result<Selector, SelectorError> {
+    let mut parser = SelectorParser {
+        src,
+        chars: src.char_indices().collect(),
+        cursor: 0,
+    };
+    let selector = parser.parse_group()?;
+    if let Some(c) = parser.peek() {
+        return Err(parser.error(&format!("Unexpected character '{}'", c)));
+    }
+    Ok(selector)
+}
+
+impl<'s> SelectorParser<'s> {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.cursor).map(|(_, c)| *c)
+    }
+
+    fn next(&mut self) {
+        self.cursor += 1;
+    }
+
+    /// Returns true and moves the cursor to the next character if the current character is `c`.
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.next();
+            return true;
+        }
+        false
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), SelectorError> {
+        if self.eat(c) {
+            return Ok(());
+        }
+        Err(self.error(&format!("Expected '{}'", c)))
+    }
+
+    /// Returns true if any whitespace was skipped.
+    fn skip_whitespace(&mut self) -> bool {
+        let bgn = self.cursor;
+        while let Some(c) = self.peek() {
+            if !c.is_whitespace() {
+                break;
+            }
+            self.next();
+        }
+        self.cursor != bgn
+    }
+
+    /// Byte offset of the cursor.
+    fn offset(&self) -> usize {
+        match self.chars.get(self.cursor) {
+            Some((offset, _)) => *offset,
+            None => self.src.len(),
+        }
+    }
+
+    fn error(&self, message: &str) -> SelectorError {
+        SelectorError::new(message, self.offset(), self.src)
+    }
+
+    /// grammar: `complex [, complex]*`
+    /// Parsing stops before ')' or the end of the selector.
+    fn parse_group(&mut self) -> Result<Selector, SelectorError> {
+        let mut complexes = Vec::new();
+        loop {
+            complexes.push(self.parse_complex()?);
+            if !self.eat(',') {
+                break;
+            }
+        }
+        Ok(Selector { complexes })
+    }
+
+    /// grammar: `compound [combinator compound]*`
+    fn parse_complex(&mut self) -> Result<Complex, SelectorError> {
+        self.skip_whitespace();
+        let mut compounds = vec![self.parse_compound()?];
+        let mut combinators = Vec::new();
+        loop {
+            let whitespace = self.skip_whitespace();
+            let combinator = match self.peek() {
+                None | Some(',') | Some(')') => break,
+                Some('>') => Combinator::Child,
+                Some('+') => Combinator::Adjacent,
+                Some('~') => Combinator::Sibling,
+                Some(_) if whitespace => Combinator::Descendant,
+                Some(c) => return Err(self.error(&format!("Unexpected character '{}'", c))),
+            };
+            if combinator != Combinator::Descendant {
+                self.next();
+                self.skip_whitespace();
+            }
+            combinators.push(combinator);
+            compounds.push(self.parse_compound()?);
+        }
+        Ok(Complex {
+            compounds,
+            combinators,
+        })
+    }
+
+    /// grammar: `[name|*] [#id|.class|[attr]|:pseudo]*`
+    fn parse_compound(&mut self) -> Result<Compound, SelectorError> {
+        let mut name = None;
+        let universal = self.eat('*');
+        if !universal && self.peek().is_some_and(is_ident_char) {
+            name = Some(self.parse_ident()?);
+        }
+
+        let mut conditions = Vec::new();
+        loop {
+            match self.peek() {
+                Some('#') => {
+                    self.next();
+                    conditions.push(Condition::Id(self.parse_ident()?));
+                }
+                Some('.') => {
+                    self.next();
+                    conditions.push(Condition::Class(self.parse_ident()?));
+                }
+                Some('[') => {
+                    self.next();
+                    conditions.push(self.parse_attr()?);
+                }
+                Some(':') => {
+                    self.next();
+                    conditions.push(self.parse_pseudo()?);
+                }
+                _ => break,
+            }
+        }
+
+        if !universal && name.is_none() && conditions.is_empty() {
+            return Err(self.error("Expected a selector"));
+        }
+        Ok(Compound { name, conditions })
+    }
+
+    /// Parse the identifier. A character escaped by '\' is taken as it is.
+    fn parse_ident(&mut self) -> Result<String, SelectorError> {
+        let mut ident = String::new();
+        while let Some(c) = self.peek() {
+            if c == '\\' {
+                self.next();
+                match self.peek() {
+                    Some(c) => ident.push(c),
+                    None => return Err(self.error("Input ends after '\\'")),
+                }
+            } else if is_ident_char(c) {
+                ident.push(c);
+            } else {
+                break;
+            }
+            self.next();
+        }
+        if ident.is_empty() {
+            return Err(self.error("Expected an identifier"));
+        }
+        Ok(ident)
+    }
+
+    /// The cursor points to the character after '['.
+    /// grammar: `attr [op value]]`
+    fn parse_attr(&mut self) -> Result<Condition, SelectorError> {
+        self.skip_whitespace();
+        let name = self.parse_ident()?;
+        self.skip_whitespace();
+        if self.eat(']') {
+            return Ok(Condition::Attr {
+                name,
+                op: AttrOp::Exists,
+                value: String::new(),
+            });
+        }
+
+        let op = match self.peek() {
+            Some('=') => AttrOp::Equals,
+            Some('~') => AttrOp::Includes,
+            Some('|') => AttrOp::DashMatch,
+            Some('^') => AttrOp::Prefix,
+            Some('$') => AttrOp::Suffix,
+            Some('*') => AttrOp::Substring,
+            _ => return Err(self.error("Expected an attribute operator")),
+        };
+        self.next();
+        if op != AttrOp::Equals {
+            self.expect('=')?;
+        }
+
+        self.skip_whitespace();
+        let value = match self.peek() {
+            Some(q) if q == '"' || q == '\'' => self.parse_string(q)?,
+            _ => self.parse_ident()?,
+        };
+        self.skip_whitespace();
+        self.expect(']')?;
+        Ok(Condition::Attr { name, op, value })
+    }
+
+    /// The cursor points to the opening quote.
+    fn parse_string(&mut self, quote: char) -> Result<String, SelectorError> {
+        self.next();
+        let mut s = String::new();
+        loop {
+            match self.peek() {
+                None => return Err(self.error(&format!("Expected '{}'", quote))),
+                Some(c) if c == quote => break,
+                Some('\\') => {
+                    self.next();
+                    if let Some(c) = self.peek() {
+                        s.push(c);
+                    }
+                }
+                Some(c) => s.push(c),
+            }
+            self.next();
+        }
+        self.next();
+        Ok(s)
+    }
+
+    /// The cursor points to the character after ':'.
+    fn parse_pseudo(&mut self) -> Result<Condition, SelectorError> {
+        let offset = self.cursor;
+        let name = self.parse_ident()?.to_ascii_lowercase();
+        match name.as_str() {
+            "first-child" => Ok(Condition::FirstChild),
+            "last-child" => Ok(Condition::LastChild),
+            "nth-child" => {
+                self.expect('(')?;
+                let (a, b) = self.parse_nth()?;
+                self.expect(')')?;
+                Ok(Condition::NthChild(a, b))
+            }
+            "not" => {
+                self.expect('(')?;
+                let selector = self.parse_group()?;
+                self.skip_whitespace();
+                self.expect(')')?;
+                Ok(Condition::Not(selector))
+            }
+            _ => {
+                self.cursor = offset;
+                Err(self.error(&format!("Unsupported pseudo-class ':{}'", name)))
+            }
+        }
+    }
+
+    /// Parse the argument of `:nth-child()`.
+    /// grammar: `odd | even | an+b | b`
+    /// Whitespace is allowed only around the sign of `b`. e.g. `2n + 1`, not `- n`
+    fn parse_nth(&mut self) -> Result<(i64, i64), SelectorError> {
+        let bgn = self.cursor;
+        let mut arg = String::new();
+        while let Some(c) = self.peek() {
+            if c == ')' {
+                break;
+            }
+            arg.push(c.to_ascii_lowercase());
+            self.next();
+        }
+
+        let arg = arg.trim();
+        let nth = match arg {
+            "odd" => Some((2, 1)),
+            "even" => Some((2, 0)),
+            _ => match arg.find('n') {
+                Some(i) => {
+                    let a = match &arg[..i] {
+                        "" | "+" => Some(1),
+                        "-" => Some(-1),
+                        a => parse_integer(a),
+                    };
+                    let b = match arg[i + 1..].trim_start() {
+                        "" => Some(0),
+                        b => match (b.strip_prefix('+'), b.strip_prefix('-')) {
+                            (Some(digits), _) => parse_digits(digits.trim_start()),
+                            (_, Some(digits)) => parse_digits(digits.trim_start()).map(|b| -b),
+                            _ => None,
+                        },
+                    };
+                    a.zip(b)
+                }
+                None => parse_integer(arg).map(|b| (0, b)),
+            },
+        };
+        match nth {
+            Some(nth) => Ok(nth),
+            None => {
+                self.cursor = bgn;
+                Err(self.error("Invalid argument of :nth-child()"))
+            }
+        }
+    }
+}
+
+/// Parses the integer with an optional sign and without whitespace.
+fn parse_integer(s: &str) -> Option<i64> {
+    if s.contains(char::is_whitespace) {
+        return None;
+    }
+    s.parse().ok()
+}
+
+/// Parses the integer without a sign.
+fn parse_digits(s: &str) -> Option<i64> {
+    if s.is_empty() || !s.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    s.parse().ok()
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '\\' || !c.is_ascii()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_nth_test() {
+        let nth = |s: &str| match parse(&format!(":nth-child({})", s)).unwrap().complexes[0]
+            .compounds[0]
+            .conditions[0]
+        {
+            Condition::NthChild(a, b) => (a, b),
+            _ => panic!(),
+        };
+        assert_eq!(nth("odd"), (2, 1));
+        assert_eq!(nth("even"), (2, 0));
+        assert_eq!(nth("3"), (0, 3));
+        assert_eq!(nth("2n + 1"), (2, 1));
+        assert_eq!(nth("-n+3"), (-1, 3));
+        assert_eq!(nth("n"), (1, 0));
+        assert_eq!(nth(" -2n- 3 "), (-2, -3));
+        assert!(parse(":nth-child(2x)").is_err());
+        assert!(parse(":nth-child(- n)").is_err());
+        assert!(parse(":nth-child(2 n)").is_err());
+        assert!(parse(":nth-child(+ 3)").is_err());
+        assert!(parse(":nth-child(2n 1)").is_err());
+        assert!(parse(":nth-child(2n+ +1)").is_err());
+    }
+
+    #[test]
+    fn parse_error_test() {
+        let e = parse("div > ").unwrap_err();
+        assert_eq!(e.offset(), 6);
+        assert_eq!(e.to_string(), "Expected a selector at offset 6: `div > `");
+
+        assert!(parse("a[href^]").is_err());
+        assert!(parse("a[href='x'").is_err());
+        assert!(parse("li:hover").is_err());
+        assert!(parse("ul,").is_err());
+        assert!(parse("").is_err());
+    }
+}
@@ -0,0 +1,325 @@
+//! Module for converting the Dom structure tree to a tag document.
+
+use std::fmt;
+use std::io;
+use std::io::Write;
+
+use crate::dom::elements::RAW_TEXT_ELEMENTS;
+use crate::dom::elements::VOID_ELEMENTS;
+use crate::dom::Dom;
+use crate::dom::DomType;
+use crate::dom::Tag;
+use crate::ParseMode;
+
+/// Options for `parsercher::serialize_with()`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SerializeOptions {
+    /// If true, each tag, text and comment is written on its own line with indentation.
+    /// Whitespace around texts is not kept.
+    /// Default is false.
+    pub pretty: bool,
+    /// String written for each depth of the tree if `pretty` is true.
+    /// Default is two spaces.
+    pub indent: String,
+    /// How elements are written. Use the mode the document was parsed with.
+    /// In `ParseMode::Html`, the void elements (e.g. `<br>`) have no end tag and the texts in
+    /// the raw text elements (e.g. `<script>`) are not escaped.
+    /// In `ParseMode::Xml`, every element has its end tag unless it is terminated (`<br />`).
+    /// Default is `ParseMode::Html`.
+    pub mode: ParseMode,
+}
+
+impl Default for SerializeOptions {
+    fn default() -> SerializeOptions {
+        SerializeOptions {
+            pretty: false,
+            indent: String::from("  "),
+            mode: ParseMode::Html,
+        }
+    }
+}
+
+/// Writes the Dom structure tree as a tag document to `writer`.
+///
+/// The root dom created by `parsercher::parse()` is not written, only its children are.
+/// Characters in texts and attribute values are escaped.
+///
+/// # Errors
+/// Returns the error of `writer`.
+///
+/// # Examples
+/// ```rust
+/// let html = r#"<ul class="list"><li>Tom &amp; Jerry</li><li><br /></li></ul>"#;
+/// let dom = parsercher::parse(&html).unwrap();
+///
+/// let mut buf: Vec<u8> = Vec::new();
+/// parsercher::serialize(&dom, &mut buf).unwrap();
+/// assert_eq!(String::from_utf8(buf).unwrap(), html);
+/// ```
+pub fn serialize<W: Write>(dom: &Dom, writer: &mut W) -> io::Result<()> {
+    serialize_with(dom, writer, &SerializeOptions::default())
+}
+
+/// Writes the Dom structure tree as a tag document to `writer` with `options`.
+///
+/// # Errors
+/// Returns the error of `writer`.
+///
+/// # Examples
+/// ```rust
+/// use parsercher::SerializeOptions;
+///
+/// let html = r#"<ul class="list"><li>first</li><li><span>second</span></li></ul>"#;
+/// let dom = parsercher::parse(&html).unwrap();
+///
+/// let options = SerializeOptions {
+///     pretty: true,
+///     indent: String::from("    "),
+///     ..Default::default()
+/// };
+/// let mut buf: Vec<u8> = Vec::new();
+/// parsercher::serialize_with(&dom, &mut buf, &options).unwrap();
+/// assert_eq!(
+///     String::from_utf8(buf).unwrap(),
+///     r#"<ul class="list">
+///     <li>first</li>
+///     <li>
+///         <span>second</span>
+///     </li>
+/// </ul>
+/// "#
+/// );
+/// ```
+pub fn serialize_with<W: Write>(
+    dom: &Dom,
+    writer: &mut W,
+    options: &SerializeOptions,
+) -> io::Result<()> {
+    if dom.is_root() {
+        if let Some(children) = dom.get_children() {
+            for child in children.iter() {
+                serialize_exe(child, writer, options, 0)?;
+            }
+        }
+        return Ok(());
+    }
+    serialize_exe(dom, writer, options, 0)
+}
+
+fn serialize_exe<W: Write>(
+    dom: &Dom,
+    writer: &mut W,
+    options: &SerializeOptions,
+    depth: usize,
+) -> io::Result<()> {
+    match dom.dom_type {
+        DomType::Tag => serialize_tag(dom, writer, options, depth),
+        DomType::Text => {
+            let text = dom.get_text().unwrap().get_text();
+            if !options.pretty {
+                return write!(writer, "{}", escape_text(text));
+            }
+            let text = text.trim();
+            if text.is_empty() {
+                return Ok(());
+            }
+            write_indent(writer, options, depth)?;
+            writeln!(writer, "{}", escape_text(text))
+        }
+        DomType::Comment => {
+            let comment = dom.get_comment().unwrap().get_comment();
+            if !options.pretty {
+                return write!(writer, "<!--{}-->", comment);
+            }
+            write_indent(writer, options, depth)?;
+            writeln!(writer, "<!--{}-->", comment)
+        }
+        DomType::Doctype => {
+            let doctype = dom.get_doctype().unwrap();
+            if !options.pretty {
+                return write!(writer, "{}", doctype);
+            }
+            write_indent(writer, options, depth)?;
+            writeln!(writer, "{}", doctype)
+        }
+        DomType::CData => {
+            let cdata = dom.get_cdata().unwrap();
+            if !options.pretty {
+                return write!(writer, "{}", cdata);
+            }
+            write_indent(writer, options, depth)?;
+            writeln!(writer, "{}", cdata)
+        }
+        DomType::ProcessingInstruction => {
+            let pi = dom.get_processing_instruction().unwrap();
+            if !options.pretty {
+                return write!(writer, "{}", pi);
+            }
+            write_indent(writer, options, depth)?;
+            writeln!(writer, "{}", pi)
+        }
+    }
+}
+
+fn serialize_tag<W: Write>(
+    dom: &Dom,
+    writer: &mut W,
+    options: &SerializeOptions,
+    depth: usize,
+) -> io::Result<()> {
+    let tag = dom.get_tag().unwrap();
+    let name = tag.get_name();
+    let html = options.mode == ParseMode::Html;
+    let void = html
+        && VOID_ELEMENTS
+            .iter()
+            .any(|void| void.eq_ignore_ascii_case(name));
+
+    if options.pretty {
+        write_indent(writer, options, depth)?;
+    }
+    write_start_tag(tag, writer)?;
+
+    let children = match dom.get_children() {
+        Some(children) => children,
+        None => {
+            if !tag.is_terminated() && !void {
+                write!(writer, "</{}>", name)?;
+            }
+            if options.pretty {
+                writeln!(writer)?;
+            }
+            return Ok(());
+        }
+    };
+
+    if html
+        && RAW_TEXT_ELEMENTS
+            .iter()
+            .any(|raw| raw.eq_ignore_ascii_case(name))
+    {
+        // the content is written on the same line without escaping the texts
+        let inline = SerializeOptions {
+            pretty: false,
+            ..options.clone()
+        };
+        for child in children.iter() {
+            match child.get_text() {
+                Some(text) => write!(writer, "{}", text.get_text())?,
+                None => serialize_exe(child, writer, &inline, depth + 1)?,
+            }
+        }
+    } else if !options.pretty {
+        for child in children.iter() {
+            serialize_exe(child, writer, options, depth + 1)?;
+        }
+    } else if children.iter().all(|child| child.dom_type == DomType::Text) {
+        // Texts are written on the same line as the tags
+        for child in children.iter() {
+            write!(
+                writer,
+                "{}",
+                escape_text(child.get_text().unwrap().get_text().trim())
+            )?;
+        }
+    } else {
+        writeln!(writer)?;
+        for child in children.iter() {
+            serialize_exe(child, writer, options, depth + 1)?;
+        }
+        write_indent(writer, options, depth)?;
+    }
+
+    write!(writer, "</{}>", name)?;
+    if options.pretty {
+        writeln!(writer)?;
+    }
+    Ok(())
+}
+
+fn write_start_tag<W: Write>(tag: &Tag, writer: &mut W) -> io::Result<()> {
+    write!(writer, "<{}", tag.get_name())?;
+    if let Some(attrs) = tag.get_attrs() {
+        for (attr, value) in attrs.iter() {
+            write!(writer, " {}=\"{}\"", attr, escape_attr(value))?;
+        }
+    }
+    if tag.is_terminated() {
+        write!(writer, " />")
+    } else {
+        write!(writer, ">")
+    }
+}
+
+fn write_indent<W: Write>(
+    writer: &mut W,
+    options: &SerializeOptions,
+    depth: usize,
+) -> io::Result<()> {
+    for _ in 0..depth {
+        write!(writer, "{}", options.indent)?;
+    }
+    Ok(())
+}
+
+/// Escapes '&', '<' and '>' in the text.
+fn escape_text(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Escapes '&', '<', '>' and '"' in the attribute value.
+fn escape_attr(s: &str) -> String {
+    escape_text(s).replace('"', "&quot;")
+}
+
+/// Writes the Dom structure tree as a tag document.
+/// `{}` is the same as `Dom::to_html()`, `{:#}` writes it with indentation.
+impl fmt::Display for Dom {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let options = SerializeOptions {
+            pretty: f.alternate(),
+            ..Default::default()
+        };
+        let mut buf: Vec<u8> = Vec::new();
+        serialize_with(self, &mut buf, &options).map_err(|_| fmt::Error)?;
+        f.write_str(&String::from_utf8_lossy(&buf))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dom::Text;
+
+    #[test]
+    fn escape_test() {
+        assert_eq!(
+            escape_text("a < b && c > d"),
+            "a &lt; b &amp;&amp; c &gt; d"
+        );
+        assert_eq!(escape_attr("say \"hi\""), "say &quot;hi&quot;");
+    }
+
+    #[test]
+    fn serialize_built_dom() {
+        let mut dom = Dom::new(DomType::Tag);
+        let mut tag = Tag::new("p");
+        tag.set_attr("title", "\"quoted\"");
+        dom.set_tag(tag);
+
+        let mut text = Dom::new(DomType::Text);
+        text.set_text(Text::new("1 < 2"));
+        dom.add_child(text);
+
+        let mut br = Dom::new(DomType::Tag);
+        br.set_tag(Tag::new("br"));
+        dom.add_child(br);
+
+        assert_eq!(
+            dom.to_string(),
+            "<p title=\"&quot;quoted&quot;\">1 &lt; 2<br></p>"
+        );
+    }
+}
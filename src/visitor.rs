@@ -0,0 +1,272 @@
+//! Module of the visitor traits over the Dom structure tree.
+
+use crate::dom::CData;
+use crate::dom::Comment;
+use crate::dom::Doctype;
+use crate::dom::Dom;
+use crate::dom::DomType;
+use crate::dom::ProcessingInstruction;
+use crate::dom::Tag;
+use crate::dom::Text;
+
+/// Visitor over the Dom structure tree. It is driven by `Dom::visit()`.
+///
+/// By default, `visit_tag()` visits the children of the tag and the others do nothing.
+/// When `visit_tag()` is overridden, call `dom.visit(self)` to visit the children.
+///
+/// # Examples
+/// ```rust
+/// use parsercher::dom::{Dom, Tag};
+/// use parsercher::Visitor;
+///
+/// struct LinkCollector(Vec<String>);
+///
+/// impl Visitor for LinkCollector {
+///     fn visit_tag(&mut self, dom: &Dom, tag: &Tag) {
+///         if let Some(href) = tag.get_attr("href") {
+///             self.0.push(href);
+///         }
+///         dom.visit(self);
+///     }
+/// }
+///
+/// let html = r#"<ul><li><a href="/1">1</a></li><li><a href="/2">2</a></li></ul>"#;
+/// let dom = parsercher::parse(&html).unwrap();
+///
+/// let mut collector = LinkCollector(Vec::new());
+/// dom.visit(&mut collector);
+/// assert_eq!(collector.0, vec!["/1", "/2"]);
+/// ```
+pub trait Visitor {
+    /// Called for a Dom structure of `DomType::Tag`.
+    fn visit_tag(&mut self, dom: &Dom, _tag: &Tag) {
+        dom.visit(self);
+    }
+
+    /// Called for a Dom structure of `DomType::Text`.
+    fn visit_text(&mut self, _dom: &Dom, _text: &Text) {}
+
+    /// Called for a Dom structure of `DomType::Comment`.
+    fn visit_comment(&mut self, _dom: &Dom, _comment: &Comment) {}
+
+    /// Called for a Dom structure of `DomType::Doctype`.
+    fn visit_doctype(&mut self, _dom: &Dom, _doctype: &Doctype) {}
+
+    /// Called for a Dom structure of `DomType::CData`.
+    fn visit_cdata(&mut self, _dom: &Dom, _cdata: &CData) {}
+
+    /// Called for a Dom structure of `DomType::ProcessingInstruction`.
+    fn visit_processing_instruction(&mut self, _dom: &Dom, _pi: &ProcessingInstruction) {}
+}
+
+/// What to do with the Dom structure visited by `VisitorMut`.
+#[derive(Debug, PartialEq, Clone)]
+#[allow(clippy::large_enum_variant)]
+pub enum VisitAction {
+    /// Keep the Dom structure.
+    Keep,
+    /// Remove the Dom structure and its children.
+    Remove,
+    /// Replace the Dom structure with the Dom structures. The replacements are not visited.
+    Replace(Vec<Dom>),
+    /// Add the Dom structure as the last child of the Dom structure, and put it in the place.
+    Wrap(Dom),
+}
+
+/// Visitor that can edit the Dom structure tree. It is driven by `Dom::visit_mut()`.
+///
+/// Each method returns `VisitAction` to keep, remove, replace or wrap the visited Dom structure.
+/// By default, `visit_tag()` visits the children of the tag and all of them keep the Dom structure.
+/// When `visit_tag()` is overridden, call `dom.visit_mut(self)` to visit the children.
+///
+/// # Examples
+/// ```rust
+/// use parsercher::dom::Dom;
+/// use parsercher::{VisitAction, VisitorMut};
+///
+/// struct ScriptRemover;
+///
+/// impl VisitorMut for ScriptRemover {
+///     fn visit_tag(&mut self, dom: &mut Dom) -> VisitAction {
+///         if dom.get_tag().unwrap().get_name() == "script" {
+///             return VisitAction::Remove;
+///         }
+///         dom.visit_mut(self);
+///         VisitAction::Keep
+///     }
+///
+///     fn visit_comment(&mut self, _dom: &mut Dom) -> VisitAction {
+///         VisitAction::Remove
+///     }
+/// }
+///
+/// let html = r#"<body><script src="track.js"></script><!-- ad --><p>text</p></body>"#;
+/// let mut dom = parsercher::parse(&html).unwrap();
+///
+/// dom.visit_mut(&mut ScriptRemover);
+/// assert_eq!(dom.to_html(), "<body><p>text</p></body>");
+/// ```
+pub trait VisitorMut {
+    /// Called for a Dom structure of `DomType::Tag`.
+    fn visit_tag(&mut self, dom: &mut Dom) -> VisitAction {
+        dom.visit_mut(self);
+        VisitAction::Keep
+    }
+
+    /// Called for a Dom structure of `DomType::Text`.
+    fn visit_text(&mut self, _dom: &mut Dom) -> VisitAction {
+        VisitAction::Keep
+    }
+
+    /// Called for a Dom structure of `DomType::Comment`.
+    fn visit_comment(&mut self, _dom: &mut Dom) -> VisitAction {
+        VisitAction::Keep
+    }
+
+    /// Called for a Dom structure of `DomType::Doctype`.
+    fn visit_doctype(&mut self, _dom: &mut Dom) -> VisitAction {
+        VisitAction::Keep
+    }
+
+    /// Called for a Dom structure of `DomType::CData`.
+    fn visit_cdata(&mut self, _dom: &mut Dom) -> VisitAction {
+        VisitAction::Keep
+    }
+
+    /// Called for a Dom structure of `DomType::ProcessingInstruction`.
+    fn visit_processing_instruction(&mut self, _dom: &mut Dom) -> VisitAction {
+        VisitAction::Keep
+    }
+}
+
+/// Calls the visitor for each child of `dom`.
+pub(crate) fn visit_children<V: Visitor + ?Sized>(visitor: &mut V, dom: &Dom) {
+    if let Some(children) = dom.get_children() {
+        for child in children.iter() {
+            match child.dom_type {
+                DomType::Tag => visitor.visit_tag(child, child.get_tag().unwrap()),
+                DomType::Text => visitor.visit_text(child, child.get_text().unwrap()),
+                DomType::Comment => visitor.visit_comment(child, child.get_comment().unwrap()),
+                DomType::Doctype => visitor.visit_doctype(child, child.get_doctype().unwrap()),
+                DomType::CData => visitor.visit_cdata(child, child.get_cdata().unwrap()),
+                DomType::ProcessingInstruction => visitor.visit_processing_instruction(
+                    child,
+                    child.get_processing_instruction().unwrap(),
+                ),
+            }
+        }
+    }
+}
+
+/// Calls the visitor for each child of `dom` and applies the actions.
+pub(crate) fn visit_children_mut<V: VisitorMut + ?Sized>(visitor: &mut V, dom: &mut Dom) {
+    let children = match dom.take_children() {
+        Some(children) => children,
+        None => return,
+    };
+    for mut child in children.into_iter() {
+        let action = match child.dom_type {
+            DomType::Tag => visitor.visit_tag(&mut child),
+            DomType::Text => visitor.visit_text(&mut child),
+            DomType::Comment => visitor.visit_comment(&mut child),
+            DomType::Doctype => visitor.visit_doctype(&mut child),
+            DomType::CData => visitor.visit_cdata(&mut child),
+            DomType::ProcessingInstruction => visitor.visit_processing_instruction(&mut child),
+        };
+        match action {
+            VisitAction::Keep => dom.add_child(*child),
+            VisitAction::Remove => (),
+            VisitAction::Replace(doms) => {
+                for replacement in doms.into_iter() {
+                    dom.add_child(replacement);
+                }
+            }
+            VisitAction::Wrap(mut wrapper) => {
+                wrapper.add_child(*child);
+                dom.add_child(wrapper);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser;
+
+    struct Counter {
+        tags: usize,
+        texts: usize,
+        comments: usize,
+    }
+
+    impl Visitor for Counter {
+        fn visit_tag(&mut self, dom: &Dom, _tag: &Tag) {
+            self.tags += 1;
+            dom.visit(self);
+        }
+
+        fn visit_text(&mut self, _dom: &Dom, _text: &Text) {
+            self.texts += 1;
+        }
+
+        fn visit_comment(&mut self, _dom: &Dom, _comment: &Comment) {
+            self.comments += 1;
+        }
+    }
+
+    #[test]
+    fn visit_test() {
+        let dom = parser::parse("<a><b>text</b><!--c--></a><d>text</d>").unwrap();
+        let mut counter = Counter {
+            tags: 0,
+            texts: 0,
+            comments: 0,
+        };
+        dom.visit(&mut counter);
+        assert_eq!(counter.tags, 3);
+        assert_eq!(counter.texts, 2);
+        assert_eq!(counter.comments, 1);
+    }
+
+    struct Editor;
+
+    impl VisitorMut for Editor {
+        fn visit_tag(&mut self, dom: &mut Dom) -> VisitAction {
+            match dom.get_tag().unwrap().get_name() {
+                "i" => VisitAction::Remove,
+                "a" => {
+                    let mut wrapper = Dom::new(DomType::Tag);
+                    wrapper.set_tag(Tag::new("p"));
+                    VisitAction::Wrap(wrapper)
+                }
+                _ => {
+                    dom.visit_mut(self);
+                    VisitAction::Keep
+                }
+            }
+        }
+
+        fn visit_text(&mut self, dom: &mut Dom) -> VisitAction {
+            let text = dom.get_text().unwrap().get_text().trim().to_string();
+            let mut replacement = Dom::new(DomType::Text);
+            replacement.set_text(Text::new(&text));
+            VisitAction::Replace(vec![replacement])
+        }
+    }
+
+    #[test]
+    fn visit_mut_test() {
+        let mut dom = parser::parse("<div><i>x</i><a>link</a><span> text </span></div>").unwrap();
+        dom.visit_mut(&mut Editor);
+        assert_eq!(
+            dom.to_html(),
+            "<div><p><a>link</a></p><span>text</span></div>"
+        );
+
+        let mut dom = parser::parse("<div><i></i></div>").unwrap();
+        dom.visit_mut(&mut Editor);
+        let div = &dom.get_children().unwrap()[0];
+        assert_eq!(div.get_children(), None);
+    }
+}
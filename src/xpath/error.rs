@@ -0,0 +1,44 @@
+//! Module of XPathError.
+
+use std::error::Error;
+use std::fmt;
+
+/// An error that occurs while parsing or evaluating an XPath expression.
+#[derive(Debug, PartialEq, Clone)]
+pub struct XPathError {
+    message: String,
+    offset: usize,
+    expr: String,
+}
+
+impl XPathError {
+    pub(crate) fn new(message: &str, offset: usize, expr: &str) -> XPathError {
+        XPathError {
+            message: String::from(message),
+            offset,
+            expr: String::from(expr),
+        }
+    }
+
+    /// Returns the byte offset in the expression where the error occurred.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns the expression that failed.
+    pub fn expr(&self) -> &str {
+        &self.expr
+    }
+}
+
+impl fmt::Display for XPathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at offset {}: `{}`",
+            self.message, self.offset, self.expr
+        )
+    }
+}
+
+impl Error for XPathError {}
@@ -0,0 +1,644 @@
+//! Module for evaluating parsed XPath expressions.
+
+use super::Axis;
+use super::BinaryOp;
+use super::Expr;
+use super::NodeTest;
+use super::Path;
+use super::Step;
+use super::XPathError;
+use super::XPathNode;
+use super::XPathValue;
+
+use crate::dom::DomTree;
+use crate::dom::DomType;
+use crate::dom::NodeId;
+
+/// A node of the tree or an attribute of an element.
+/// Ordered in document order. The attributes are after their element and before its children.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+struct NodeRef {
+    id: NodeId,
+    /// Index of the attribute.
+    attr: Option<usize>,
+}
+
+impl NodeRef {
+    fn new(id: NodeId) -> NodeRef {
+        NodeRef { id, attr: None }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Value {
+    /// Nodes in document order.
+    Nodes(Vec<NodeRef>),
+    String(String),
+    Number(f64),
+    Boolean(bool),
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Context {
+    node: NodeRef,
+    position: usize,
+    size: usize,
+}
+
+struct Evaluator<'t, 'a> {
+    tree: &'t DomTree<'a>,
+    src: &'t str,
+}
+
+/// Evaluates `expr` with the root of `tree` as the context node.
+pub fn evaluate<'a>(
+    tree: &DomTree<'a>,
+    expr: &Expr,
+    src: &str,
+) -> Result<XPathValue<'a>, XPathError> {
+    let evaluator = Evaluator { tree, src };
+    let ctx = Context {
+        node: NodeRef::new(tree.root()),
+        position: 1,
+        size: 1,
+    };
+    Ok(match evaluator.eval(expr, &ctx)? {
+        Value::Nodes(nodes) => {
+            XPathValue::Nodes(nodes.iter().map(|n| evaluator.to_xpath_node(*n)).collect())
+        }
+        Value::String(s) => XPathValue::String(s),
+        Value::Number(n) => XPathValue::Number(n),
+        Value::Boolean(b) => XPathValue::Boolean(b),
+    })
+}
+
+/// Converts the number to a string according to the XPath `string()` function.
+pub fn number_to_string(n: f64) -> String {
+    if n.is_nan() {
+        String::from("NaN")
+    } else if n.is_infinite() {
+        String::from(if n > 0.0 { "Infinity" } else { "-Infinity" })
+    } else if n == 0.0 {
+        String::from("0")
+    } else {
+        n.to_string()
+    }
+}
+
+/// Converts the string to a number according to the XPath `number()` function.
+fn string_to_number(s: &str) -> f64 {
+    let s = s.trim_matches(|c| c == ' ' || c == '\t' || c == '\r' || c == '\n');
+    let digits = s.strip_prefix('-').unwrap_or(s);
+    let valid = !digits.is_empty()
+        && digits != "."
+        && digits.chars().all(|c| c.is_ascii_digit() || c == '.')
+        && digits.matches('.').count() <= 1;
+    if !valid {
+        return f64::NAN;
+    }
+    s.parse().unwrap_or(f64::NAN)
+}
+
+/// Rounds the number according to the XPath `round()` function.
+fn round(n: f64) -> f64 {
+    if n.is_nan() || n.is_infinite() {
+        return n;
+    }
+    let r = (n + 0.5).floor();
+    if r == 0.0 && n < 0.0 {
+        return -0.0;
+    }
+    r
+}
+
+impl<'t, 'a> Evaluator<'t, 'a> {
+    fn error(&self, message: &str, offset: usize) -> XPathError {
+        XPathError::new(message, offset, self.src)
+    }
+
+    fn to_xpath_node(&self, node: NodeRef) -> XPathNode<'a> {
+        let dom = self.tree.get(node.id);
+        match node.attr {
+            Some(i) => {
+                let (name, value) = dom
+                    .get_tag()
+                    .unwrap()
+                    .get_attrs()
+                    .unwrap()
+                    .iter()
+                    .nth(i)
+                    .unwrap();
+                XPathNode::Attr {
+                    element: dom,
+                    name,
+                    value,
+                }
+            }
+            None => XPathNode::Dom(dom),
+        }
+    }
+
+    fn eval(&self, expr: &Expr, ctx: &Context) -> Result<Value, XPathError> {
+        match expr {
+            Expr::Binary(op, l, r) => self.eval_binary(*op, l, r, ctx),
+            Expr::Negate(e) => Ok(Value::Number(-self.to_number(&self.eval(e, ctx)?))),
+            Expr::Union(l, r, offset) => {
+                let mut nodes = self.eval_nodes(l, ctx, *offset)?;
+                nodes.extend(self.eval_nodes(r, ctx, *offset)?);
+                nodes.sort();
+                nodes.dedup();
+                Ok(Value::Nodes(nodes))
+            }
+            Expr::Path(path) => self.eval_path(path, ctx).map(Value::Nodes),
+            Expr::Filter {
+                primary,
+                predicates,
+                steps,
+                offset,
+            } => {
+                let mut nodes = self.eval_nodes(primary, ctx, *offset)?;
+                for predicate in predicates.iter() {
+                    nodes = self.filter(nodes, predicate)?;
+                }
+                for step in steps.iter() {
+                    nodes = self.eval_step(&nodes, step)?;
+                }
+                Ok(Value::Nodes(nodes))
+            }
+            Expr::Literal(s) => Ok(Value::String(s.clone())),
+            Expr::Number(n) => Ok(Value::Number(*n)),
+            Expr::Function(name, args, offset) => self.eval_function(name, args, *offset, ctx),
+        }
+    }
+
+    fn eval_nodes(
+        &self,
+        expr: &Expr,
+        ctx: &Context,
+        offset: usize,
+    ) -> Result<Vec<NodeRef>, XPathError> {
+        match self.eval(expr, ctx)? {
+            Value::Nodes(nodes) => Ok(nodes),
+            _ => Err(self.error("Expression must evaluate to a node-set", offset)),
+        }
+    }
+
+    fn eval_path(&self, path: &Path, ctx: &Context) -> Result<Vec<NodeRef>, XPathError> {
+        let start = if path.absolute {
+            NodeRef::new(self.tree.root())
+        } else {
+            ctx.node
+        };
+        let mut nodes = vec![start];
+        for step in path.steps.iter() {
+            nodes = self.eval_step(&nodes, step)?;
+        }
+        Ok(nodes)
+    }
+
+    /// Returns the nodes selected by `step` from each of `nodes` in document order.
+    fn eval_step(&self, nodes: &[NodeRef], step: &Step) -> Result<Vec<NodeRef>, XPathError> {
+        let mut res = Vec::new();
+        for node in nodes.iter() {
+            let mut selected: Vec<NodeRef> = self
+                .axis(*node, step.axis)
+                .into_iter()
+                .filter(|n| self.node_test(*n, step.axis, &step.test))
+                .collect();
+            for predicate in step.predicates.iter() {
+                selected = self.filter(selected, predicate)?;
+            }
+            res.extend(selected);
+        }
+        res.sort();
+        res.dedup();
+        Ok(res)
+    }
+
+    /// Returns the nodes for which `predicate` is true.
+    /// The position of a node is its index in `nodes` starting from 1.
+    fn filter(&self, nodes: Vec<NodeRef>, predicate: &Expr) -> Result<Vec<NodeRef>, XPathError> {
+        let size = nodes.len();
+        let mut res = Vec::new();
+        for (i, node) in nodes.into_iter().enumerate() {
+            let ctx = Context {
+                node,
+                position: i + 1,
+                size,
+            };
+            let satisfied = match self.eval(predicate, &ctx)? {
+                Value::Number(n) => n == (i + 1) as f64,
+                value => self.to_boolean(&value),
+            };
+            if satisfied {
+                res.push(node);
+            }
+        }
+        Ok(res)
+    }
+
+    /// Returns the nodes on the axis in the order of the axis.
+    /// The reverse axes return the nearest node first.
+    fn axis(&self, node: NodeRef, axis: Axis) -> Vec<NodeRef> {
+        let tree = self.tree;
+        let id = node.id;
+        let is_attr = node.attr.is_some();
+        if is_attr {
+            // An attribute has no children, siblings and attributes.
+            match axis {
+                Axis::Child
+                | Axis::Descendant
+                | Axis::Attribute
+                | Axis::FollowingSibling
+                | Axis::PrecedingSibling => return Vec::new(),
+                Axis::DescendantOrSelf => return vec![node],
+                _ => (),
+            }
+        }
+
+        match axis {
+            Axis::SelfNode => vec![node],
+            Axis::Child => tree.children(id).iter().map(|c| NodeRef::new(*c)).collect(),
+            Axis::Descendant => tree.descendants(id).map(NodeRef::new).collect(),
+            Axis::DescendantOrSelf => std::iter::once(node)
+                .chain(tree.descendants(id).map(NodeRef::new))
+                .collect(),
+            Axis::Parent => match node.attr {
+                Some(_) => vec![NodeRef::new(id)],
+                None => tree.parent(id).map(NodeRef::new).into_iter().collect(),
+            },
+            Axis::Ancestor | Axis::AncestorOrSelf => {
+                let mut res = Vec::new();
+                if axis == Axis::AncestorOrSelf {
+                    res.push(node);
+                }
+                if is_attr {
+                    res.push(NodeRef::new(id));
+                }
+                res.extend(tree.ancestors(id).map(NodeRef::new));
+                res
+            }
+            Axis::FollowingSibling => tree.following_siblings(id).map(NodeRef::new).collect(),
+            Axis::PrecedingSibling => tree.preceding_siblings(id).map(NodeRef::new).collect(),
+            Axis::Following => {
+                let mut res = Vec::new();
+                if is_attr {
+                    res.extend(tree.descendants(id).map(NodeRef::new));
+                }
+                res.extend(tree.following(id).map(NodeRef::new));
+                res
+            }
+            Axis::Preceding => {
+                let mut res: Vec<NodeRef> = tree.preceding(id).map(NodeRef::new).collect();
+                res.reverse();
+                res
+            }
+            Axis::Attribute => {
+                let count = match tree.get(id).get_tag().and_then(|tag| tag.get_attrs()) {
+                    Some(attrs) => attrs.len(),
+                    None => 0,
+                };
+                (0..count).map(|i| NodeRef { id, attr: Some(i) }).collect()
+            }
+        }
+    }
+
+    fn node_test(&self, node: NodeRef, axis: Axis, test: &NodeTest) -> bool {
+        if let Some(i) = node.attr {
+            // The principal node type of the attribute axis is attribute
+            // and of the other axes is element.
+            return match test {
+                NodeTest::Node => true,
+                NodeTest::Any => axis == Axis::Attribute,
+                NodeTest::Name(name) => {
+                    axis == Axis::Attribute && name.eq_ignore_ascii_case(self.attr_name(node.id, i))
+                }
+                NodeTest::Text | NodeTest::Comment | NodeTest::ProcessingInstruction(_) => false,
+            };
+        }
+
+        let dom = self.tree.get(node.id);
+        match test {
+            NodeTest::Node => true,
+            // A CDATA section is a part of the text in the XPath data model
+            NodeTest::Text => matches!(dom.dom_type, DomType::Text | DomType::CData),
+            NodeTest::Comment => dom.dom_type == DomType::Comment,
+            NodeTest::ProcessingInstruction(target) => match dom.get_processing_instruction() {
+                Some(pi) => target
+                    .as_ref()
+                    .is_none_or(|target| target == pi.get_target()),
+                None => false,
+            },
+            NodeTest::Any | NodeTest::Name(_) if axis == Axis::Attribute || dom.is_root() => false,
+            NodeTest::Any => dom.dom_type == DomType::Tag,
+            NodeTest::Name(name) => match dom.get_tag() {
+                Some(tag) => name.eq_ignore_ascii_case(tag.get_name()),
+                None => false,
+            },
+        }
+    }
+
+    fn attr_name(&self, id: NodeId, i: usize) -> &'a str {
+        let attrs = self.tree.get(id).get_tag().unwrap().get_attrs().unwrap();
+        attrs.iter().nth(i).unwrap().0
+    }
+
+    /// Returns the name of the node. Empty for texts, comments and the root.
+    fn node_name(&self, node: NodeRef) -> &'a str {
+        match node.attr {
+            Some(i) => self.attr_name(node.id, i),
+            None => {
+                let dom = self.tree.get(node.id);
+                match dom.get_tag() {
+                    Some(tag) if !dom.is_root() => tag.get_name(),
+                    _ => "",
+                }
+            }
+        }
+    }
+
+    fn string_value(&self, node: NodeRef) -> String {
+        self.to_xpath_node(node).to_string()
+    }
+
+    fn to_string(&self, value: &Value) -> String {
+        match value {
+            Value::Nodes(nodes) => match nodes.first() {
+                Some(node) => self.string_value(*node),
+                None => String::new(),
+            },
+            Value::String(s) => s.clone(),
+            Value::Number(n) => number_to_string(*n),
+            Value::Boolean(b) => b.to_string(),
+        }
+    }
+
+    fn to_number(&self, value: &Value) -> f64 {
+        match value {
+            Value::Number(n) => *n,
+            Value::Boolean(b) => {
+                if *b {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            _ => string_to_number(&self.to_string(value)),
+        }
+    }
+
+    fn to_boolean(&self, value: &Value) -> bool {
+        match value {
+            Value::Nodes(nodes) => !nodes.is_empty(),
+            Value::String(s) => !s.is_empty(),
+            Value::Number(n) => *n != 0.0 && !n.is_nan(),
+            Value::Boolean(b) => *b,
+        }
+    }
+
+    fn eval_binary(
+        &self,
+        op: BinaryOp,
+        l: &Expr,
+        r: &Expr,
+        ctx: &Context,
+    ) -> Result<Value, XPathError> {
+        match op {
+            BinaryOp::Or => {
+                let b =
+                    self.to_boolean(&self.eval(l, ctx)?) || self.to_boolean(&self.eval(r, ctx)?);
+                Ok(Value::Boolean(b))
+            }
+            BinaryOp::And => {
+                let b =
+                    self.to_boolean(&self.eval(l, ctx)?) && self.to_boolean(&self.eval(r, ctx)?);
+                Ok(Value::Boolean(b))
+            }
+            BinaryOp::Eq
+            | BinaryOp::Ne
+            | BinaryOp::Lt
+            | BinaryOp::Le
+            | BinaryOp::Gt
+            | BinaryOp::Ge => {
+                let l = self.eval(l, ctx)?;
+                let r = self.eval(r, ctx)?;
+                Ok(Value::Boolean(self.compare(op, &l, &r)))
+            }
+            _ => {
+                let l = self.to_number(&self.eval(l, ctx)?);
+                let r = self.to_number(&self.eval(r, ctx)?);
+                Ok(Value::Number(match op {
+                    BinaryOp::Add => l + r,
+                    BinaryOp::Sub => l - r,
+                    BinaryOp::Mul => l * r,
+                    BinaryOp::Div => l / r,
+                    _ => l % r,
+                }))
+            }
+        }
+    }
+
+    /// Compares the values. If a value is a node-set,
+    /// the comparison is true if it is true for any node in it.
+    fn compare(&self, op: BinaryOp, l: &Value, r: &Value) -> bool {
+        match (l, r) {
+            (Value::Nodes(ls), Value::Nodes(rs)) => ls.iter().any(|ln| {
+                let ls = Value::String(self.string_value(*ln));
+                rs.iter()
+                    .any(|rn| self.compare_atomic(op, &ls, &Value::String(self.string_value(*rn))))
+            }),
+            (Value::Nodes(_), Value::Boolean(_)) | (Value::Boolean(_), Value::Nodes(_)) => {
+                let l = Value::Boolean(self.to_boolean(l));
+                let r = Value::Boolean(self.to_boolean(r));
+                self.compare_atomic(op, &l, &r)
+            }
+            (Value::Nodes(ls), _) => ls
+                .iter()
+                .any(|n| self.compare_atomic(op, &Value::String(self.string_value(*n)), r)),
+            (_, Value::Nodes(rs)) => rs
+                .iter()
+                .any(|n| self.compare_atomic(op, l, &Value::String(self.string_value(*n)))),
+            _ => self.compare_atomic(op, l, r),
+        }
+    }
+
+    fn compare_atomic(&self, op: BinaryOp, l: &Value, r: &Value) -> bool {
+        match op {
+            BinaryOp::Eq | BinaryOp::Ne => {
+                let eq = match (l, r) {
+                    (Value::Boolean(_), _) | (_, Value::Boolean(_)) => {
+                        self.to_boolean(l) == self.to_boolean(r)
+                    }
+                    (Value::Number(_), _) | (_, Value::Number(_)) => {
+                        self.to_number(l) == self.to_number(r)
+                    }
+                    _ => self.to_string(l) == self.to_string(r),
+                };
+                if op == BinaryOp::Eq {
+                    eq
+                } else {
+                    !eq
+                }
+            }
+            _ => {
+                let l = self.to_number(l);
+                let r = self.to_number(r);
+                match op {
+                    BinaryOp::Lt => l < r,
+                    BinaryOp::Le => l <= r,
+                    BinaryOp::Gt => l > r,
+                    _ => l >= r,
+                }
+            }
+        }
+    }
+
+    /// Returns the string of the argument, or of the context node if there is no argument.
+    fn string_arg(&self, args: &[Expr], ctx: &Context) -> Result<String, XPathError> {
+        match args.first() {
+            Some(arg) => Ok(self.to_string(&self.eval(arg, ctx)?)),
+            None => Ok(self.string_value(ctx.node)),
+        }
+    }
+
+    fn eval_function(
+        &self,
+        name: &str,
+        args: &[Expr],
+        offset: usize,
+        ctx: &Context,
+    ) -> Result<Value, XPathError> {
+        let string = |i: usize| -> Result<String, XPathError> {
+            Ok(self.to_string(&self.eval(&args[i], ctx)?))
+        };
+        let number = |i: usize| -> Result<f64, XPathError> {
+            Ok(self.to_number(&self.eval(&args[i], ctx)?))
+        };
+
+        let value = match name {
+            "last" => Value::Number(ctx.size as f64),
+            "position" => Value::Number(ctx.position as f64),
+            "count" => Value::Number(self.eval_nodes(&args[0], ctx, offset)?.len() as f64),
+            "name" | "local-name" => {
+                let node = match args.first() {
+                    Some(arg) => self.eval_nodes(arg, ctx, offset)?.first().copied(),
+                    None => Some(ctx.node),
+                };
+                let node_name = node.map_or("", |node| self.node_name(node));
+                if name == "local-name" {
+                    let local = node_name.rsplit(':').next().unwrap_or("");
+                    Value::String(String::from(local))
+                } else {
+                    Value::String(String::from(node_name))
+                }
+            }
+            "string" => Value::String(self.string_arg(args, ctx)?),
+            "concat" => {
+                let mut s = String::new();
+                for i in 0..args.len() {
+                    s.push_str(&string(i)?);
+                }
+                Value::String(s)
+            }
+            "starts-with" => Value::Boolean(string(0)?.starts_with(&string(1)?)),
+            "contains" => Value::Boolean(string(0)?.contains(&string(1)?)),
+            "substring-before" => {
+                let s = string(0)?;
+                let before = match s.find(&string(1)?) {
+                    Some(i) => &s[..i],
+                    None => "",
+                };
+                Value::String(String::from(before))
+            }
+            "substring-after" => {
+                let s = string(0)?;
+                let needle = string(1)?;
+                let after = match s.find(&needle) {
+                    Some(i) => &s[i + needle.len()..],
+                    None => "",
+                };
+                Value::String(String::from(after))
+            }
+            "substring" => {
+                let s = string(0)?;
+                let start = round(number(1)?);
+                let end = match args.len() {
+                    3 => start + round(number(2)?),
+                    _ => f64::INFINITY,
+                };
+                // The position of the first character is 1
+                let sub: String = s
+                    .chars()
+                    .enumerate()
+                    .filter(|(i, _)| {
+                        let p = (*i + 1) as f64;
+                        p >= start && p < end
+                    })
+                    .map(|(_, c)| c)
+                    .collect();
+                Value::String(sub)
+            }
+            "string-length" => Value::Number(self.string_arg(args, ctx)?.chars().count() as f64),
+            "normalize-space" => {
+                let s = self.string_arg(args, ctx)?;
+                Value::String(s.split_whitespace().collect::<Vec<&str>>().join(" "))
+            }
+            "translate" => {
+                let from: Vec<char> = string(1)?.chars().collect();
+                let to: Vec<char> = string(2)?.chars().collect();
+                let s = string(0)?
+                    .chars()
+                    .filter_map(|c| match from.iter().position(|f| *f == c) {
+                        Some(i) => to.get(i).copied(),
+                        None => Some(c),
+                    })
+                    .collect();
+                Value::String(s)
+            }
+            "boolean" => Value::Boolean(self.to_boolean(&self.eval(&args[0], ctx)?)),
+            "not" => Value::Boolean(!self.to_boolean(&self.eval(&args[0], ctx)?)),
+            "true" => Value::Boolean(true),
+            "false" => Value::Boolean(false),
+            "number" => match args.first() {
+                Some(_) => Value::Number(number(0)?),
+                None => Value::Number(string_to_number(&self.string_value(ctx.node))),
+            },
+            "sum" => {
+                let nodes = self.eval_nodes(&args[0], ctx, offset)?;
+                let sum = nodes
+                    .iter()
+                    .map(|n| string_to_number(&self.string_value(*n)))
+                    .sum();
+                Value::Number(sum)
+            }
+            "floor" => Value::Number(number(0)?.floor()),
+            "ceiling" => Value::Number(number(0)?.ceil()),
+            _ => Value::Number(round(number(0)?)),
+        };
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn number_test() {
+        assert_eq!(number_to_string(3.0), "3");
+        assert_eq!(number_to_string(-0.0), "0");
+        assert_eq!(number_to_string(0.5), "0.5");
+        assert_eq!(number_to_string(f64::NAN), "NaN");
+        assert_eq!(number_to_string(-f64::INFINITY), "-Infinity");
+
+        assert_eq!(string_to_number(" 12.5 "), 12.5);
+        assert_eq!(string_to_number("-.5"), -0.5);
+        assert!(string_to_number("1e3").is_nan());
+        assert!(string_to_number("").is_nan());
+        assert!(string_to_number("inf").is_nan());
+
+        assert_eq!(round(2.5), 3.0);
+        assert_eq!(round(-2.5), -2.0);
+        assert!(round(-0.3).is_sign_negative());
+    }
+}
@@ -0,0 +1,228 @@
+//! Module for evaluating XPath 1.0 expressions over the Dom structure tree.
+
+mod error;
+mod eval;
+mod parser;
+
+pub use error::XPathError;
+
+use std::fmt;
+
+use crate::dom::Dom;
+use crate::dom::DomTree;
+use crate::dom::DomType;
+
+/// A parsed XPath 1.0 expression.
+///
+/// Supported:
+/// * location paths with abbreviations (`/`, `//`, `.`, `..`, `@`)
+/// * axes: `child`, `descendant`, `descendant-or-self`, `parent`, `ancestor`, `ancestor-or-self`,
+///   `following-sibling`, `preceding-sibling`, `following`, `preceding`, `attribute`, `self`
+/// * node tests: name, `*`, `node()`, `text()`, `comment()`
+/// * predicates with positions (`li[2]`, `li[last()]`, `li[position() > 1]`)
+/// * operators: `or`, `and`, `=`, `!=`, `<`, `<=`, `>`, `>=`, `+`, `-`, `*`, `div`, `mod`, `|`
+/// * the core functions except `id()`, `lang()` and `namespace-uri()`
+///
+/// Variables are not supported.
+/// Tag names and attribute names are compared case-insensitively.
+///
+/// # Examples
+/// ```rust
+/// use parsercher::XPath;
+/// use parsercher::XPathValue;
+///
+/// let html = r#"<ol><li class="a">first</li><li>second</li></ol>"#;
+/// let dom = parsercher::parse(&html).unwrap();
+///
+/// let xpath = XPath::parse("count(//li[@class])").unwrap();
+/// assert_eq!(xpath.evaluate(&dom).unwrap(), XPathValue::Number(1.0));
+/// ```
+#[derive(Debug, Clone)]
+pub struct XPath {
+    expr: Expr,
+    src: String,
+}
+
+/// The result of an XPath expression.
+#[derive(Debug, PartialEq, Clone)]
+pub enum XPathValue<'a> {
+    /// Nodes in document order.
+    Nodes(Vec<XPathNode<'a>>),
+    String(String),
+    Number(f64),
+    Boolean(bool),
+}
+
+/// A node selected by an XPath expression.
+#[derive(Debug, PartialEq, Clone)]
+pub enum XPathNode<'a> {
+    /// Tag, text or comment. The root dom for `/`.
+    Dom(&'a Dom),
+    /// Attribute of the `element`.
+    Attr {
+        element: &'a Dom,
+        name: &'a str,
+        value: &'a str,
+    },
+}
+
+impl XPath {
+    /// Parse the XPath expression.
+    ///
+    /// # Errors
+    /// Returns `XPathError` if the expression is invalid or not supported.
+    pub fn parse(expr: &str) -> Result<XPath, XPathError> {
+        Ok(XPath {
+            expr: parser::parse(expr)?,
+            src: String::from(expr),
+        })
+    }
+
+    /// Evaluates the expression with `dom` as the context node.
+    /// `dom` is also the root node of absolute paths.
+    ///
+    /// # Errors
+    /// Returns `XPathError` if a node-set is required but the expression returns another type.
+    /// e.g. `count('a')`
+    pub fn evaluate<'a>(&self, dom: &'a Dom) -> Result<XPathValue<'a>, XPathError> {
+        let tree = DomTree::new(dom);
+        eval::evaluate(&tree, &self.expr, &self.src)
+    }
+}
+
+/// Writes the value converted by the XPath `string()` function.
+impl fmt::Display for XPathValue<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            XPathValue::Nodes(nodes) => match nodes.first() {
+                Some(node) => write!(f, "{}", node),
+                None => Ok(()),
+            },
+            XPathValue::String(s) => write!(f, "{}", s),
+            XPathValue::Number(n) => write!(f, "{}", eval::number_to_string(*n)),
+            XPathValue::Boolean(b) => write!(f, "{}", b),
+        }
+    }
+}
+
+/// Writes the string-value of the node.
+/// For a tag, the texts of all descendants are concatenated.
+impl fmt::Display for XPathNode<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            XPathNode::Dom(dom) => write_string_value(f, dom),
+            XPathNode::Attr { value, .. } => write!(f, "{}", value),
+        }
+    }
+}
+
+fn write_string_value(f: &mut fmt::Formatter<'_>, dom: &Dom) -> fmt::Result {
+    match dom.dom_type {
+        DomType::Text => write!(f, "{}", dom.get_text().unwrap().get_text()),
+        DomType::Comment => write!(f, "{}", dom.get_comment().unwrap().get_comment()),
+        DomType::Doctype => Ok(()),
+        DomType::CData => write!(f, "{}", dom.get_cdata().unwrap().get_data()),
+        DomType::ProcessingInstruction => {
+            write!(
+                f,
+                "{}",
+                dom.get_processing_instruction().unwrap().get_data()
+            )
+        }
+        DomType::Tag => {
+            if let Some(children) = dom.get_children() {
+                for child in children.iter() {
+                    if !matches!(
+                        child.dom_type,
+                        DomType::Comment | DomType::ProcessingInstruction
+                    ) {
+                        write_string_value(f, child)?;
+                    }
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    Negate(Box<Expr>),
+    /// `a | b` and the offset of `|`.
+    Union(Box<Expr>, Box<Expr>, usize),
+    Path(Path),
+    /// Primary expression with predicates and a relative path.
+    /// e.g. `(//li)[1]/text()`
+    Filter {
+        primary: Box<Expr>,
+        predicates: Vec<Expr>,
+        steps: Vec<Step>,
+        offset: usize,
+    },
+    Literal(String),
+    Number(f64),
+    /// Function name, arguments and the offset of the name.
+    Function(String, Vec<Expr>, usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BinaryOp {
+    Or,
+    And,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+}
+
+#[derive(Debug, Clone)]
+struct Path {
+    absolute: bool,
+    steps: Vec<Step>,
+}
+
+#[derive(Debug, Clone)]
+struct Step {
+    axis: Axis,
+    test: NodeTest,
+    predicates: Vec<Expr>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Axis {
+    Child,
+    Descendant,
+    DescendantOrSelf,
+    Parent,
+    Ancestor,
+    AncestorOrSelf,
+    FollowingSibling,
+    PrecedingSibling,
+    Following,
+    Preceding,
+    Attribute,
+    SelfNode,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum NodeTest {
+    Name(String),
+    /// `*`
+    Any,
+    /// `node()`
+    Node,
+    /// `text()`
+    Text,
+    /// `comment()`
+    Comment,
+    /// `processing-instruction()` and `processing-instruction('target')`
+    ProcessingInstruction(Option<String>),
+}
@@ -0,0 +1,609 @@
+//! Module for parsing XPath expressions.
+
+use super::Axis;
+use super::BinaryOp;
+use super::Expr;
+use super::NodeTest;
+use super::Path;
+use super::Step;
+use super::XPathError;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Slash,
+    DoubleSlash,
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    Dot,
+    DoubleDot,
+    At,
+    Comma,
+    DoubleColon,
+    Pipe,
+    Plus,
+    Minus,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Star,
+    Dollar,
+    Literal(String),
+    Number(f64),
+    Name(String),
+}
+
+/// Functions and their minimum and maximum number of arguments.
+const FUNCTIONS: [(&str, usize, usize); 24] = [
+    ("last", 0, 0),
+    ("position", 0, 0),
+    ("count", 1, 1),
+    ("local-name", 0, 1),
+    ("name", 0, 1),
+    ("string", 0, 1),
+    ("concat", 2, usize::MAX),
+    ("starts-with", 2, 2),
+    ("contains", 2, 2),
+    ("substring-before", 2, 2),
+    ("substring-after", 2, 2),
+    ("substring", 2, 3),
+    ("string-length", 0, 1),
+    ("normalize-space", 0, 1),
+    ("translate", 3, 3),
+    ("boolean", 1, 1),
+    ("not", 1, 1),
+    ("true", 0, 0),
+    ("false", 0, 0),
+    ("number", 0, 1),
+    ("sum", 1, 1),
+    ("floor", 1, 1),
+    ("ceiling", 1, 1),
+    ("round", 1, 1),
+];
+
+/// Names of the node type tests. They are not functions.
+const NODE_TYPES: [&str; 4] = ["text", "node", "comment", "processing-instruction"];
+
+/// Parse the XPath expression.
+pub fn parse(src: &str) -> Result<Expr, XPathError> {
+    let tokens = tokenize(src)?;
+    let mut parser = XPathParser {
+        src,
+        tokens,
+        cursor: 0,
+    };
+    let expr = parser.parse_expr()?;
+    if parser.peek().is_some() {
+        return Err(parser.error("Unexpected token"));
+    }
+    Ok(expr)
+}
+
+fn tokenize(src: &str) -> Result<Vec<(Token, usize)>, XPathError> {
+    let chars: Vec<(usize, char)> = src.char_indices().collect();
+    let char_at = |i: usize| chars.get(i).map(|(_, c)| *c);
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while let Some(c) = char_at(i) {
+        let offset = chars[i].0;
+        let next = char_at(i + 1);
+        let (token, len) = match c {
+            ' ' | '\t' | '\r' | '\n' => {
+                i += 1;
+                continue;
+            }
+            '/' if next == Some('/') => (Token::DoubleSlash, 2),
+            '/' => (Token::Slash, 1),
+            '(' => (Token::LParen, 1),
+            ')' => (Token::RParen, 1),
+            '[' => (Token::LBracket, 1),
+            ']' => (Token::RBracket, 1),
+            '.' if next == Some('.') => (Token::DoubleDot, 2),
+            '.' if !next.is_some_and(|c| c.is_ascii_digit()) => (Token::Dot, 1),
+            '@' => (Token::At, 1),
+            ',' => (Token::Comma, 1),
+            ':' if next == Some(':') => (Token::DoubleColon, 2),
+            '|' => (Token::Pipe, 1),
+            '+' => (Token::Plus, 1),
+            '-' => (Token::Minus, 1),
+            '=' => (Token::Eq, 1),
+            '!' if next == Some('=') => (Token::Ne, 2),
+            '<' if next == Some('=') => (Token::Le, 2),
+            '<' => (Token::Lt, 1),
+            '>' if next == Some('=') => (Token::Ge, 2),
+            '>' => (Token::Gt, 1),
+            '*' => (Token::Star, 1),
+            '$' => (Token::Dollar, 1),
+            '"' | '\'' => {
+                let len = chars[i + 1..]
+                    .iter()
+                    .position(|(_, q)| *q == c)
+                    .ok_or_else(|| XPathError::new("Unterminated string literal", offset, src))?;
+                let s: String = chars[i + 1..i + 1 + len].iter().map(|(_, c)| c).collect();
+                (Token::Literal(s), len + 2)
+            }
+            c if c.is_ascii_digit() || c == '.' => {
+                let len = chars[i..]
+                    .iter()
+                    .take_while(|(_, c)| c.is_ascii_digit() || *c == '.')
+                    .count();
+                let s: String = chars[i..i + len].iter().map(|(_, c)| c).collect();
+                match s.parse() {
+                    Ok(n) => (Token::Number(n), len),
+                    Err(_) => return Err(XPathError::new("Invalid number", offset, src)),
+                }
+            }
+            c if is_name_start_char(c) => {
+                let mut len = chars[i..]
+                    .iter()
+                    .take_while(|(_, c)| is_name_char(*c))
+                    .count();
+                // prefixed name (e.g. `svg:rect`)
+                if char_at(i + len) == Some(':')
+                    && char_at(i + len + 1).is_some_and(is_name_start_char)
+                {
+                    len += 1;
+                    len += chars[i + len..]
+                        .iter()
+                        .take_while(|(_, c)| is_name_char(*c))
+                        .count();
+                }
+                let s: String = chars[i..i + len].iter().map(|(_, c)| c).collect();
+                (Token::Name(s), len)
+            }
+            _ => {
+                return Err(XPathError::new(
+                    &format!("Unexpected character '{}'", c),
+                    offset,
+                    src,
+                ))
+            }
+        };
+        tokens.push((token, offset));
+        i += len;
+    }
+    Ok(tokens)
+}
+
+fn is_name_start_char(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_' || !c.is_ascii()
+}
+
+fn is_name_char(c: char) -> bool {
+    is_name_start_char(c) || c.is_ascii_digit() || c == '-' || c == '.'
+}
+
+struct XPathParser<'s> {
+    src: &'s str,
+    tokens: Vec<(Token, usize)>,
+    cursor: usize,
+}
+
+impl<'s> XPathParser<'s> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.cursor).map(|(t, _)| t)
+    }
+
+    fn peek_at(&self, n: usize) -> Option<&Token> {
+        self.tokens.get(self.cursor + n).map(|(t, _)| t)
+    }
+
+    fn next(&mut self) {
+        self.cursor += 1;
+    }
+
+    /// Returns true and moves the cursor to the next token if the current token is `token`.
+    fn eat(&mut self, token: &Token) -> bool {
+        if self.peek() == Some(token) {
+            self.next();
+            return true;
+        }
+        false
+    }
+
+    fn expect(&mut self, token: &Token, s: &str) -> Result<(), XPathError> {
+        if self.eat(token) {
+            return Ok(());
+        }
+        Err(self.error(&format!("Expected '{}'", s)))
+    }
+
+    /// Returns true and moves the cursor if the current token is the operator name.
+    fn eat_operator_name(&mut self, name: &str) -> bool {
+        if let Some(Token::Name(n)) = self.peek() {
+            if n == name {
+                self.next();
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Byte offset of the current token.
+    fn offset(&self) -> usize {
+        match self.tokens.get(self.cursor) {
+            Some((_, offset)) => *offset,
+            None => self.src.len(),
+        }
+    }
+
+    fn error(&self, message: &str) -> XPathError {
+        XPathError::new(message, self.offset(), self.src)
+    }
+
+    fn parse_expr(&mut self) -> Result<Expr, XPathError> {
+        let mut expr = self.parse_and()?;
+        while self.eat_operator_name("or") {
+            expr = Expr::Binary(BinaryOp::Or, Box::new(expr), Box::new(self.parse_and()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, XPathError> {
+        let mut expr = self.parse_equality()?;
+        while self.eat_operator_name("and") {
+            expr = Expr::Binary(
+                BinaryOp::And,
+                Box::new(expr),
+                Box::new(self.parse_equality()?),
+            );
+        }
+        Ok(expr)
+    }
+
+    fn parse_equality(&mut self) -> Result<Expr, XPathError> {
+        let mut expr = self.parse_relational()?;
+        loop {
+            let op = match self.peek() {
+                Some(Token::Eq) => BinaryOp::Eq,
+                Some(Token::Ne) => BinaryOp::Ne,
+                _ => break,
+            };
+            self.next();
+            expr = Expr::Binary(op, Box::new(expr), Box::new(self.parse_relational()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_relational(&mut self) -> Result<Expr, XPathError> {
+        let mut expr = self.parse_additive()?;
+        loop {
+            let op = match self.peek() {
+                Some(Token::Lt) => BinaryOp::Lt,
+                Some(Token::Le) => BinaryOp::Le,
+                Some(Token::Gt) => BinaryOp::Gt,
+                Some(Token::Ge) => BinaryOp::Ge,
+                _ => break,
+            };
+            self.next();
+            expr = Expr::Binary(op, Box::new(expr), Box::new(self.parse_additive()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_additive(&mut self) -> Result<Expr, XPathError> {
+        let mut expr = self.parse_multiplicative()?;
+        loop {
+            let op = match self.peek() {
+                Some(Token::Plus) => BinaryOp::Add,
+                Some(Token::Minus) => BinaryOp::Sub,
+                _ => break,
+            };
+            self.next();
+            expr = Expr::Binary(op, Box::new(expr), Box::new(self.parse_multiplicative()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_multiplicative(&mut self) -> Result<Expr, XPathError> {
+        let mut expr = self.parse_unary()?;
+        loop {
+            let op = if self.eat(&Token::Star) {
+                BinaryOp::Mul
+            } else if self.eat_operator_name("div") {
+                BinaryOp::Div
+            } else if self.eat_operator_name("mod") {
+                BinaryOp::Mod
+            } else {
+                break;
+            };
+            expr = Expr::Binary(op, Box::new(expr), Box::new(self.parse_unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, XPathError> {
+        if self.eat(&Token::Minus) {
+            return Ok(Expr::Negate(Box::new(self.parse_unary()?)));
+        }
+        self.parse_union()
+    }
+
+    fn parse_union(&mut self) -> Result<Expr, XPathError> {
+        let mut expr = self.parse_path()?;
+        loop {
+            let offset = self.offset();
+            if !self.eat(&Token::Pipe) {
+                break;
+            }
+            expr = Expr::Union(Box::new(expr), Box::new(self.parse_path()?), offset);
+        }
+        Ok(expr)
+    }
+
+    /// grammar: `LocationPath | FilterExpr [('/' | '//') RelativeLocationPath]`
+    fn parse_path(&mut self) -> Result<Expr, XPathError> {
+        match self.peek() {
+            Some(Token::Slash) => {
+                self.next();
+                let mut steps = Vec::new();
+                if self.is_step_start() {
+                    self.parse_relative_path(&mut steps)?;
+                }
+                Ok(Expr::Path(Path {
+                    absolute: true,
+                    steps,
+                }))
+            }
+            Some(Token::DoubleSlash) => {
+                self.next();
+                let mut steps = vec![descendant_or_self()];
+                self.parse_relative_path(&mut steps)?;
+                Ok(Expr::Path(Path {
+                    absolute: true,
+                    steps,
+                }))
+            }
+            _ if self.is_step_start() => {
+                let mut steps = Vec::new();
+                self.parse_relative_path(&mut steps)?;
+                Ok(Expr::Path(Path {
+                    absolute: false,
+                    steps,
+                }))
+            }
+            _ => self.parse_filter(),
+        }
+    }
+
+    /// Returns true if the current token starts a location step.
+    fn is_step_start(&self) -> bool {
+        match self.peek() {
+            Some(Token::Dot) | Some(Token::DoubleDot) | Some(Token::At) | Some(Token::Star) => true,
+            Some(Token::Name(name)) => {
+                self.peek_at(1) != Some(&Token::LParen) || NODE_TYPES.contains(&name.as_str())
+            }
+            _ => false,
+        }
+    }
+
+    /// grammar: `Step [('/' | '//') Step]*`
+    fn parse_relative_path(&mut self, steps: &mut Vec<Step>) -> Result<(), XPathError> {
+        loop {
+            steps.push(self.parse_step()?);
+            if self.eat(&Token::Slash) {
+                continue;
+            }
+            if self.eat(&Token::DoubleSlash) {
+                steps.push(descendant_or_self());
+                continue;
+            }
+            return Ok(());
+        }
+    }
+
+    /// grammar: `[axis:: | @] NodeTest Predicate* | . | ..`
+    fn parse_step(&mut self) -> Result<Step, XPathError> {
+        if self.eat(&Token::Dot) {
+            return Ok(step(Axis::SelfNode, NodeTest::Node));
+        }
+        if self.eat(&Token::DoubleDot) {
+            return Ok(step(Axis::Parent, NodeTest::Node));
+        }
+
+        let mut axis = Axis::Child;
+        if self.eat(&Token::At) {
+            axis = Axis::Attribute;
+        } else if let (Some(Token::Name(name)), Some(Token::DoubleColon)) =
+            (self.peek(), self.peek_at(1))
+        {
+            axis = match name.as_str() {
+                "child" => Axis::Child,
+                "descendant" => Axis::Descendant,
+                "descendant-or-self" => Axis::DescendantOrSelf,
+                "parent" => Axis::Parent,
+                "ancestor" => Axis::Ancestor,
+                "ancestor-or-self" => Axis::AncestorOrSelf,
+                "following-sibling" => Axis::FollowingSibling,
+                "preceding-sibling" => Axis::PrecedingSibling,
+                "following" => Axis::Following,
+                "preceding" => Axis::Preceding,
+                "attribute" => Axis::Attribute,
+                "self" => Axis::SelfNode,
+                _ => return Err(self.error(&format!("Unsupported axis '{}'", name))),
+            };
+            self.next();
+            self.next();
+        }
+
+        let test = match self.peek().cloned() {
+            Some(Token::Star) => {
+                self.next();
+                NodeTest::Any
+            }
+            Some(Token::Name(name)) => {
+                self.next();
+                if self.peek() == Some(&Token::LParen) && NODE_TYPES.contains(&name.as_str()) {
+                    self.next();
+                    let test = match name.as_str() {
+                        "text" => NodeTest::Text,
+                        "comment" => NodeTest::Comment,
+                        "processing-instruction" => match self.peek().cloned() {
+                            Some(Token::Literal(target)) => {
+                                self.next();
+                                NodeTest::ProcessingInstruction(Some(target))
+                            }
+                            _ => NodeTest::ProcessingInstruction(None),
+                        },
+                        _ => NodeTest::Node,
+                    };
+                    self.expect(&Token::RParen, ")")?;
+                    test
+                } else {
+                    NodeTest::Name(name)
+                }
+            }
+            _ => return Err(self.error("Expected a node test")),
+        };
+
+        let mut step = step(axis, test);
+        step.predicates = self.parse_predicates()?;
+        Ok(step)
+    }
+
+    fn parse_predicates(&mut self) -> Result<Vec<Expr>, XPathError> {
+        let mut predicates = Vec::new();
+        while self.eat(&Token::LBracket) {
+            predicates.push(self.parse_expr()?);
+            self.expect(&Token::RBracket, "]")?;
+        }
+        Ok(predicates)
+    }
+
+    /// grammar: `PrimaryExpr Predicate* [('/' | '//') RelativeLocationPath]`
+    fn parse_filter(&mut self) -> Result<Expr, XPathError> {
+        let offset = self.offset();
+        let primary = self.parse_primary()?;
+        let predicates = self.parse_predicates()?;
+        let mut steps = Vec::new();
+        if self.eat(&Token::Slash) {
+            self.parse_relative_path(&mut steps)?;
+        } else if self.eat(&Token::DoubleSlash) {
+            steps.push(descendant_or_self());
+            self.parse_relative_path(&mut steps)?;
+        }
+
+        if predicates.is_empty() && steps.is_empty() {
+            return Ok(primary);
+        }
+        Ok(Expr::Filter {
+            primary: Box::new(primary),
+            predicates,
+            steps,
+            offset,
+        })
+    }
+
+    /// grammar: `( Expr ) | Literal | Number | FunctionCall`
+    fn parse_primary(&mut self) -> Result<Expr, XPathError> {
+        let offset = self.offset();
+        match self.peek().cloned() {
+            Some(Token::LParen) => {
+                self.next();
+                let expr = self.parse_expr()?;
+                self.expect(&Token::RParen, ")")?;
+                Ok(expr)
+            }
+            Some(Token::Literal(s)) => {
+                self.next();
+                Ok(Expr::Literal(s))
+            }
+            Some(Token::Number(n)) => {
+                self.next();
+                Ok(Expr::Number(n))
+            }
+            Some(Token::Name(name)) if self.peek_at(1) == Some(&Token::LParen) => {
+                let (min, max) = match FUNCTIONS.iter().find(|(n, _, _)| *n == name) {
+                    Some((_, min, max)) => (*min, *max),
+                    None => return Err(self.error(&format!("Unsupported function '{}'", name))),
+                };
+                self.next();
+                self.next();
+                let mut args = Vec::new();
+                if !self.eat(&Token::RParen) {
+                    loop {
+                        args.push(self.parse_expr()?);
+                        if self.eat(&Token::RParen) {
+                            break;
+                        }
+                        self.expect(&Token::Comma, ",")?;
+                    }
+                }
+                if args.len() < min || args.len() > max {
+                    return Err(XPathError::new(
+                        &format!("Wrong number of arguments to {}()", name),
+                        offset,
+                        self.src,
+                    ));
+                }
+                Ok(Expr::Function(name, args, offset))
+            }
+            Some(Token::Dollar) => Err(self.error("Variables are not supported")),
+            _ => Err(self.error("Expected an expression")),
+        }
+    }
+}
+
+fn step(axis: Axis, test: NodeTest) -> Step {
+    Step {
+        axis,
+        test,
+        predicates: Vec::new(),
+    }
+}
+
+/// The step of `//`.
+fn descendant_or_self() -> Step {
+    step(Axis::DescendantOrSelf, NodeTest::Node)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokenize_test() {
+        let tokens: Vec<Token> = tokenize("//li[@class='a' and 1.5 != .5]/..")
+            .unwrap()
+            .into_iter()
+            .map(|(t, _)| t)
+            .collect();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::DoubleSlash,
+                Token::Name("li".to_string()),
+                Token::LBracket,
+                Token::At,
+                Token::Name("class".to_string()),
+                Token::Eq,
+                Token::Literal("a".to_string()),
+                Token::Name("and".to_string()),
+                Token::Number(1.5),
+                Token::Ne,
+                Token::Number(0.5),
+                Token::RBracket,
+                Token::Slash,
+                Token::DoubleDot,
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_error_test() {
+        let e = parse("//li[").unwrap_err();
+        assert_eq!(e.offset(), 5);
+        assert_eq!(e.to_string(), "Expected an expression at offset 5: `//li[`");
+
+        assert_eq!(parse("foo(1)").unwrap_err().offset(), 0);
+        assert_eq!(parse("count()").unwrap_err().offset(), 0);
+        assert_eq!(parse("//a/b::c").unwrap_err().offset(), 4);
+        assert!(parse("'abc").is_err());
+        assert!(parse("$var").is_err());
+        assert!(parse("//li]").is_err());
+    }
+}
@@ -0,0 +1,68 @@
+extern crate parsercher;
+
+use parsercher::dom::{Dom, DomType, Tag, Text};
+
+#[test]
+fn edit() {
+    let html = r#"<body><ul><li><a href="/1" onclick="track()">1</a></li><li>2</li></ul></body>"#;
+    let mut root_dom = parsercher::parse(html).unwrap();
+    let body = root_dom.get_children_mut().unwrap().first_mut().unwrap();
+    let ul = body.get_children_mut().unwrap().first_mut().unwrap();
+
+    // rename and edit attributes
+    let li = ul.get_children_mut().unwrap().first_mut().unwrap();
+    let a = li.get_children_mut().unwrap().first_mut().unwrap();
+    let tag = a.get_tag_mut().unwrap();
+    tag.set_name("A");
+    tag.remove_attr("onclick");
+    assert_eq!(tag.remove_attr("onclick"), None);
+
+    // insert
+    let mut li = Dom::new(DomType::Tag);
+    li.set_tag(Tag::new("li"));
+    let mut text = Dom::new(DomType::Text);
+    text.set_text(Text::new("0"));
+    li.add_child(text);
+    ul.insert_child(0, li);
+    assert_eq!(
+        ul.to_html(),
+        r#"<ul><li>0</li><li><A href="/1">1</A></li><li>2</li></ul>"#
+    );
+
+    // unwrap and remove
+    let removed = ul.unwrap_child(1);
+    assert_eq!(removed.to_html(), "<li></li>");
+    let removed = ul.remove_child(2);
+    assert_eq!(removed.to_html(), "<li>2</li>");
+    assert_eq!(ul.to_html(), r#"<ul><li>0</li><A href="/1">1</A></ul>"#);
+
+    // replace
+    let mut ol = Dom::new(DomType::Tag);
+    ol.set_tag(Tag::new("ol"));
+    let ul = ul.replace_with(ol);
+    assert_eq!(ul.get_children().unwrap().len(), 2);
+
+    // retain
+    body.retain_children(|dom| dom.dom_type != DomType::Tag);
+    assert_eq!(body.get_children(), None);
+    assert_eq!(root_dom.to_html(), "<body></body>");
+}
+
+#[test]
+fn edit_children_mut_to_empty() {
+    let mut root_dom = parsercher::parse("<p><b>1</b><i>2</i></p>").unwrap();
+    let p = root_dom.get_children_mut().unwrap().first_mut().unwrap();
+    p.get_children_mut().unwrap().clear();
+
+    assert_eq!(p.get_children(), None);
+    assert_eq!(p.get_children_mut().map(|children| children.len()), Some(0));
+    assert_eq!(p.to_html(), "<p></p>");
+    assert_eq!(root_dom, parsercher::parse("<p></p>").unwrap());
+
+    let p = root_dom.get_children_mut().unwrap().first_mut().unwrap();
+    let mut b = parsercher::parse("<b>1</b>").unwrap();
+    p.get_children_mut()
+        .unwrap()
+        .push(b.get_children_mut().unwrap().remove(0));
+    assert_eq!(root_dom.to_html(), "<p><b>1</b></p>");
+}
@@ -0,0 +1,222 @@
+extern crate parsercher;
+
+use std::fs;
+use std::path::Path;
+
+use parsercher::dom::namespace;
+use parsercher::dom::Dom;
+use parsercher::dom::DomType;
+
+/// Writes the tree in the format of the html5lib-tests tree construction tests.
+fn dump(dom: &Dom, depth: usize, out: &mut Vec<String>) {
+    let indent = format!("| {}", "  ".repeat(depth));
+    match dom.dom_type {
+        DomType::Tag => {
+            let tag = dom.get_tag().unwrap();
+            let prefix = match tag.namespace_uri() {
+                Some(namespace::SVG) => "svg ",
+                Some(namespace::MATHML) => "math ",
+                _ => "",
+            };
+            out.push(format!("{}<{}{}>", indent, prefix, tag.get_name()));
+            if let Some(attrs) = tag.get_attrs() {
+                let mut attrs: Vec<(&str, &str)> = attrs.iter().collect();
+                attrs.sort();
+                for (name, value) in attrs.iter() {
+                    out.push(format!("{}  {}=\"{}\"", indent, name, value));
+                }
+            }
+            if let Some(children) = dom.get_children() {
+                for child in children.iter() {
+                    dump(child, depth + 1, out);
+                }
+            }
+        }
+        DomType::Text => {
+            out.push(format!(
+                "{}\"{}\"",
+                indent,
+                dom.get_text().unwrap().get_text()
+            ));
+        }
+        DomType::Comment => {
+            out.push(format!(
+                "{}<!-- {} -->",
+                indent,
+                dom.get_comment().unwrap().get_comment()
+            ));
+        }
+        DomType::Doctype => {
+            let doctype = dom.get_doctype().unwrap();
+            match (doctype.get_public_id(), doctype.get_system_id()) {
+                (None, None) => out.push(format!("{}<!DOCTYPE {}>", indent, doctype.get_name())),
+                (public_id, system_id) => out.push(format!(
+                    "{}<!DOCTYPE {} \"{}\" \"{}\">",
+                    indent,
+                    doctype.get_name(),
+                    public_id.unwrap_or(""),
+                    system_id.unwrap_or("")
+                )),
+            }
+        }
+        DomType::CData | DomType::ProcessingInstruction => {
+            unreachable!("parse_html5 does not create {:?}", dom.dom_type)
+        }
+    }
+}
+
+/// Cases of the .dat files that are known to fail, as (file name, `#data`).
+/// A case listed here that passes is reported, so that the list is kept up to date.
+const KNOWN_FAILURES: &[(&str, &str)] = &[];
+
+struct Case {
+    data: String,
+    document: String,
+    /// The context element of a `#document-fragment` case.
+    fragment: Option<String>,
+    /// `Some(true)` for a `#script-on` case, `Some(false)` for a `#script-off` case.
+    scripting: Option<bool>,
+}
+
+impl Case {
+    /// Returns the reason why the case is not run, or `None` if it is run.
+    fn skip_reason(&self) -> Option<&'static str> {
+        if self.fragment.is_some() {
+            return Some("parse_html5 does not parse fragments");
+        }
+        if self.scripting == Some(false) {
+            // parse_html5 treats scripting as enabled
+            return Some("parse_html5 does not disable scripting");
+        }
+        None
+    }
+}
+
+/// Section names of a test case.
+const SECTIONS: [&str; 7] = [
+    "#data",
+    "#errors",
+    "#new-errors",
+    "#document-fragment",
+    "#script-off",
+    "#script-on",
+    "#document",
+];
+
+/// Reads the test cases of the content of a .dat file.
+fn read_cases(content: &str) -> Vec<Case> {
+    let mut cases = Vec::new();
+    for test in content.split("\n\n#data\n") {
+        let test = test.strip_prefix("#data\n").unwrap_or(test);
+        let mut sections: Vec<(&str, Vec<&str>)> = vec![("#data", Vec::new())];
+        for line in test.split('\n') {
+            match SECTIONS.iter().find(|section| **section == line) {
+                Some(section) if *section != "#data" => sections.push((section, Vec::new())),
+                _ => sections.last_mut().unwrap().1.push(line),
+            }
+        }
+        let section = |name: &str| {
+            sections
+                .iter()
+                .find(|(section, _)| *section == name)
+                .map(|(_, lines)| lines.join("\n"))
+        };
+        cases.push(Case {
+            data: section("#data").unwrap(),
+            document: String::from(section("#document").unwrap().trim_end_matches('\n')),
+            fragment: section("#document-fragment"),
+            scripting: match (section("#script-on"), section("#script-off")) {
+                (Some(_), _) => Some(true),
+                (_, Some(_)) => Some(false),
+                _ => None,
+            },
+        });
+    }
+    cases
+}
+
+#[test]
+fn html5lib_tree_construction() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/html5");
+    let mut paths: Vec<_> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "dat"))
+        .collect();
+    paths.sort();
+    assert!(!paths.is_empty());
+
+    let mut failures = Vec::new();
+    let mut fixed = Vec::new();
+    let mut count = 0;
+    for path in paths.iter() {
+        let file_name = path.file_name().unwrap().to_str().unwrap();
+        for case in read_cases(&fs::read_to_string(path).unwrap()).iter() {
+            if case.skip_reason().is_some() {
+                continue;
+            }
+            count += 1;
+            let dom = parsercher::parse_html5(&case.data);
+            let mut lines = Vec::new();
+            for child in dom.get_children().unwrap().iter() {
+                dump(child, 0, &mut lines);
+            }
+            let actual = lines.join("\n");
+            let known = KNOWN_FAILURES.contains(&(file_name, case.data.as_str()));
+            if actual == case.document {
+                if known {
+                    fixed.push(format!("{}\n#data\n{}", path.display(), case.data));
+                }
+            } else if !known {
+                failures.push(format!(
+                    "{}\n#data\n{}\n#expected\n{}\n#actual\n{}",
+                    path.display(),
+                    case.data,
+                    case.document,
+                    actual
+                ));
+            }
+        }
+    }
+    assert!(
+        failures.is_empty(),
+        "{} of {} cases failed\n\n{}",
+        failures.len(),
+        count,
+        failures.join("\n\n")
+    );
+    assert!(
+        fixed.is_empty(),
+        "{} cases in KNOWN_FAILURES passed\n\n{}",
+        fixed.len(),
+        fixed.join("\n\n")
+    );
+}
+
+#[test]
+fn html5lib_sections() {
+    let cases = read_cases(
+        "#data\n<b>\n#errors\n(1,3): error\n#new-errors\n(1:4) error\n#document-fragment\ndiv\n#document\n| <b>\n\n\
+         #data\n<noscript>a\n#errors\n#script-off\n#document\n| <noscript>\n\n\
+         #data\n#a\n\n#errors\n#document\n| <html>\n",
+    );
+    assert_eq!(cases.len(), 3);
+    assert_eq!(cases[0].data, "<b>");
+    assert_eq!(cases[0].fragment.as_deref(), Some("div"));
+    assert_eq!(cases[0].document, "| <b>");
+    assert!(cases[0].skip_reason().is_some());
+    assert_eq!(cases[1].scripting, Some(false));
+    assert!(cases[1].skip_reason().is_some());
+    assert_eq!(cases[2].data, "#a\n");
+    assert_eq!(cases[2].document, "| <html>");
+    assert!(cases[2].skip_reason().is_none());
+}
+
+#[test]
+fn parse_html5_search() {
+    let dom = parsercher::parse_html5("<ul><li class=a>1<li class=b>2<li class=a>3</ul>");
+    let mut needle = parsercher::dom::Tag::new("li");
+    needle.set_attr("class", "a");
+    let texts = parsercher::search_text_from_tag_children(&dom, &needle).unwrap();
+    assert_eq!(texts, vec!["1", "3"]);
+}
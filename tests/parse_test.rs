@@ -1,6 +1,9 @@
 extern crate parsercher;
 
 use parsercher::dom::DomType;
+use parsercher::ParseError;
+use parsercher::ParseMode;
+use parsercher::ParseOptions;
 
 #[test]
 fn parse() {
@@ -30,7 +33,7 @@ fn parse() {
 </html>
 "#;
 
-    let root_dom = parsercher::parse(&html).unwrap();
+    let root_dom = parsercher::parse(html).unwrap();
 
     // root
     assert_eq!(DomType::Tag, root_dom.dom_type);
@@ -39,11 +42,11 @@ fn parse() {
     assert_eq!(None, tag.get_attrs());
 
     // doctype
-    let doctype_dom = root_dom.get_children().unwrap().get(0).unwrap().clone();
-    assert_eq!(DomType::Tag, doctype_dom.dom_type);
-    let tag = doctype_dom.get_tag().unwrap();
-    assert_eq!("!DOCTYPE".to_string(), tag.get_name());
-    assert_eq!(Some("".to_string()), tag.get_attr("html"));
+    let doctype_dom = root_dom.get_children().unwrap().first().unwrap().clone();
+    assert_eq!(DomType::Doctype, doctype_dom.dom_type);
+    let doctype = doctype_dom.get_doctype().unwrap();
+    assert_eq!("html", doctype.get_name());
+    assert_eq!(None, doctype.get_public_id());
 
     // html
     let html_dom = root_dom.get_children().unwrap().get(1).unwrap().clone();
@@ -53,14 +56,14 @@ fn parse() {
     assert_eq!(None, tag.get_attrs());
 
     // head
-    let head_dom = html_dom.get_children().unwrap().get(0).unwrap().clone();
+    let head_dom = html_dom.get_children().unwrap().first().unwrap().clone();
     assert_eq!(DomType::Tag, head_dom.dom_type);
     let tag = head_dom.get_tag().unwrap();
     assert_eq!("head".to_string(), tag.get_name());
     assert_eq!(None, tag.get_attrs());
 
     // meta
-    let meta_dom = head_dom.get_children().unwrap().get(0).unwrap().clone();
+    let meta_dom = head_dom.get_children().unwrap().first().unwrap().clone();
     assert_eq!(DomType::Tag, meta_dom.dom_type);
     let tag = meta_dom.get_tag().unwrap();
     assert_eq!("meta".to_string(), tag.get_name());
@@ -74,7 +77,7 @@ fn parse() {
     assert_eq!(None, tag.get_attrs());
 
     // text "sample html"
-    let text_dom = title_dom.get_children().unwrap().get(0).unwrap().clone();
+    let text_dom = title_dom.get_children().unwrap().first().unwrap().clone();
     assert_eq!(DomType::Text, text_dom.dom_type);
     let text = text_dom.get_text().unwrap();
     assert_eq!("sample html", text.get_text());
@@ -87,14 +90,14 @@ fn parse() {
     assert_eq!(None, tag.get_attrs());
 
     // h1
-    let h1_dom = body_dom.get_children().unwrap().get(0).unwrap().clone();
+    let h1_dom = body_dom.get_children().unwrap().first().unwrap().clone();
     assert_eq!(DomType::Tag, h1_dom.dom_type);
     let tag = h1_dom.get_tag().unwrap();
     assert_eq!("h1".to_string(), tag.get_name());
     assert_eq!(None, tag.get_attrs());
 
     // text "Hello, world!"
-    let text_dom = h1_dom.get_children().unwrap().get(0).unwrap().clone();
+    let text_dom = h1_dom.get_children().unwrap().first().unwrap().clone();
     assert_eq!(DomType::Text, text_dom.dom_type);
     let text = text_dom.get_text().unwrap();
     assert_eq!("Hello, world!", text.get_text());
@@ -114,7 +117,7 @@ fn parse() {
     assert_eq!(None, tag.get_attrs());
 
     // li first
-    let li_first_dom = ol_dom.get_children().unwrap().get(0).unwrap().clone();
+    let li_first_dom = ol_dom.get_children().unwrap().first().unwrap().clone();
     assert_eq!(DomType::Tag, li_first_dom.dom_type);
     let tag = li_first_dom.get_tag().unwrap();
     assert_eq!("li".to_string(), tag.get_name());
@@ -150,8 +153,708 @@ fn parse() {
     assert_eq!(None, tag.get_attrs());
 
     //  text "\n  let content = document.getElementById('content');\n  content.textContent = 'content';\n"
-    let text_dom = script_dom.get_children().unwrap().get(0).unwrap().clone();
+    let text_dom = script_dom.get_children().unwrap().first().unwrap().clone();
     assert_eq!(DomType::Text, text_dom.dom_type);
     let text = text_dom.get_text().unwrap();
     assert_eq!("\n  let content = document.getElementById('content');\n  content.textContent = 'content';\n", text.get_text());
 }
+
+#[test]
+fn parse_error_unterminated_tag() {
+    let html = "<body>\n  <h1 class=\"h1\">Hello</h1>\n  <div id=\"a\"\n";
+
+    let err = parsercher::parse(html).unwrap_err();
+    match err {
+        ParseError::UnterminatedTag { .. } => (),
+        _ => panic!("unexpected error: {:?}", err),
+    }
+    let position = err.position();
+    assert_eq!(position.get_offset(), 37);
+    assert_eq!(position.get_line(), 3);
+    assert_eq!(position.get_column(), 3);
+    assert_eq!(err.snippet(), "<div id=\"a\"");
+}
+
+#[test]
+fn parse_error_unterminated_comment() {
+    let html = "<body>\n<!-- comment\n</body>";
+
+    let err = parsercher::parse(html).unwrap_err();
+    match err {
+        ParseError::UnterminatedComment { .. } => (),
+        _ => panic!("unexpected error: {:?}", err),
+    }
+    assert_eq!(err.position().get_line(), 2);
+    assert_eq!(err.position().get_column(), 1);
+    assert_eq!(err.snippet(), "<!-- comment");
+}
+
+#[test]
+fn parse_error_missing_attr_delimiter() {
+    let html = "<p>é</p><div class='a>text</div><p class='b'></p>";
+
+    let err = parsercher::parse(html).unwrap_err();
+    match err {
+        ParseError::MissingAttrDelimiter { delimiter, .. } => assert_eq!(delimiter, '\''),
+        _ => panic!("unexpected error: {:?}", err),
+    }
+    // 'é' is 2 bytes in UTF-8
+    assert_eq!(err.position().get_offset(), 20);
+    assert_eq!(err.position().get_column(), 20);
+    assert_eq!(
+        err.to_string(),
+        "There is no delimiter(') to terminate the attribute at line 1, column 20: `'a>text</div><p class='b'></p>`"
+    );
+}
+
+#[test]
+fn parse_error_unterminated_script() {
+    let html = "<body>\n<script>\nlet a = 1;\n";
+
+    let err = parsercher::parse(html).unwrap_err();
+    match &err {
+        ParseError::UnterminatedRawText { name, .. } => assert_eq!(name, "script"),
+        _ => panic!("unexpected error: {:?}", err),
+    }
+    assert_eq!(err.position().get_line(), 2);
+    assert_eq!(err.position().get_column(), 9);
+}
+
+#[test]
+fn parse_with_recover() {
+    let html = r#"
+<body>
+  <div class="a>1</div>
+  <div id='b>2</div><p id='c'>3</p>
+</body>
+<!-- unterminated comment
+<p>4</p>
+"#;
+
+    assert!(parsercher::parse(html).is_err());
+
+    let options = ParseOptions {
+        recover: true,
+        ..Default::default()
+    };
+    let (root_dom, errors) = parsercher::parse_with(html, options).unwrap();
+    assert_eq!(errors.len(), 4);
+    match errors[0] {
+        ParseError::UnterminatedTag { .. } => (),
+        _ => panic!("unexpected error: {:?}", errors[0]),
+    }
+    match errors[1] {
+        ParseError::UnterminatedAttrValue { delimiter, .. } => assert_eq!(delimiter, '"'),
+        _ => panic!("unexpected error: {:?}", errors[1]),
+    }
+    match errors[2] {
+        ParseError::MissingAttrDelimiter { delimiter, .. } => assert_eq!(delimiter, '\''),
+        _ => panic!("unexpected error: {:?}", errors[2]),
+    }
+    match errors[3] {
+        ParseError::UnterminatedComment { .. } => (),
+        _ => panic!("unexpected error: {:?}", errors[3]),
+    }
+
+    let children = root_dom.get_children().unwrap();
+    assert_eq!(children.len(), 2);
+
+    assert_eq!(DomType::Comment, children[1].dom_type);
+    let comment = children[1].get_comment().unwrap();
+    assert_eq!(" unterminated comment\n<p>4</p>", comment.get_comment());
+
+    let body_dom = children.first().unwrap();
+    let children = body_dom.get_children().unwrap();
+    assert_eq!(children.len(), 3);
+
+    let tag = children[0].get_tag().unwrap();
+    assert_eq!("div", tag.get_name());
+    assert_eq!(Some("a".to_string()), tag.get_attr("class"));
+
+    let tag = children[1].get_tag().unwrap();
+    assert_eq!("div", tag.get_name());
+    assert_eq!(Some("b".to_string()), tag.get_attr("id"));
+
+    let tag = children[2].get_tag().unwrap();
+    assert_eq!("p", tag.get_name());
+    assert_eq!(Some("c".to_string()), tag.get_attr("id"));
+}
+
+#[test]
+fn parse_with_recover_unterminated_tag_and_script() {
+    let options = ParseOptions {
+        recover: true,
+        ..Default::default()
+    };
+
+    let (root_dom, errors) = parsercher::parse_with("<p>1</p><div class", options.clone()).unwrap();
+    assert_eq!(errors.len(), 1);
+    let children = root_dom.get_children().unwrap();
+    assert_eq!(children.len(), 2);
+    assert_eq!("<div class", children[1].get_text().unwrap().get_text());
+
+    let (root_dom, errors) = parsercher::parse_with("<script>let a = 1;", options.clone()).unwrap();
+    assert_eq!(errors.len(), 1);
+    let children = root_dom.get_children().unwrap();
+    assert_eq!(children.len(), 2);
+    assert_eq!("script", children[0].get_tag().unwrap().get_name());
+    assert_eq!("let a = 1;", children[1].get_text().unwrap().get_text());
+
+    let (root_dom, errors) = parsercher::parse_with("no tags", options.clone()).unwrap();
+    assert!(errors.is_empty());
+    let children = root_dom.get_children().unwrap();
+    assert_eq!(children.len(), 1);
+    assert_eq!("no tags", children[0].get_text().unwrap().get_text());
+
+    let (root_dom, errors) = parsercher::parse_with("", options).unwrap();
+    assert!(errors.is_empty());
+    assert_eq!(None, root_dom.get_children());
+}
+
+#[test]
+fn parse_span() {
+    let html = "<body>\n  <!-- é -->\n  <ul class=\"list\">\n    <li>first</li>\n  </ul>\n</body>";
+
+    let root_dom = parsercher::parse(html).unwrap();
+    assert_eq!(None, root_dom.get_span());
+
+    let body_dom = root_dom.get_children().unwrap().first().unwrap();
+    assert_eq!(html, &html[body_dom.get_span().unwrap().get_range()]);
+
+    // comment
+    let comment_dom = body_dom.get_children().unwrap().first().unwrap();
+    let span = comment_dom.get_span().unwrap();
+    assert_eq!("<!-- é -->", &html[span.get_range()]);
+    assert_eq!(2, span.get_start().get_line());
+    assert_eq!(3, span.get_start().get_column());
+    assert_eq!(13, span.get_end().get_column());
+
+    // ul
+    let ul_dom = body_dom.get_children().unwrap().get(1).unwrap();
+    let tag = ul_dom.get_tag().unwrap();
+    let span = tag.get_span().unwrap();
+    assert_eq!("<ul class=\"list\">", &html[span.get_range()]);
+    assert_eq!(3, span.get_start().get_line());
+    let end_span = tag.get_end_span().unwrap();
+    assert_eq!("</ul>", &html[end_span.get_range()]);
+    assert_eq!(5, end_span.get_start().get_line());
+    assert_eq!(3, end_span.get_start().get_column());
+    let span = ul_dom.get_span().unwrap();
+    assert!(html[span.get_range()].starts_with("<ul class=\"list\">\n    <li>"));
+    assert!(html[span.get_range()].ends_with("</li>\n  </ul>"));
+
+    // text
+    let li_dom = ul_dom.get_children().unwrap().first().unwrap();
+    let text_dom = li_dom.get_children().unwrap().first().unwrap();
+    let span = text_dom.get_span().unwrap();
+    assert_eq!("first", &html[span.get_range()]);
+    assert_eq!(4, span.get_start().get_line());
+    assert_eq!(9, span.get_start().get_column());
+
+    // spans are kept in the search results
+    let tags = parsercher::search_tag_from_name(&root_dom, "li").unwrap();
+    let span = tags[0].get_span().unwrap();
+    assert_eq!("<li>", &html[span.get_range()]);
+    let span = tags[0].get_end_span().unwrap();
+    assert_eq!("</li>", &html[span.get_range()]);
+
+    let res = root_dom.search("<ul><li></li></ul>").unwrap().unwrap();
+    assert_eq!(ul_dom.get_span(), res[0].get_span());
+}
+
+#[test]
+fn span_is_not_compared() {
+    let a = parsercher::parse("<ul><li>first</li></ul>").unwrap();
+    let b = parsercher::parse("\n\n<ul>\n  <li>first</li>\n</ul>").unwrap();
+    assert_eq!(a, b);
+}
+
+#[test]
+fn parse_entities() {
+    let html = r#"<p title="Tom &amp; Jerry&#39;s" data-x="?a=1&copy=2">caf&eacute; &lt;b&gt; &#x27;&#39;</p>"#;
+
+    let root_dom = parsercher::parse(html).unwrap();
+    let p_dom = root_dom.get_children().unwrap().first().unwrap();
+    let tag = p_dom.get_tag().unwrap();
+    assert_eq!(Some("Tom & Jerry's".to_string()), tag.get_attr("title"));
+    assert_eq!(
+        Some("Tom &amp; Jerry&#39;s".to_string()),
+        tag.get_raw_attr("title")
+    );
+    assert_eq!(Some("?a=1&copy=2".to_string()), tag.get_attr("data-x"));
+    assert_eq!(Some("?a=1&copy=2".to_string()), tag.get_raw_attr("data-x"));
+
+    let text = p_dom
+        .get_children()
+        .unwrap()
+        .first()
+        .unwrap()
+        .get_text()
+        .unwrap();
+    assert_eq!("caf\u{e9} <b> ''", text.get_text());
+    assert_eq!("caf&eacute; &lt;b&gt; &#x27;&#39;", text.get_raw_text());
+
+    // without decoding
+    let options = ParseOptions {
+        decode_entities: false,
+        ..Default::default()
+    };
+    let (root_dom, _) = parsercher::parse_with(html, options).unwrap();
+    let p_dom = root_dom.get_children().unwrap().first().unwrap();
+    let tag = p_dom.get_tag().unwrap();
+    assert_eq!(
+        Some("Tom &amp; Jerry&#39;s".to_string()),
+        tag.get_attr("title")
+    );
+    let text = p_dom
+        .get_children()
+        .unwrap()
+        .first()
+        .unwrap()
+        .get_text()
+        .unwrap();
+    assert_eq!("caf&eacute; &lt;b&gt; &#x27;&#39;", text.get_text());
+}
+
+#[test]
+fn parse_script_entities_not_decoded() {
+    let html = "<script>if (a &amp;&amp; b) {}</script>";
+    let root_dom = parsercher::parse(html).unwrap();
+    let script_dom = root_dom.get_children().unwrap().first().unwrap();
+    let text = script_dom
+        .get_children()
+        .unwrap()
+        .first()
+        .unwrap()
+        .get_text()
+        .unwrap();
+    assert_eq!("if (a &amp;&amp; b) {}", text.get_text());
+}
+
+#[test]
+fn parse_attr_order() {
+    let html = r#"<input type="text" name="q" value="&lt;" id="q" name="dup" disabled />"#;
+    let dom = parsercher::parse(html).unwrap();
+    let tag = dom.get_children().unwrap()[0].get_tag().unwrap();
+
+    let attrs = tag.get_attrs().unwrap();
+    let names: Vec<&str> = attrs.iter().map(|(name, _)| name).collect();
+    assert_eq!(
+        names,
+        vec!["type", "name", "value", "id", "name", "disabled"]
+    );
+    assert_eq!(attrs.get("value"), Some("<"));
+    assert_eq!(Some("&lt;".to_string()), tag.get_raw_attr("value"));
+
+    // the first one is used as in HTML
+    assert_eq!(Some("q".to_string()), tag.get_attr("name"));
+    assert_eq!(
+        vec!["q".to_string(), "dup".to_string()],
+        tag.get_attr_values("name")
+    );
+
+    assert!(tag.is_terminated());
+    assert_eq!(
+        dom.to_html(),
+        r#"<input type="text" name="q" value="&lt;" id="q" name="dup" disabled="" />"#
+    );
+}
+
+#[test]
+fn parse_raw_text_elements() {
+    let first_text = |html: &str| -> String {
+        let root_dom = parsercher::parse(html).unwrap();
+        let children = root_dom.get_children().unwrap();
+        assert_eq!(children.len(), 1);
+        let text_dom = children[0].get_children().unwrap().first().unwrap();
+        text_dom.get_text().unwrap().get_text().to_string()
+    };
+
+    assert_eq!(
+        "a > b { color: red; }",
+        first_text("<style>a > b { color: red; }</style>")
+    );
+    assert_eq!(
+        "<b>bold</b> &",
+        first_text("<textarea><b>bold</b> &amp;</textarea>")
+    );
+    assert_eq!(
+        "<i>A & B</i>",
+        first_text("<title><i>A &amp; B</i></title>")
+    );
+    assert_eq!("<p>x</p>", first_text("<noscript><p>x</p></noscript>"));
+    assert_eq!(
+        "if (a < b) {}",
+        first_text("<SCRIPT>if (a < b) {}</script >")
+    );
+    assert_eq!(
+        "let s = '</scripts>';",
+        first_text("<script>let s = '</scripts>';</SCRIPT>")
+    );
+    assert_eq!("<a></plaintext>", first_text("<plaintext><a></plaintext>"));
+
+    let root_dom = parsercher::parse("<script></script><p>text</p>").unwrap();
+    let children = root_dom.get_children().unwrap();
+    assert_eq!(children.len(), 2);
+    assert_eq!(None, children[0].get_children());
+
+    let html = "<style>p > a {}</style>";
+    let root_dom = parsercher::parse(html).unwrap();
+    assert_eq!(html, root_dom.to_html());
+}
+
+#[test]
+fn parse_void_elements() {
+    let html = r#"<div><img src="a.png"><p>first<br>second<BR/>third</p><input type="text"></div><span></span></br>"#;
+    let root_dom = parsercher::parse(html).unwrap();
+    let children = root_dom.get_children().unwrap();
+    assert_eq!(children.len(), 2);
+
+    let div = &children[0];
+    let names: Vec<&str> = div
+        .get_children()
+        .unwrap()
+        .iter()
+        .map(|dom| dom.get_tag().unwrap().get_name())
+        .collect();
+    assert_eq!(names, vec!["img", "p", "input"]);
+    assert_eq!(None, div.get_children().unwrap()[0].get_children());
+
+    let p = &div.get_children().unwrap()[1];
+    let p_children = p.get_children().unwrap();
+    assert_eq!(p_children.len(), 5);
+    let br = p_children[3].get_tag().unwrap();
+    assert_eq!("br", br.get_name());
+    assert_eq!("BR", br.get_raw_name());
+    assert!(br.is_terminated());
+
+    // `<link>` has no end tag, so the text is its sibling
+    let root_dom = parsercher::parse("<head><link>text</link></head>").unwrap();
+    let head = &root_dom.get_children().unwrap()[0];
+    assert_eq!(head.get_children().unwrap().len(), 2);
+}
+
+#[test]
+fn parse_implied_end_tags() {
+    let tree = |html: &str| -> String {
+        fn write(dom: &parsercher::dom::Dom, out: &mut String) {
+            match dom.get_tag() {
+                Some(tag) => {
+                    out.push_str(&format!("({}", tag.get_name()));
+                    if let Some(children) = dom.get_children() {
+                        for child in children.iter() {
+                            write(child, out);
+                        }
+                    }
+                    out.push(')');
+                }
+                None => {
+                    out.push(' ');
+                    out.push_str(dom.get_text().unwrap().get_text());
+                }
+            }
+        }
+        let mut out = String::new();
+        for dom in parsercher::parse(html)
+            .unwrap()
+            .get_children()
+            .unwrap()
+            .iter()
+        {
+            write(dom, &mut out);
+        }
+        out
+    };
+
+    assert_eq!("(ul(li a)(li b))", tree("<ul><li>a<li>b</ul>"));
+    assert_eq!("(ul(li a)(li b))", tree("<ul><li>a<li>b</li></ul>"));
+    assert_eq!(
+        "(ul(li a(ul(li b)))(li c))",
+        tree("<ul><li>a<ul><li>b</ul><li>c</ul>")
+    );
+    assert_eq!("(p a)(div b)", tree("<p>a<div>b</div>"));
+    assert_eq!("(p a)(p b)", tree("<p>a<p>b</p>"));
+    assert_eq!("(dl(dt a)(dd b)(dt c))", tree("<dl><dt>a<dd>b<dt>c</dl>"));
+    assert_eq!(
+        "(select(option a)(option b))",
+        tree("<select><option>a<option>b</select>")
+    );
+    assert_eq!(
+        "(table(tr(td a)(td b))(tr(th c)))",
+        tree("<table><tr><td>a<td>b<tr><th>c</table>")
+    );
+
+    // misnested and stray end tags
+    assert_eq!("(div(p a))(p b)", tree("<div><p>a</div><p>b</p>"));
+    assert_eq!("(p a)(b c)", tree("<p>a</span></p><b>c</b>"));
+    assert_eq!("(p a)(p b)", tree("<p>a</div><p>b</p>"));
+}
+
+#[test]
+fn parse_mode() {
+    // HTML: the names are case-insensitive
+    let html = r#"<DIV ID="a"><Span>text</SPAN></div><p>end</p>"#;
+    let root_dom = parsercher::parse(html).unwrap();
+    let children = root_dom.get_children().unwrap();
+    assert_eq!(children.len(), 2);
+    let div = children[0].get_tag().unwrap();
+    assert_eq!("div", div.get_name());
+    assert_eq!("DIV", div.get_raw_name());
+    assert_eq!(Some("a".to_string()), div.get_attr("id"));
+    assert_eq!(Some("ID"), div.get_attrs().unwrap().get_raw_name("id"));
+
+    let mut needle = parsercher::dom::Tag::new("span");
+    let spans = parsercher::search_tag(&root_dom, &needle).unwrap();
+    assert_eq!(spans.len(), 1);
+    assert_eq!(spans[0].get_raw_name(), "Span");
+
+    // XML: the names are case-sensitive and the HTML rules are not applied
+    let options = ParseOptions {
+        mode: ParseMode::Xml,
+        ..Default::default()
+    };
+    let xml =
+        r#"<Item ID="a"><item></item><br><Name>desk</Name></br><script><b></b></script></Item>"#;
+    let (root_dom, _) = parsercher::parse_with(xml, options).unwrap();
+    let item = &root_dom.get_children().unwrap()[0];
+    assert_eq!("Item", item.get_tag().unwrap().get_name());
+    assert_eq!(
+        Some("a".to_string()),
+        item.get_tag().unwrap().get_attr("ID")
+    );
+    let names: Vec<&str> = item
+        .descendants()
+        .filter_map(|dom| dom.get_tag())
+        .map(|tag| tag.get_name())
+        .collect();
+    assert_eq!(names, vec!["item", "br", "Name", "script", "b"]);
+
+    needle.set_name("name");
+    assert_eq!(None, parsercher::search_tag(&root_dom, &needle));
+    needle.set_name("Name");
+    assert_eq!(1, parsercher::search_tag(&root_dom, &needle).unwrap().len());
+}
+
+#[test]
+fn parse_doctype() {
+    let html = r#"<!DOCTYPE HTML PUBLIC "-//W3C//DTD HTML 4.01//EN" "http://www.w3.org/TR/html4/strict.dtd">
+<html><body></body></html>"#;
+    let root_dom = parsercher::parse(html).unwrap();
+    assert_eq!(root_dom.get_children().unwrap().len(), 2);
+    let doctype = root_dom.doctype().unwrap();
+    assert_eq!("html", doctype.get_name());
+    assert_eq!(Some("-//W3C//DTD HTML 4.01//EN"), doctype.get_public_id());
+    assert_eq!(
+        Some("http://www.w3.org/TR/html4/strict.dtd"),
+        doctype.get_system_id()
+    );
+    assert_eq!(
+        root_dom.to_html(),
+        r#"<!DOCTYPE html PUBLIC "-//W3C//DTD HTML 4.01//EN" "http://www.w3.org/TR/html4/strict.dtd"><html><body></body></html>"#
+    );
+
+    // XML with the internal subset
+    let xml = r#"<!DOCTYPE note SYSTEM "note.dtd" [
+  <!ELEMENT note (to)>
+  <!ENTITY writer "a > b">
+]>
+<note><to>Tove</to></note>"#;
+    let options = ParseOptions {
+        mode: ParseMode::Xml,
+        ..Default::default()
+    };
+    let (root_dom, _) = parsercher::parse_with(xml, options).unwrap();
+    let doctype = root_dom.doctype().unwrap();
+    assert_eq!("note", doctype.get_name());
+    assert_eq!(None, doctype.get_public_id());
+    assert_eq!(Some("note.dtd"), doctype.get_system_id());
+    assert_eq!(
+        Some("\n  <!ELEMENT note (to)>\n  <!ENTITY writer \"a > b\">\n"),
+        doctype.get_internal_subset()
+    );
+    let note = &root_dom.get_children().unwrap()[1];
+    assert_eq!("note", note.get_tag().unwrap().get_name());
+
+    // unterminated
+    assert!(parsercher::parse("<!DOCTYPE html").is_err());
+}
+
+#[test]
+fn parse_xml_constructs() {
+    let xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<?xml-stylesheet type="text/xsl" href="style.xsl"?>
+<script><![CDATA[ if (a < b && c) {} ]]></script>"#;
+    let options = ParseOptions {
+        mode: ParseMode::Xml,
+        ..Default::default()
+    };
+    let (root_dom, _) = parsercher::parse_with(xml, options).unwrap();
+    let children = root_dom.get_children().unwrap();
+    assert_eq!(children.len(), 3);
+
+    let declaration = root_dom.xml_declaration().unwrap();
+    assert_eq!("1.0", declaration.get_version());
+    assert_eq!(Some("UTF-8"), declaration.get_encoding());
+    assert_eq!(Some(true), declaration.get_standalone());
+
+    let pi = children[1].get_processing_instruction().unwrap();
+    assert_eq!("xml-stylesheet", pi.get_target());
+    assert_eq!(r#"type="text/xsl" href="style.xsl""#, pi.get_data());
+    assert_eq!(None, pi.xml_declaration());
+
+    let script = children[2].get_children().unwrap();
+    assert_eq!(script.len(), 1);
+    assert_eq!(DomType::CData, script[0].dom_type);
+    assert_eq!(
+        " if (a < b && c) {} ",
+        script[0].get_cdata().unwrap().get_data()
+    );
+
+    assert_eq!(
+        root_dom.to_html(),
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><?xml-stylesheet type="text/xsl" href="style.xsl"?><script><![CDATA[ if (a < b && c) {} ]]></script>"#
+    );
+
+    // unterminated
+    assert!(parsercher::parse("<a><![CDATA[ a < b </a>").is_err());
+    assert!(parsercher::parse("<?xml version=\"1.0\"<a></a>").is_err());
+}
+
+#[test]
+fn parse_xml_strict() {
+    let xml = r#"<?xml version="1.0"?>
+<!DOCTYPE note [
+  <!ENTITY writer "Jani">
+]>
+<note date='2021-05-07'>
+  <to>Tove</to>
+  <from>&writer; &amp; &#x41;</from>
+  <empty/>
+</note>"#;
+    let root_dom = parsercher::parse_xml_strict(xml).unwrap();
+    let texts =
+        parsercher::search_text_from_tag_children(&root_dom, &parsercher::dom::Tag::new("from"))
+            .unwrap();
+    assert_eq!(texts, vec!["Jani & A"]);
+
+    let errors = vec![
+        ("<a>\n  <b></a>", 2, 6, "Expected </b> but found </a>"),
+        ("<a></a></b>", 1, 8, "There is no open element for </b>"),
+        (
+            "<a>\n  <b></b>",
+            1,
+            1,
+            "Input ends before the end tag of <a>",
+        ),
+        ("<a></a>\n<b/>", 2, 1, "There is more than one root element"),
+        ("<!-- comment -->", 1, 1, "There is no root element"),
+        ("text<a/>", 1, 1, "There is text outside the root element"),
+        ("<a/>text", 1, 5, "There is text outside the root element"),
+        (
+            "<a></a>\n x",
+            2,
+            2,
+            "There is text outside the root element",
+        ),
+        (
+            "<!-- c --> t <a></a>",
+            1,
+            12,
+            "There is text outside the root element",
+        ),
+        (
+            "<a></a><![CDATA[x]]>",
+            1,
+            8,
+            "There is text outside the root element",
+        ),
+        (
+            r#"<a id="1" id="2"></a>"#,
+            1,
+            11,
+            "The attribute(id) appears more than once",
+        ),
+        (
+            "<a id=1></a>",
+            1,
+            4,
+            "The value of the attribute(id) is not quoted",
+        ),
+        (
+            "<input checked></input>",
+            1,
+            8,
+            "The value of the attribute(checked) is not quoted",
+        ),
+        (
+            r#"<a title="x<y"></a>"#,
+            1,
+            12,
+            "The value of the attribute(title) contains '<'",
+        ),
+        (
+            "<a>caf\u{e9}&nbsp;</a>",
+            1,
+            8,
+            "The entity reference(&nbsp) is not defined",
+        ),
+        (
+            r#"<a href="?a=1&b=2"></a>"#,
+            1,
+            14,
+            "The entity reference(&b=2) is not defined",
+        ),
+        (
+            "\u{3000}<a></a>",
+            1,
+            1,
+            "There is text outside the root element",
+        ),
+        (
+            "<a></a>\u{3000}\n",
+            1,
+            8,
+            "There is text outside the root element",
+        ),
+        (
+            "<a></a>\n \u{3000}<!-- c -->",
+            2,
+            2,
+            "There is text outside the root element",
+        ),
+        (
+            "<a>&#+65;</a>",
+            1,
+            4,
+            "The entity reference(&#+65) is not defined",
+        ),
+        (
+            "<a>&#x+41;</a>",
+            1,
+            4,
+            "The entity reference(&#x+41) is not defined",
+        ),
+        (
+            "<a>&#0;</a>",
+            1,
+            4,
+            "The entity reference(&#0) is not defined",
+        ),
+    ];
+    for (xml, line, column, message) in errors.iter() {
+        let err = parsercher::parse_xml_strict(xml).unwrap_err();
+        assert_eq!(
+            (err.position().get_line(), err.position().get_column()),
+            (*line, *column),
+            "{}",
+            xml
+        );
+        assert!(err.to_string().starts_with(message), "{}", err);
+    }
+
+    assert!(parsercher::parse_xml_strict("").is_err());
+    let root_dom = parsercher::parse_xml_strict(" \t<a/>\r\n ").unwrap();
+    assert_eq!(root_dom.get_children().unwrap().len(), 1);
+    // the lenient parser accepts them
+    assert!(parsercher::parse("<a><b></a>").is_ok());
+}
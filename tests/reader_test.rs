@@ -0,0 +1,195 @@
+extern crate parsercher;
+
+use std::io;
+use std::io::Read;
+
+use parsercher::dom::Tag;
+use parsercher::Event;
+use parsercher::ParseError;
+use parsercher::ParseMode;
+use parsercher::ParseOptions;
+use parsercher::ReadError;
+use parsercher::Reader;
+
+/// Returns the document `chunk` bytes at a time, then fails if `fail` is true.
+struct ChunkReader<'a> {
+    bytes: &'a [u8],
+    chunk: usize,
+    fail: bool,
+}
+
+impl Read for ChunkReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.bytes.is_empty() && self.fail {
+            return Err(io::Error::other("broken pipe"));
+        }
+        let len = self.chunk.min(buf.len()).min(self.bytes.len());
+        buf[..len].copy_from_slice(&self.bytes[..len]);
+        self.bytes = &self.bytes[len..];
+        Ok(len)
+    }
+}
+
+fn chunks(doc: &str, chunk: usize) -> ChunkReader<'_> {
+    ChunkReader {
+        bytes: doc.as_bytes(),
+        chunk,
+        fail: false,
+    }
+}
+
+#[test]
+fn read_events() {
+    let xml = r#"<?xml version="1.0"?>
+<!DOCTYPE note>
+<note lang="日本語">
+  <!-- memo -->
+  <to>Tove &amp; Jani</to>
+  <br/>
+  <![CDATA[a < b]]>
+</note>
+"#;
+    let options = ParseOptions {
+        mode: ParseMode::Xml,
+        ..Default::default()
+    };
+    let events: Vec<Event> = Reader::with_options(chunks(xml, 1), options.clone())
+        .map(|event| event.unwrap())
+        .collect();
+
+    let mut note = Tag::new("note");
+    note.set_attr("lang", "日本語");
+    let mut end_to = Tag::new("to");
+    end_to.set_terminator(true);
+    let mut br = Tag::new("br");
+    br.set_terminated(true);
+    let mut end_note = Tag::new("note");
+    end_note.set_terminator(true);
+    let names: Vec<String> = events
+        .iter()
+        .map(|event| match event {
+            Event::StartTag(tag) => format!("<{}>", tag.get_name()),
+            Event::EndTag(tag) => format!("</{}>", tag.get_name()),
+            Event::EmptyTag(tag) => format!("<{}/>", tag.get_name()),
+            Event::Text(text) => text.get_text().to_string(),
+            Event::Comment(comment) => format!("<!--{}-->", comment.get_comment()),
+            Event::Doctype(doctype) => doctype.to_string(),
+            Event::CData(cdata) => cdata.to_string(),
+            Event::ProcessingInstruction(pi) => pi.to_string(),
+        })
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            "<?xml version=\"1.0\"?>",
+            "<!DOCTYPE note>",
+            "<note>",
+            "<!-- memo -->",
+            "<to>",
+            "Tove & Jani",
+            "</to>",
+            "<br/>",
+            "<![CDATA[a < b]]>",
+            "</note>",
+        ]
+    );
+    assert_eq!(events[2], Event::StartTag(note));
+    assert_eq!(events[6], Event::EndTag(end_to));
+    assert_eq!(events[7], Event::EmptyTag(br));
+    assert_eq!(events[9], Event::EndTag(end_note));
+
+    // the chunk size does not change the events
+    for chunk in [2, 3, 5, 64] {
+        let chunked: Vec<Event> = Reader::with_options(chunks(xml, chunk), options.clone())
+            .map(|event| event.unwrap())
+            .collect();
+        assert_eq!(chunked, events);
+    }
+}
+
+#[test]
+fn read_large_document() {
+    let mut xml = String::from("<list>\n");
+    for i in 0..3000 {
+        xml.push_str(&format!("  <item id=\"{}\">café {}</item>\n", i, i));
+    }
+    xml.push_str("</list>\n");
+
+    let mut items = 0;
+    let mut last = None;
+    for event in Reader::new(chunks(&xml, 1000)) {
+        if let Event::StartTag(tag) = event.unwrap() {
+            if tag.get_name() == "item" {
+                items += 1;
+                last = Some(tag);
+            }
+        }
+    }
+    assert_eq!(items, 3000);
+
+    // the positions are kept after the parsed part of the document is dropped
+    let last = last.unwrap();
+    assert_eq!(last.get_attr("id").unwrap(), "2999");
+    let dom = parsercher::parse(&xml).unwrap();
+    let tags = parsercher::search_tag_from_name(&dom, "item").unwrap();
+    let expected = tags.last().unwrap().get_span().unwrap();
+    let span = last.get_span().unwrap();
+    assert_eq!(span, expected);
+    assert_eq!(span.get_start().get_line(), 3001);
+    assert_eq!(span.get_start().get_column(), 3);
+}
+
+#[test]
+fn read_errors() {
+    let mut reader = Reader::new("<p>a</p><!-- b".as_bytes());
+    assert!(matches!(reader.read_event(), Ok(Some(Event::StartTag(_)))));
+    assert!(matches!(reader.read_event(), Ok(Some(Event::Text(_)))));
+    assert!(matches!(reader.read_event(), Ok(Some(Event::EndTag(_)))));
+    match reader.read_event() {
+        Err(ReadError::Parse(e)) => assert!(matches!(e, ParseError::UnterminatedComment { .. })),
+        res => panic!("unexpected {:?}", res),
+    }
+    assert!(reader.read_event().unwrap().is_none());
+
+    let options = ParseOptions {
+        recover: true,
+        ..Default::default()
+    };
+    let mut reader = Reader::with_options("<p>a</p><!-- b".as_bytes(), options);
+    let mut comments = Vec::new();
+    reader
+        .read_to_end(|event| {
+            if let Event::Comment(comment) = event {
+                comments.push(comment.get_comment().to_string());
+            }
+        })
+        .unwrap();
+    assert_eq!(comments, vec![" b"]);
+    assert_eq!(reader.get_errors().len(), 1);
+
+    let source = ChunkReader {
+        bytes: b"<p>a</p>",
+        chunk: 4,
+        fail: true,
+    };
+    let res: Result<Vec<Event>, ReadError> = Reader::new(source).collect();
+    assert!(matches!(res, Err(ReadError::Io(_))));
+}
+
+#[test]
+fn read_text_outside_tags() {
+    for chunk in [1, 3, 64] {
+        let texts: Vec<String> = Reader::new(chunks("head<p>a</p>tail &amp; more", chunk))
+            .filter_map(|event| match event.unwrap() {
+                Event::Text(text) => Some(text.get_text().to_string()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(texts, vec!["head", "a", "tail & more"]);
+
+        let events: Vec<Event> = Reader::new(chunks("abc", chunk))
+            .map(|event| event.unwrap())
+            .collect();
+        assert_eq!(events, vec![Event::Text(parsercher::dom::Text::new("abc"))]);
+    }
+}
@@ -44,7 +44,7 @@ fn search_dom() {
 </html>
 "#;
 
-    let dom = parsercher::parse(&html).unwrap();
+    let dom = parsercher::parse(html).unwrap();
     //parsercher::print_dom_tree(&root_dom);
 
     let needle = r#"
@@ -54,11 +54,11 @@ fn search_dom() {
 </ul>
 "#;
 
-    let needle_dom = parsercher::parse(&needle).unwrap();
+    let needle_dom = parsercher::parse(needle).unwrap();
     // Remove `root`dom of needle_dom
-    let needle_dom = needle_dom.get_children().unwrap().get(0).unwrap();
+    let needle_dom = needle_dom.get_children().unwrap().first().unwrap();
 
-    let root_dom = parsercher::search_dom(&dom, &needle_dom).unwrap();
+    let root_dom = parsercher::search_dom(&dom, needle_dom).unwrap();
 
     // root
     assert_eq!(DomType::Tag, root_dom.dom_type);
@@ -67,7 +67,7 @@ fn search_dom() {
     assert_eq!(None, tag.get_attrs());
 
     // ul: list1
-    let ul_dom = root_dom.get_children().unwrap().get(0).unwrap().clone();
+    let ul_dom = root_dom.get_children().unwrap().first().unwrap().clone();
     assert_eq!(DomType::Tag, ul_dom.dom_type);
     let tag = ul_dom.get_tag().unwrap();
     assert_eq!("ul".to_string(), tag.get_name());
@@ -75,14 +75,14 @@ fn search_dom() {
     assert_eq!(Some("targetList".to_string()), tag.get_attr("class"));
 
     // li key1
-    let li_dom = ul_dom.get_children().unwrap().get(0).unwrap().clone();
+    let li_dom = ul_dom.get_children().unwrap().first().unwrap().clone();
     assert_eq!(DomType::Tag, li_dom.dom_type);
     let tag = li_dom.get_tag().unwrap();
     assert_eq!("li".to_string(), tag.get_name());
     assert_eq!(Some("key1".to_string()), tag.get_attr("class"));
 
     // text: 1-1
-    let text_dom = li_dom.get_children().unwrap().get(0).unwrap().clone();
+    let text_dom = li_dom.get_children().unwrap().first().unwrap().clone();
     assert_eq!(DomType::Text, text_dom.dom_type);
     let text = text_dom.get_text().unwrap();
     assert_eq!("1-1", text.get_text());
@@ -95,13 +95,13 @@ fn search_dom() {
     assert_eq!(Some("key2".to_string()), tag.get_attr("class"));
 
     // span
-    let span_dom = li_dom.get_children().unwrap().get(0).unwrap().clone();
+    let span_dom = li_dom.get_children().unwrap().first().unwrap().clone();
     assert_eq!(DomType::Tag, span_dom.dom_type);
     let tag = span_dom.get_tag().unwrap();
     assert_eq!("span".to_string(), tag.get_name());
 
     // text: 1-2
-    let text_dom = span_dom.get_children().unwrap().get(0).unwrap().clone();
+    let text_dom = span_dom.get_children().unwrap().first().unwrap().clone();
     assert_eq!(DomType::Text, text_dom.dom_type);
     let text = text_dom.get_text().unwrap();
     assert_eq!("1-2", text.get_text());
@@ -115,14 +115,14 @@ fn search_dom() {
     assert_eq!(Some("targetList".to_string()), tag.get_attr("class"));
 
     // li key1
-    let li_dom = ul_dom.get_children().unwrap().get(0).unwrap().clone();
+    let li_dom = ul_dom.get_children().unwrap().first().unwrap().clone();
     assert_eq!(DomType::Tag, li_dom.dom_type);
     let tag = li_dom.get_tag().unwrap();
     assert_eq!("li".to_string(), tag.get_name());
     assert_eq!(Some("key1".to_string()), tag.get_attr("class"));
 
     // text: 3-1
-    let text_dom = li_dom.get_children().unwrap().get(0).unwrap().clone();
+    let text_dom = li_dom.get_children().unwrap().first().unwrap().clone();
     assert_eq!(DomType::Text, text_dom.dom_type);
     let text = text_dom.get_text().unwrap();
     assert_eq!("3-1", text.get_text());
@@ -135,7 +135,7 @@ fn search_dom() {
     assert_eq!(Some("item".to_string()), tag.get_attr("class"));
 
     // text: 3-2
-    let text_dom = li_dom.get_children().unwrap().get(0).unwrap().clone();
+    let text_dom = li_dom.get_children().unwrap().first().unwrap().clone();
     assert_eq!(DomType::Text, text_dom.dom_type);
     let text = text_dom.get_text().unwrap();
     assert_eq!("3-2", text.get_text());
@@ -148,7 +148,7 @@ fn search_dom() {
     assert_eq!(Some("key2".to_string()), tag.get_attr("class"));
 
     // text: 3-3
-    let text_dom = li_dom.get_children().unwrap().get(0).unwrap().clone();
+    let text_dom = li_dom.get_children().unwrap().first().unwrap().clone();
     assert_eq!(DomType::Text, text_dom.dom_type);
     let text = text_dom.get_text().unwrap();
     assert_eq!("3-3", text.get_text());
@@ -1,6 +1,10 @@
 extern crate parsercher;
 
+use parsercher::dom::Attrs;
 use parsercher::dom::DomType;
+use parsercher::dom::Tag;
+use parsercher::ParseMode;
+use parsercher::ParseOptions;
 
 #[test]
 fn search() {
@@ -44,7 +48,7 @@ fn search() {
 </html>
 "#;
 
-    let dom = parsercher::parse(&html).unwrap();
+    let dom = parsercher::parse(html).unwrap();
     //parsercher::print_dom_tree(&root_dom);
 
     let needle = r#"
@@ -54,13 +58,13 @@ fn search() {
 </ul>
 "#;
 
-    let res = dom.search(&needle).unwrap().unwrap();
+    let res = dom.search(needle).unwrap().unwrap();
     println!("{:?}", res);
 
     assert_eq!(res.len(), 2);
 
     // ul: list1
-    let ul_dom = res.get(0).unwrap();
+    let ul_dom = res.first().unwrap();
     assert_eq!(DomType::Tag, ul_dom.dom_type);
     let tag = ul_dom.get_tag().unwrap();
     assert_eq!("ul".to_string(), tag.get_name());
@@ -68,14 +72,14 @@ fn search() {
     assert_eq!(Some("targetList".to_string()), tag.get_attr("class"));
 
     // li key1
-    let li_dom = ul_dom.get_children().unwrap().get(0).unwrap().clone();
+    let li_dom = ul_dom.get_children().unwrap().first().unwrap().clone();
     assert_eq!(DomType::Tag, li_dom.dom_type);
     let tag = li_dom.get_tag().unwrap();
     assert_eq!("li".to_string(), tag.get_name());
     assert_eq!(Some("key1".to_string()), tag.get_attr("class"));
 
     // text: 1-1
-    let text_dom = li_dom.get_children().unwrap().get(0).unwrap().clone();
+    let text_dom = li_dom.get_children().unwrap().first().unwrap().clone();
     assert_eq!(DomType::Text, text_dom.dom_type);
     let text = text_dom.get_text().unwrap();
     assert_eq!("1-1", text.get_text());
@@ -88,13 +92,13 @@ fn search() {
     assert_eq!(Some("key2".to_string()), tag.get_attr("class"));
 
     // span
-    let span_dom = li_dom.get_children().unwrap().get(0).unwrap().clone();
+    let span_dom = li_dom.get_children().unwrap().first().unwrap().clone();
     assert_eq!(DomType::Tag, span_dom.dom_type);
     let tag = span_dom.get_tag().unwrap();
     assert_eq!("span".to_string(), tag.get_name());
 
     // text: 1-2
-    let text_dom = span_dom.get_children().unwrap().get(0).unwrap().clone();
+    let text_dom = span_dom.get_children().unwrap().first().unwrap().clone();
     assert_eq!(DomType::Text, text_dom.dom_type);
     let text = text_dom.get_text().unwrap();
     assert_eq!("1-2", text.get_text());
@@ -108,14 +112,14 @@ fn search() {
     assert_eq!(Some("targetList".to_string()), tag.get_attr("class"));
 
     // li key1
-    let li_dom = ul_dom.get_children().unwrap().get(0).unwrap().clone();
+    let li_dom = ul_dom.get_children().unwrap().first().unwrap().clone();
     assert_eq!(DomType::Tag, li_dom.dom_type);
     let tag = li_dom.get_tag().unwrap();
     assert_eq!("li".to_string(), tag.get_name());
     assert_eq!(Some("key1".to_string()), tag.get_attr("class"));
 
     // text: 3-1
-    let text_dom = li_dom.get_children().unwrap().get(0).unwrap().clone();
+    let text_dom = li_dom.get_children().unwrap().first().unwrap().clone();
     assert_eq!(DomType::Text, text_dom.dom_type);
     let text = text_dom.get_text().unwrap();
     assert_eq!("3-1", text.get_text());
@@ -128,7 +132,7 @@ fn search() {
     assert_eq!(Some("item".to_string()), tag.get_attr("class"));
 
     // text: 3-2
-    let text_dom = li_dom.get_children().unwrap().get(0).unwrap().clone();
+    let text_dom = li_dom.get_children().unwrap().first().unwrap().clone();
     assert_eq!(DomType::Text, text_dom.dom_type);
     let text = text_dom.get_text().unwrap();
     assert_eq!("3-2", text.get_text());
@@ -141,8 +145,120 @@ fn search() {
     assert_eq!(Some("key2".to_string()), tag.get_attr("class"));
 
     // text: 3-3
-    let text_dom = li_dom.get_children().unwrap().get(0).unwrap().clone();
+    let text_dom = li_dom.get_children().unwrap().first().unwrap().clone();
     assert_eq!(DomType::Text, text_dom.dom_type);
     let text = text_dom.get_text().unwrap();
     assert_eq!("3-3", text.get_text());
 }
+
+#[test]
+fn search_refs() {
+    let html = r#"
+<body>
+  <ul id="list1" class="targetList">
+    <li class="key1">1-1</li>
+    <li class="key2"><span>1-2</span></li>
+  </ul>
+  <ul id="list2">
+    <li class="key1">2-1</li>
+  </ul>
+</body>
+"#;
+    let root_dom = parsercher::parse(html).unwrap();
+    let tree = root_dom.tree();
+
+    let result = root_dom
+        .search_refs(r#"<ul class="targetList"><li class="key2"></li></ul>"#)
+        .unwrap();
+    assert_eq!(result.len(), 1);
+    let id = tree.find(result[0]).unwrap();
+    let body = tree.get(tree.parent(id).unwrap());
+    assert_eq!("body", body.get_tag().unwrap().get_name());
+
+    // same nodes as the cloned result
+    let cloned = root_dom
+        .search(r#"<ul class="targetList"><li class="key2"></li></ul>"#)
+        .unwrap()
+        .unwrap();
+    assert_eq!(*cloned[0], *result[0]);
+
+    // an empty needle matches nothing
+    assert!(root_dom.search_refs("").unwrap().is_empty());
+    assert!(root_dom.search_refs(" \n ").unwrap().is_empty());
+    assert!(root_dom.search("").unwrap().is_none());
+
+    let mut needle = parsercher::dom::Tag::new("li");
+    needle.set_attr("class", "key1");
+    let lis = parsercher::search_tag_refs(&root_dom, &needle);
+    assert_eq!(lis.len(), 2);
+    assert!(tree.find(lis[1]).is_some());
+    let text = lis[1].get_children().unwrap()[0].get_text().unwrap();
+    assert_eq!("2-1", text.get_text());
+
+    let spans = parsercher::search_tag_from_name_refs(&root_dom, "span");
+    assert_eq!(spans.len(), 1);
+    let li = &result[0].get_children().unwrap()[1];
+    assert!(std::ptr::eq(spans[0], &*li.get_children().unwrap()[0]));
+    assert!(parsercher::search_tag_from_name_refs(&root_dom, "table").is_empty());
+}
+
+#[test]
+fn search_namespace() {
+    let xml = r#"<soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/" xmlns="urn:default">
+  <soap:Body>
+    <GetPrice xmlns:m="urn:market" m:currency="JPY">
+      <Item xmlns="">Apple</Item>
+    </GetPrice>
+  </soap:Body>
+</soap:Envelope>"#;
+    let options = ParseOptions {
+        mode: ParseMode::Xml,
+        ..Default::default()
+    };
+    let (root_dom, _) = parsercher::parse_with(xml, options).unwrap();
+
+    let tags = parsercher::search_tag_from_name(&root_dom, "soap:Body").unwrap();
+    assert_eq!(Some("soap"), tags[0].prefix());
+    assert_eq!("Body", tags[0].local_name());
+    assert_eq!(
+        Some("http://schemas.xmlsoap.org/soap/envelope/"),
+        tags[0].namespace_uri()
+    );
+
+    let tags = parsercher::search_tag_from_name(&root_dom, "GetPrice").unwrap();
+    assert_eq!(None, tags[0].prefix());
+    assert_eq!(Some("urn:default"), tags[0].namespace_uri());
+    assert_eq!(
+        Some("JPY".to_string()),
+        tags[0].get_attr_ns("urn:market", "currency")
+    );
+
+    // `xmlns=""` undeclares the default namespace
+    let tags = parsercher::search_tag_from_name(&root_dom, "Item").unwrap();
+    assert_eq!(None, tags[0].namespace_uri());
+
+    // matched by the namespace URI and the local name regardless of the prefix
+    let mut needle = Tag::new("env:Body");
+    needle.set_namespace_uri("http://schemas.xmlsoap.org/soap/envelope/");
+    let tags = parsercher::search_tag(&root_dom, &needle).unwrap();
+    assert_eq!(tags.len(), 1);
+    assert_eq!("soap:Body", tags[0].get_name());
+
+    let mut needle = Tag::new("GetPrice");
+    needle.set_namespace_uri("urn:default");
+    let mut attrs = Attrs::new();
+    attrs.push("x:currency", "JPY");
+    attrs.set_namespace_uri("x:currency", "urn:market");
+    needle.set_attrs(attrs);
+    assert!(parsercher::search_tag(&root_dom, &needle).is_some());
+
+    needle.set_namespace_uri("urn:other");
+    assert!(parsercher::search_tag(&root_dom, &needle).is_none());
+
+    // the needle document declares its own prefix
+    let root_dom = parsercher::parse(xml).unwrap();
+    let needle = r#"<s:Body xmlns:s="http://schemas.xmlsoap.org/soap/envelope/"></s:Body>"#;
+    let result = root_dom.search(needle).unwrap().unwrap();
+    assert_eq!(result.len(), 1);
+    assert_eq!("soap:body", result[0].get_tag().unwrap().get_name());
+}
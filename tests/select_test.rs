@@ -0,0 +1,90 @@
+extern crate parsercher;
+
+use parsercher::dom::Dom;
+use parsercher::Selector;
+
+fn texts(doms: &[&Dom]) -> Vec<String> {
+    doms.iter().map(|dom| first_text(dom)).collect()
+}
+
+fn first_text(dom: &Dom) -> String {
+    if let Some(text) = dom.get_text() {
+        return text.get_text().to_string();
+    }
+    first_text(&dom.get_children().unwrap()[0])
+}
+
+#[test]
+fn select() {
+    let html = r#"
+<!DOCTYPE html>
+<html>
+  <head>
+    <meta charset="UTF-8">
+    <title>sample html</title>
+  </head>
+  <body>
+    <div id="content" class="content main">
+      <ul id="list1" class="targetList">
+        <li class="key1">1-1</li>
+        <li class="key2"><a href="https://example.com/1-2">1-2</a></li>
+      </ul>
+      <ul id="list2" lang="en-US">
+        <li class="key1">2-1</li>
+        <li><a href="http://example.com/2-2">2-2</a></li>
+        <li class="key2">2-3</li>
+      </ul>
+    </div>
+    <div>
+      <ul id="list3" class="targetList">
+        <li class="key1">3-1</li>
+      </ul>
+    </div>
+  </body>
+</html>
+"#;
+    let dom = parsercher::parse(html).unwrap();
+
+    let li = dom.select("ul.targetList > li.key1").unwrap();
+    assert_eq!(texts(&li), vec!["1-1", "3-1"]);
+
+    let li = dom.select("#content li:last-child").unwrap();
+    assert_eq!(texts(&li), vec!["1-2", "2-3"]);
+
+    let a = dom
+        .select("div.content > ul li:nth-child(2) a[href^='https']")
+        .unwrap();
+    assert_eq!(texts(&a), vec!["1-2"]);
+
+    let li = dom.select("ul[lang|=en] li:not(.key1, .key2)").unwrap();
+    assert_eq!(texts(&li), vec!["2-2"]);
+
+    let li = dom.select("li.key1 ~ li, #list3 li").unwrap();
+    assert_eq!(texts(&li), vec!["1-2", "2-2", "2-3", "3-1"]);
+
+    let a = dom.select("A[HREF$=\"2-2\"]").unwrap();
+    assert_eq!(texts(&a), vec!["2-2"]);
+
+    assert!(dom.select("body > p").unwrap().is_empty());
+
+    // search from a subtree
+    let list2 = dom.select("#list2").unwrap()[0];
+    let li = list2.select("ul > li").unwrap();
+    assert_eq!(li.len(), 3);
+
+    // the compiled selector can be reused
+    let selector = Selector::parse("li:first-child").unwrap();
+    assert_eq!(texts(&selector.select(&dom)), vec!["1-1", "2-1", "3-1"]);
+    assert_eq!(texts(&selector.select(list2)), vec!["2-1"]);
+}
+
+#[test]
+fn select_error() {
+    let dom = parsercher::parse("<p></p>").unwrap();
+    let e = dom.select("p:hover").unwrap_err();
+    assert_eq!(e.offset(), 2);
+    assert_eq!(
+        e.to_string(),
+        "Unsupported pseudo-class ':hover' at offset 2: `p:hover`"
+    );
+}
@@ -0,0 +1,135 @@
+extern crate parsercher;
+
+use parsercher::dom::{Dom, DomType, Tag};
+use parsercher::ParseMode;
+use parsercher::ParseOptions;
+use parsercher::SerializeOptions;
+
+#[test]
+fn round_trip() {
+    let html = r#"
+<!DOCTYPE html>
+<html>
+  <head>
+    <meta charset="UTF-8">
+    <title>sample &amp; html</title>
+  </head>
+  <body>
+    <h1 class="title" data-text="say &quot;hi&quot; &lt;3">Hello, world!</h1>
+    <div id="content"></div>
+    <ol>
+      <li id="first" class="listItem">first &lt;1&gt;</li>
+      <li class="listItem">second<br /></li>
+      <li id="therd" class="listItem">caf&eacute;</li>
+    </ol>
+    <!-- All script code becomes one text -->
+<script>
+  if (a < b && b > c) {
+    console.log('content');
+  }
+</script>
+  </body>
+</html>
+"#;
+
+    let dom = parsercher::parse(html).unwrap();
+
+    let doc = dom.to_html();
+    assert_eq!(parsercher::parse(&doc).unwrap(), dom);
+
+    let mut buf: Vec<u8> = Vec::new();
+    parsercher::serialize(&dom, &mut buf).unwrap();
+    assert_eq!(String::from_utf8(buf).unwrap(), doc);
+
+    assert_eq!(format!("{}", dom), doc);
+}
+
+#[test]
+fn pretty() {
+    let html = r#"<body><h1>Hello</h1><ul><li>first</li><li>second<!-- c --></li></ul><br></body>"#;
+    let dom = parsercher::parse(html).unwrap();
+
+    let expected = r#"<body>
+  <h1>Hello</h1>
+  <ul>
+    <li>first</li>
+    <li>
+      second
+      <!-- c -->
+    </li>
+  </ul>
+  <br>
+</body>
+"#;
+    assert_eq!(format!("{:#}", dom), expected);
+
+    let options = SerializeOptions {
+        pretty: true,
+        indent: String::from("\t"),
+        ..Default::default()
+    };
+    let mut buf: Vec<u8> = Vec::new();
+    parsercher::serialize_with(&dom, &mut buf, &options).unwrap();
+    assert_eq!(
+        String::from_utf8(buf).unwrap(),
+        expected.replace("  ", "\t")
+    );
+}
+
+#[test]
+fn round_trip_xml() {
+    let xml = "<r><script><b>x</b></script><style><i /></style><br></br><p>a &lt; b</p></r>";
+    let parse_options = ParseOptions {
+        mode: ParseMode::Xml,
+        ..Default::default()
+    };
+    let (dom, _) = parsercher::parse_with(xml, parse_options.clone()).unwrap();
+
+    let options = SerializeOptions {
+        mode: ParseMode::Xml,
+        ..Default::default()
+    };
+    let mut buf: Vec<u8> = Vec::new();
+    parsercher::serialize_with(&dom, &mut buf, &options).unwrap();
+    let doc = String::from_utf8(buf).unwrap();
+    assert_eq!(doc, xml);
+    assert_eq!(parsercher::parse_with(&doc, parse_options).unwrap().0, dom);
+}
+
+#[test]
+fn raw_text_children() {
+    // elements added to a raw text element are not dropped
+    let mut dom = parsercher::parse("<script>let a = 1 < 2;</script>").unwrap();
+    let b = parsercher::parse("<b>x</b>")
+        .unwrap()
+        .get_children()
+        .unwrap()[0]
+        .clone();
+    dom.get_children_mut().unwrap()[0].add_child(*b);
+    assert_eq!(dom.to_html(), "<script>let a = 1 < 2;<b>x</b></script>");
+}
+
+#[test]
+fn round_trip_text_outside_tags() {
+    for html in ["head<a>x</a>tail &amp; more", "<a>x</a>y", "a &lt; b"] {
+        let dom = parsercher::parse(html).unwrap();
+        let doc = dom.to_html();
+        assert_eq!(doc, html);
+        assert_eq!(parsercher::parse(&doc).unwrap(), dom);
+    }
+}
+
+#[test]
+fn void_elements_case_insensitive() {
+    let mut dom = Dom::new(DomType::Tag);
+    dom.set_tag(Tag::new("BR"));
+    assert_eq!(dom.to_html(), "<BR>");
+
+    let options = SerializeOptions {
+        mode: ParseMode::Xml,
+        ..Default::default()
+    };
+    let mut buf: Vec<u8> = Vec::new();
+    parsercher::serialize_with(&dom, &mut buf, &options).unwrap();
+    assert_eq!(String::from_utf8(buf).unwrap(), "<BR></BR>");
+}
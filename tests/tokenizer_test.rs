@@ -0,0 +1,136 @@
+extern crate parsercher;
+
+use parsercher::Event;
+use parsercher::ParseError;
+use parsercher::ParseOptions;
+use parsercher::Token;
+use parsercher::Tokenizer;
+
+fn tokens(doc: &str) -> Vec<Token<'_>> {
+    Tokenizer::new(doc).map(|token| token.unwrap()).collect()
+}
+
+#[test]
+fn tokenize() {
+    let html = r#"<!DOCTYPE html>
+<DIV Class = "a">
+  <!-- comment -->
+  <p>Tom &amp; Jerry<br/></p>
+  <script>if (a < b) {}</script>
+</DIV>"#;
+    let tokens = tokens(html);
+
+    let raws: Vec<&str> = tokens.iter().map(|token| token.get_raw()).collect();
+    assert_eq!(
+        raws,
+        vec![
+            "<!DOCTYPE html>",
+            r#"<DIV Class = "a">"#,
+            "<!-- comment -->",
+            "<p>",
+            "Tom &amp; Jerry",
+            "<br/>",
+            "</p>",
+            "<script>",
+            "if (a < b) {}",
+            "</script>",
+            "</DIV>",
+        ]
+    );
+
+    // the raw source is the span of the source document
+    for token in tokens.iter() {
+        let span = token.get_span();
+        assert_eq!(
+            &html[span.get_start().get_offset()..span.get_end().get_offset()],
+            token.get_raw()
+        );
+    }
+
+    match tokens[1].get_event() {
+        Event::StartTag(tag) => {
+            assert_eq!(tag.get_name(), "div");
+            assert_eq!(tag.get_attr("class").unwrap(), "a");
+        }
+        event => panic!("unexpected {:?}", event),
+    }
+    assert!(matches!(tokens[0].get_event(), Event::Doctype(_)));
+    assert!(matches!(tokens[2].get_event(), Event::Comment(_)));
+    assert!(matches!(tokens[5].get_event(), Event::EmptyTag(_)));
+    assert!(matches!(tokens[10].get_event(), Event::EndTag(_)));
+    match tokens[4].clone().into_event() {
+        Event::Text(text) => assert_eq!(text.get_text(), "Tom & Jerry"),
+        event => panic!("unexpected {:?}", event),
+    }
+
+    let start = tokens[3].get_span().get_start();
+    assert_eq!((start.get_line(), start.get_column()), (4, 3));
+}
+
+#[test]
+fn tokenize_plaintext() {
+    let tokens = tokens("<p>a</p><plaintext><b>");
+    let raws: Vec<&str> = tokens.iter().map(|token| token.get_raw()).collect();
+    assert_eq!(raws, vec!["<p>", "a", "</p>", "<plaintext>", "<b>"]);
+    assert!(matches!(tokens[4].get_event(), Event::Text(_)));
+}
+
+#[test]
+fn tokenize_errors() {
+    let mut tokenizer = Tokenizer::new("<p>a</p><div class=\"b>c</div>");
+    assert_eq!(tokenizer.next_token().unwrap().unwrap().get_raw(), "<p>");
+    assert_eq!(tokenizer.next_token().unwrap().unwrap().get_raw(), "a");
+    assert_eq!(tokenizer.next_token().unwrap().unwrap().get_raw(), "</p>");
+    assert!(matches!(
+        tokenizer.next_token(),
+        Err(ParseError::UnterminatedTag { .. })
+    ));
+    assert!(tokenizer.next_token().unwrap().is_none());
+
+    let options = ParseOptions {
+        recover: true,
+        ..Default::default()
+    };
+    let mut tokenizer = Tokenizer::with_options("<p>a</p><div class=\"b>c</div>", options);
+    let raws: Vec<&str> = tokenizer
+        .by_ref()
+        .map(|token| token.unwrap().get_raw())
+        .collect();
+    assert_eq!(
+        raws,
+        vec!["<p>", "a", "</p>", "<div class=\"b>", "c", "</div>"]
+    );
+    assert_eq!(tokenizer.get_errors().len(), 2);
+}
+
+#[test]
+fn tokenize_text_outside_tags() {
+    let html = "head<div>x</div>tail &amp; more";
+    let html_tokens = tokens(html);
+    let raws: Vec<&str> = html_tokens.iter().map(|token| token.get_raw()).collect();
+    assert_eq!(
+        raws,
+        vec!["head", "<div>", "x", "</div>", "tail &amp; more"]
+    );
+    match html_tokens[4].get_event() {
+        Event::Text(text) => assert_eq!(text.get_text(), "tail & more"),
+        event => panic!("unexpected {:?}", event),
+    }
+
+    let raws: Vec<&str> = tokens("<div>x</div>y")
+        .iter()
+        .map(|token| token.get_raw())
+        .collect();
+    assert_eq!(raws, vec!["<div>", "x", "</div>", "y"]);
+
+    let raws: Vec<&str> = tokens("\t\u{3000} head<a/>")
+        .iter()
+        .map(|token| token.get_raw())
+        .collect();
+    assert_eq!(raws, vec!["head", "<a/>"]);
+
+    let text_tokens = tokens("abc");
+    assert_eq!(text_tokens.len(), 1);
+    assert_eq!(text_tokens[0].get_raw(), "abc");
+    assert!(matches!(text_tokens[0].get_event(), Event::Text(_)));
+}
@@ -0,0 +1,57 @@
+extern crate parsercher;
+
+use parsercher::dom::DomTree;
+
+#[test]
+fn navigation() {
+    let html = r#"
+<body>
+  <h1>title</h1>
+  <ul id="list">
+    <li>1</li>
+    <li><span id="target">2</span></li>
+    <li>3</li>
+  </ul>
+</body>
+"#;
+    let dom = parsercher::parse(html).unwrap();
+    let tree = dom.tree();
+
+    let span = dom.select("#target").unwrap()[0];
+    let span = tree.find(span).unwrap();
+
+    let ancestors: Vec<&str> = tree
+        .ancestors(span)
+        .map(|id| tree.get(id).get_tag().unwrap().get_name())
+        .collect();
+    assert_eq!(ancestors, vec!["li", "ul", "body", "root"]);
+
+    let li = tree.parent(span).unwrap();
+    assert_eq!(tree.index_in_parent(li), Some(1));
+    assert_eq!(
+        tree.get(tree.next_sibling(li).unwrap()).to_html(),
+        "<li>3</li>"
+    );
+    assert_eq!(
+        tree.get(tree.prev_sibling(li).unwrap()).to_html(),
+        "<li>1</li>"
+    );
+    assert_eq!(tree.following_siblings(li).count(), 1);
+    assert_eq!(tree.preceding_siblings(li).count(), 1);
+
+    let ul = tree.parent(li).unwrap();
+    assert_eq!(
+        tree.get(ul).get_tag().unwrap().get_attr("id"),
+        Some("list".to_string())
+    );
+    let h1 = tree.prev_sibling(ul).unwrap();
+    assert_eq!(tree.get(h1).to_html(), "<h1>title</h1>");
+
+    assert_eq!(tree.parent(tree.root()), None);
+    assert_eq!(tree.descendants(tree.root()).count(), tree.len() - 1);
+
+    // a copy is not in the tree
+    let copy = tree.get(h1).clone();
+    assert_eq!(tree.find(&copy), None);
+    assert_eq!(DomTree::new(&copy).len(), 2);
+}
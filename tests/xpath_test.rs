@@ -0,0 +1,160 @@
+extern crate parsercher;
+
+use parsercher::XPath;
+use parsercher::XPathNode;
+use parsercher::XPathValue;
+
+const HTML: &str = r#"
+<!DOCTYPE html>
+<html>
+  <head>
+    <meta charset="UTF-8">
+    <title>sample html</title>
+  </head>
+  <body>
+    <div id="content">
+      <ol>
+        <li class="item first">first</li>
+        <li class="item" data-price="20">second</li>
+        <li class="item" data-price="30">  third   item </li>
+      </ol>
+      <!-- comment -->
+      <p>Hello, <b>world</b>!</p>
+    </div>
+    <div id="footer"><a href="https://example.com">link</a></div>
+  </body>
+</html>
+"#;
+
+fn strings(value: &XPathValue) -> Vec<String> {
+    match value {
+        XPathValue::Nodes(nodes) => nodes.iter().map(|node| node.to_string()).collect(),
+        _ => panic!("not a node-set: {:?}", value),
+    }
+}
+
+fn eval(expr: &str) -> String {
+    let dom = parsercher::parse(HTML).unwrap();
+    dom.xpath(expr).unwrap().to_string()
+}
+
+#[test]
+fn paths() {
+    let dom = parsercher::parse(HTML).unwrap();
+
+    let value = dom.xpath("//div[@id='content']/ol/li[2]/text()").unwrap();
+    assert_eq!(strings(&value), vec!["second"]);
+
+    let value = dom.xpath("//li[last()] | //li[1]").unwrap();
+    assert_eq!(strings(&value), vec!["first", "third   item "]);
+
+    let value = dom.xpath("//li[position() > 1]/@data-price").unwrap();
+    assert_eq!(strings(&value), vec!["20", "30"]);
+
+    let value = dom.xpath("/html/body/div[2]/a/@href").unwrap();
+    match &value {
+        XPathValue::Nodes(nodes) => match &nodes[0] {
+            XPathNode::Attr {
+                element,
+                name,
+                value,
+            } => {
+                assert_eq!(element.get_tag().unwrap().get_name(), "a");
+                assert_eq!(*name, "href");
+                assert_eq!(*value, "https://example.com");
+            }
+            _ => panic!(),
+        },
+        _ => panic!(),
+    }
+
+    // the nodes reference the original tree
+    let value = dom.xpath("//p").unwrap();
+    match &value {
+        XPathValue::Nodes(nodes) => match nodes[0] {
+            XPathNode::Dom(p) => assert_eq!(p.to_html(), "<p>Hello, <b>world</b>!</p>"),
+            _ => panic!(),
+        },
+        _ => panic!(),
+    }
+    assert_eq!(value.to_string(), "Hello, world!");
+}
+
+#[test]
+fn axes() {
+    assert_eq!(eval("name(//b/parent::*)"), "p");
+    assert_eq!(eval("count(//b/ancestor::*)"), "4");
+    assert_eq!(eval("count(//b/ancestor-or-self::div)"), "1");
+    assert_eq!(eval("//li[1]/following-sibling::li[1]"), "second");
+    assert_eq!(eval("//li[3]/preceding-sibling::li[1]"), "second");
+    assert_eq!(eval("name(//ol/following::*[1])"), "p");
+    assert_eq!(eval("count(//p/preceding::li)"), "3");
+    assert_eq!(eval("count(//div[@id='content']/descendant::li)"), "3");
+    assert_eq!(eval("count(//li[1]/attribute::*)"), "1");
+    assert_eq!(eval("//li[2]/@data-price/.."), "second");
+    assert_eq!(eval("count(//li/self::li[@data-price])"), "2");
+    assert_eq!(eval("//div[1]/comment()"), " comment ");
+    assert_eq!(
+        eval("count(//node()[not(self::*)][normalize-space()])"),
+        "9"
+    );
+}
+
+#[test]
+fn functions() {
+    assert_eq!(eval("count(//li[contains(@class, 'first')])"), "1");
+    assert_eq!(eval("//a[starts-with(@href, 'https')]"), "link");
+    assert_eq!(eval("normalize-space(//li[3])"), "third item");
+    assert_eq!(eval("string(count(//li))"), "3");
+    assert_eq!(eval("sum(//li/@data-price) div 2"), "25");
+    assert_eq!(eval("concat(//title, ': ', //b)"), "sample html: world");
+    assert_eq!(eval("substring('12345', 1.5, 2.6)"), "234");
+    assert_eq!(eval("substring-before('1999/04/01', '/')"), "1999");
+    assert_eq!(eval("substring-after('1999/04/01', '/')"), "04/01");
+    assert_eq!(eval("translate('bar', 'abc', 'ABC')"), "BAr");
+    assert_eq!(eval("string-length(//b)"), "5");
+    assert_eq!(eval("round(2.5) + floor(-1.5) + ceiling(1.2)"), "3");
+    assert_eq!(eval("boolean(//table)"), "false");
+    assert_eq!(eval("//li[2]/@data-price > //li[1]/@data-price"), "false");
+    assert_eq!(eval("//li/@data-price = 30"), "true");
+    assert_eq!(eval("//li/@data-price != 30"), "true");
+    assert_eq!(eval("7 mod 3 = 1 and -2 < 1 or false()"), "true");
+    assert_eq!(eval("number('abc')"), "NaN");
+}
+
+#[test]
+fn errors() {
+    let dom = parsercher::parse(HTML).unwrap();
+
+    let e = dom.xpath("//li[@class=").unwrap_err();
+    assert_eq!(e.offset(), 12);
+
+    let e = dom.xpath("count('abc')").unwrap_err();
+    assert_eq!(e.offset(), 0);
+    assert_eq!(
+        e.to_string(),
+        "Expression must evaluate to a node-set at offset 0: `count('abc')`"
+    );
+
+    let xpath = XPath::parse("//li[contains(@class, 'item')]").unwrap();
+    assert_eq!(strings(&xpath.evaluate(&dom).unwrap()).len(), 3);
+}
+
+#[test]
+fn xml_node_types() {
+    let xml = r#"<?xml version="1.0"?><?app mode="a"?><doc><code><![CDATA[a < b]]></code><?app mode="b"?></doc>"#;
+    let dom = parsercher::parse(xml).unwrap();
+
+    assert_eq!(dom.xpath("//code/text()").unwrap().to_string(), "a < b");
+    assert_eq!(dom.xpath("string(//doc)").unwrap().to_string(), "a < b");
+    assert_eq!(
+        strings(&dom.xpath("//processing-instruction('app')").unwrap()),
+        vec![r#"mode="a""#, r#"mode="b""#]
+    );
+    assert_eq!(
+        dom.xpath("count(/processing-instruction())")
+            .unwrap()
+            .to_string(),
+        "2"
+    );
+}